@@ -0,0 +1,78 @@
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use mix_emu::{
+    assembler::assemble,
+    computer::{
+        devices::{Devices, TextOutput},
+        Computer, StopReason,
+    },
+    data_types::Word,
+    object::Program,
+};
+
+// Plain timing loops rather than a benchmarking crate, run with `cargo bench`. Each one reports
+// how many instructions (or conversions) it got through per second.
+
+const RUN_TIME: Duration = Duration::from_secs(2);
+
+fn report(name: &str, count: u64, elapsed: Duration) {
+    println!(
+        "{:<12} {:>12.0} per second",
+        name,
+        count as f64 / elapsed.as_secs_f64()
+    );
+}
+
+fn run_repeatedly(name: &str, program: &Program) {
+    let start = Instant::now();
+    let mut instructions = 0;
+    while start.elapsed() < RUN_TIME {
+        let mut computer = Computer::new();
+        computer.devices = Devices::new();
+        computer.devices.line_printer = Some(TextOutput::line_printer());
+        computer.load_program(program).unwrap();
+        assert_eq!(computer.run(None), Ok(StopReason::HALTED));
+        instructions += computer.instruction_count;
+    }
+    report(name, instructions, start.elapsed());
+}
+
+// loads, stores and arithmetic with partial fields, the bulk of what student programs do
+const FIELDS: &str = " ORIG 3000
+START ENT1 2000
+LOOP LDA X(1:3)
+ ADD X(4:5)
+ STA Y(2:4)
+ LDX Y
+ SUB X
+ STX Y(0:2)
+ CMPA Y(3:5)
+ DEC1 1
+ J1P LOOP
+ HLT
+X CON 123456789
+Y CON 0
+ END START
+";
+
+fn main() {
+    run_repeatedly(
+        "primes",
+        &assemble(include_str!("../tests/programs/primes.mixal")).unwrap(),
+    );
+    run_repeatedly("fields", &assemble(FIELDS).unwrap());
+
+    let start = Instant::now();
+    let mut conversions = 0;
+    while start.elapsed() < RUN_TIME {
+        for value in -100_000..100_000 {
+            let word = Word::from_i32(black_box(value * 1000)).unwrap();
+            black_box(word.to_i32());
+        }
+        conversions += 200_000;
+    }
+    report("conversions", conversions, start.elapsed());
+}
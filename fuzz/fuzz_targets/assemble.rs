@@ -0,0 +1,18 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mix_emu::{
+    assembler::{self, AssemblerOptions},
+    data_types::ByteSize,
+};
+
+// any text should assemble or give an error, on either byte size
+fuzz_target!(|source: &str| {
+    for byte_size in [ByteSize::BINARY, ByteSize::DECIMAL] {
+        let options = AssemblerOptions {
+            byte_size,
+            ..Default::default()
+        };
+        let _ = assembler::assemble_with(source, &options);
+    }
+});
@@ -0,0 +1,9 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mix_emu::fuzzing::run_arbitrary;
+
+// see run_arbitrary for how the bytes become a machine and what's checked afterwards
+fuzz_target!(|data: &[u8]| {
+    let _ = run_arbitrary(data, 10_000);
+});
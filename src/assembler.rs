@@ -1,445 +1,1935 @@
-use std::{iter::Peekable, str::Chars};
+use std::{
+    cell::Cell,
+    collections::{HashMap, HashSet},
+    fmt, fs, io,
+    iter::Peekable,
+    path::{Path, PathBuf},
+};
 
-use crate::data_types::{Sign, Word};
+use crate::{
+    computer::memory,
+    data_types::{Byte, ByteSize, FieldSpecification, Sign, Word},
+    object::{Linkage, Program},
+    opcodes::{Op, OperandKind},
+    source_map::SourceMap,
+};
 
 #[derive(Debug)]
 pub enum AssemblerError {
     BadLine(String),
     BadOpCode(Token),
+    UndefinedSymbol(String),
+    DuplicateSymbol(String),
+    InLine(usize, Box<AssemblerError>),
+    // the macro and the line within it an error came from
+    InMacro(String, usize, Box<AssemblerError>),
+    // the file an error came from, when it isn't the one being assembled
+    InFile(String, Box<AssemblerError>),
+    Io(String, io::Error),
 }
 
+impl fmt::Display for AssemblerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::BadLine(x) => write!(f, "{}", x),
+            Self::BadOpCode(x) => write!(f, "unknown op code {}", describe(Some(x))),
+            Self::UndefinedSymbol(x) => write!(f, "undefined symbol {}", x),
+            Self::DuplicateSymbol(x) => write!(f, "symbol {} is already defined", x),
+            Self::InLine(line, x) => write!(f, "line {}: {}", line, x),
+            Self::InMacro(name, line, x) => write!(f, "macro {} line {}: {}", name, line, x),
+            Self::InFile(name, x) => write!(f, "{}: {}", name, x),
+            Self::Io(name, x) => write!(f, "{}: {}", name, x),
+        }
+    }
+}
+
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Debug, PartialEq, Eq)]
-enum Op {
+enum PseudoOp {
     EQU,
     ORIG,
     CON,
     ALF,
     END,
-    NOP,
-    ADD,
-    SUB,
-    MUL,
-    DIV,
-    NUM,
-    CHAR,
-    HALT,
-    SLA,
-    SRA,
-    SLAX,
-    SRAX,
-    SLC,
-    SRC,
-    MOVE,
-    LDA,
-    LD1,
-    LD2,
-    LD3,
-    LD4,
-    LD5,
-    LD6,
-    LDX,
-    LDAN,
-    LD1N,
-    LD2N,
-    LD3N,
-    LD4N,
-    LD5N,
-    LD6N,
-    LDXN,
-    STA,
-    ST1,
-    ST2,
-    ST3,
-    ST4,
-    ST5,
-    ST6,
-    STX,
-    STJ,
-    STZ,
-    JBUS,
-    IOC,
-    IN,
-    OUT,
-    JRED,
-    JMP,
-    JSJ,
-    JOV,
-    JNOV,
-    JL,
-    JE,
-    JG,
-    JGE,
-    JNE,
-    JLE,
-    JAN,
-    JAZ,
-    JAP,
-    JANN,
-    JANZ,
-    JANP,
-    J1N,
-    J1Z,
-    J1P,
-    J1NN,
-    J1NZ,
-    J1NP,
-    J2N,
-    J2Z,
-    J2P,
-    J2NN,
-    J2NZ,
-    J2NP,
-    J3N,
-    J3Z,
-    J3P,
-    J3NN,
-    J3NZ,
-    J3NP,
-    J4N,
-    J4Z,
-    J4P,
-    J4NN,
-    J4NZ,
-    J4NP,
-    J5N,
-    J5Z,
-    J5P,
-    J5NN,
-    J5NZ,
-    J5NP,
-    J6N,
-    J6Z,
-    J6P,
-    J6NN,
-    J6NZ,
-    J6NP,
-    JXN,
-    JXZ,
-    JXP,
-    JXNN,
-    JXNZ,
-    JXNP,
-    INCA,
-    DECA,
-    ENTA,
-    ENNA,
-    INC1,
-    DEC1,
-    ENT1,
-    ENN1,
-    INC2,
-    DEC2,
-    ENT2,
-    ENN2,
-    INC3,
-    DEC3,
-    ENT3,
-    ENN3,
-    INC4,
-    DEC4,
-    ENT4,
-    ENN4,
-    INC5,
-    DEC5,
-    ENT5,
-    ENN5,
-    INC6,
-    DEC6,
-    ENT6,
-    ENN6,
-    INCX,
-    DECX,
-    ENTX,
-    ENNX,
-    CMPA,
-    CMP1,
-    CMP2,
-    CMP3,
-    CMP4,
-    CMP5,
-    CMP6,
-    CMPX,
-}
-
-impl Op {
-    fn to_code_value(&self) -> i32 {
-        todo!("fill all these in...")
-    }
-
-    fn is_pseudo_instruction(&self) -> bool {
-        self == &Self::EQU
-            || self == &Self::ORIG
-            || self == &Self::CON
-            || self == &Self::ALF
-            || self == &Self::END
-    }
+    EXTERN,
+    EXPORT,
+}
+
+// the ops that aren't MIX instructions but tell the assembler what to generate
+pub const PSEUDO_OPS: [&str; 7] = ["EQU", "ORIG", "CON", "ALF", "END", "EXTERN", "EXPORT"];
 
+#[derive(Debug, PartialEq, Eq)]
+enum Operation {
+    Pseudo(PseudoOp),
+    Machine(Op),
+}
+
+impl Operation {
     fn from_str(s: &str) -> Result<Self, AssemblerError> {
         match s {
-            "EQU" => Ok(Op::EQU),
-            "ORIG" => Ok(Op::ORIG),
-            "CON" => Ok(Op::CON),
-            "ALF" => Ok(Op::ALF),
-            "END" => Ok(Op::END),
-            "NOP" => Ok(Op::NOP),
-            "ADD" => Ok(Op::ADD),
-            "SUB" => Ok(Op::SUB),
-            "MUL" => Ok(Op::MUL),
-            "DIV" => Ok(Op::DIV),
-            "NUM" => Ok(Op::NUM),
-            "CHAR" => Ok(Op::CHAR),
-            "HALT" => Ok(Op::HALT),
-            "SLA" => Ok(Op::SLA),
-            "SRA" => Ok(Op::SRA),
-            "SLAX" => Ok(Op::SLAX),
-            "SRAX" => Ok(Op::SRAX),
-            "SLC" => Ok(Op::SLC),
-            "SRC" => Ok(Op::SRC),
-            "MOVE" => Ok(Op::MOVE),
-            "LDA" => Ok(Op::LDA),
-            "LD1" => Ok(Op::LD1),
-            "LD2" => Ok(Op::LD2),
-            "LD3" => Ok(Op::LD3),
-            "LD4" => Ok(Op::LD4),
-            "LD5" => Ok(Op::LD5),
-            "LD6" => Ok(Op::LD6),
-            "LDX" => Ok(Op::LDX),
-            "LDAN" => Ok(Op::LDAN),
-            "LD1N" => Ok(Op::LD1N),
-            "LD2N" => Ok(Op::LD2N),
-            "LD3N" => Ok(Op::LD3N),
-            "LD4N" => Ok(Op::LD4N),
-            "LD5N" => Ok(Op::LD5N),
-            "LD6N" => Ok(Op::LD6N),
-            "LDXN" => Ok(Op::LDXN),
-            "STA" => Ok(Op::STA),
-            "ST1" => Ok(Op::ST1),
-            "ST2" => Ok(Op::ST2),
-            "ST3" => Ok(Op::ST3),
-            "ST4" => Ok(Op::ST4),
-            "ST5" => Ok(Op::ST5),
-            "ST6" => Ok(Op::ST6),
-            "STX" => Ok(Op::STX),
-            "STJ" => Ok(Op::STJ),
-            "STZ" => Ok(Op::STZ),
-            "JBUS" => Ok(Op::JBUS),
-            "IOC" => Ok(Op::IOC),
-            "IN" => Ok(Op::IN),
-            "OUT" => Ok(Op::OUT),
-            "JRED" => Ok(Op::JRED),
-            "JMP" => Ok(Op::JMP),
-            "JSJ" => Ok(Op::JSJ),
-            "JOV" => Ok(Op::JOV),
-            "JNOV" => Ok(Op::JNOV),
-            "JL" => Ok(Op::JL),
-            "JE" => Ok(Op::JE),
-            "JG" => Ok(Op::JG),
-            "JGE" => Ok(Op::JGE),
-            "JNE" => Ok(Op::JNE),
-            "JLE" => Ok(Op::JLE),
-            "JAN" => Ok(Op::JAN),
-            "JAZ" => Ok(Op::JAZ),
-            "JAP" => Ok(Op::JAP),
-            "JANN" => Ok(Op::JANN),
-            "JANZ" => Ok(Op::JANZ),
-            "JANP" => Ok(Op::JANP),
-            "J1N" => Ok(Op::J1N),
-            "J1Z" => Ok(Op::J1Z),
-            "J1P" => Ok(Op::J1P),
-            "J1NN" => Ok(Op::J1NN),
-            "J1NZ" => Ok(Op::J1NZ),
-            "J1NP" => Ok(Op::J1NP),
-            "J2N" => Ok(Op::J2N),
-            "J2Z" => Ok(Op::J2Z),
-            "J2P" => Ok(Op::J2P),
-            "J2NN" => Ok(Op::J2NN),
-            "J2NZ" => Ok(Op::J2NZ),
-            "J2NP" => Ok(Op::J2NP),
-            "J3N" => Ok(Op::J3N),
-            "J3Z" => Ok(Op::J3Z),
-            "J3P" => Ok(Op::J3P),
-            "J3NN" => Ok(Op::J3NN),
-            "J3NZ" => Ok(Op::J3NZ),
-            "J3NP" => Ok(Op::J3NP),
-            "J4N" => Ok(Op::J4N),
-            "J4Z" => Ok(Op::J4Z),
-            "J4P" => Ok(Op::J4P),
-            "J4NN" => Ok(Op::J4NN),
-            "J4NZ" => Ok(Op::J4NZ),
-            "J4NP" => Ok(Op::J4NP),
-            "J5N" => Ok(Op::J5N),
-            "J5Z" => Ok(Op::J5Z),
-            "J5P" => Ok(Op::J5P),
-            "J5NN" => Ok(Op::J5NN),
-            "J5NZ" => Ok(Op::J5NZ),
-            "J5NP" => Ok(Op::J5NP),
-            "J6N" => Ok(Op::J6N),
-            "J6Z" => Ok(Op::J6Z),
-            "J6P" => Ok(Op::J6P),
-            "J6NN" => Ok(Op::J6NN),
-            "J6NZ" => Ok(Op::J6NZ),
-            "J6NP" => Ok(Op::J6NP),
-            "JXN" => Ok(Op::JXN),
-            "JXZ" => Ok(Op::JXZ),
-            "JXP" => Ok(Op::JXP),
-            "JXNN" => Ok(Op::JXNN),
-            "JXNZ" => Ok(Op::JXNZ),
-            "JXNP" => Ok(Op::JXNP),
-            "INCA" => Ok(Op::INCA),
-            "DECA" => Ok(Op::DECA),
-            "ENTA" => Ok(Op::ENTA),
-            "ENNA" => Ok(Op::ENNA),
-            "INC1" => Ok(Op::INC1),
-            "DEC1" => Ok(Op::DEC1),
-            "ENT1" => Ok(Op::ENT1),
-            "ENN1" => Ok(Op::ENN1),
-            "INC2" => Ok(Op::INC2),
-            "DEC2" => Ok(Op::DEC2),
-            "ENT2" => Ok(Op::ENT2),
-            "ENN2" => Ok(Op::ENN2),
-            "INC3" => Ok(Op::INC3),
-            "DEC3" => Ok(Op::DEC3),
-            "ENT3" => Ok(Op::ENT3),
-            "ENN3" => Ok(Op::ENN3),
-            "INC4" => Ok(Op::INC4),
-            "DEC4" => Ok(Op::DEC4),
-            "ENT4" => Ok(Op::ENT4),
-            "ENN4" => Ok(Op::ENN4),
-            "INC5" => Ok(Op::INC5),
-            "DEC5" => Ok(Op::DEC5),
-            "ENT5" => Ok(Op::ENT5),
-            "ENN5" => Ok(Op::ENN5),
-            "INC6" => Ok(Op::INC6),
-            "DEC6" => Ok(Op::DEC6),
-            "ENT6" => Ok(Op::ENT6),
-            "ENN6" => Ok(Op::ENN6),
-            "INCX" => Ok(Op::INCX),
-            "DECX" => Ok(Op::DECX),
-            "ENTX" => Ok(Op::ENTX),
-            "ENNX" => Ok(Op::ENNX),
-            "CMPA" => Ok(Op::CMPA),
-            "CMP1" => Ok(Op::CMP1),
-            "CMP2" => Ok(Op::CMP2),
-            "CMP3" => Ok(Op::CMP3),
-            "CMP4" => Ok(Op::CMP4),
-            "CMP5" => Ok(Op::CMP5),
-            "CMP6" => Ok(Op::CMP6),
-            "CMPX" => Ok(Op::CMPX),
-            s => Err(AssemblerError::BadOpCode(Token::Symbol(s.to_string()))),
+            "EQU" => Ok(Self::Pseudo(PseudoOp::EQU)),
+            "ORIG" => Ok(Self::Pseudo(PseudoOp::ORIG)),
+            "CON" => Ok(Self::Pseudo(PseudoOp::CON)),
+            "ALF" => Ok(Self::Pseudo(PseudoOp::ALF)),
+            "END" => Ok(Self::Pseudo(PseudoOp::END)),
+            "EXTERN" => Ok(Self::Pseudo(PseudoOp::EXTERN)),
+            "EXPORT" => Ok(Self::Pseudo(PseudoOp::EXPORT)),
+            s => match Op::from_mnemonic(s) {
+                Some(op) => Ok(Self::Machine(op)),
+                None => Err(AssemblerError::BadOpCode(Token::Symbol(s.to_string()))),
+            },
         }
     }
 }
 
+struct Line {
+    // where the line is in its file, which for a line a macro generated is the line using it
+    file: usize,
+    number: usize,
+    loc: Option<String>,
+    op: Operation,
+    address: Vec<Token>,
+    text: String,
+    // the macros and lines within them this line came from, outermost first, empty when it was
+    // written in the source
+    expansion: Vec<(String, usize)>,
+}
+
+impl Line {
+    // errors on a line a macro generated also say which lines of which macros it came from
+    fn error<'a>(&'a self, sources: &'a Sources) -> impl Fn(AssemblerError) -> AssemblerError + 'a {
+        move |e| {
+            let e = self.expansion.iter().rev().fold(e, |e, (name, line)| {
+                AssemblerError::InMacro(name.clone(), *line, Box::new(e))
+            });
+            sources.in_file(self.file, AssemblerError::InLine(self.number, Box::new(e)))
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct AssemblerOptions {
+    // the machine the program is for, which decides how numbers are split into bytes
+    pub byte_size: ByteSize,
+    // what the source map calls the source, <input> if not given, and where INCLUDE looks first
+    pub file_name: Option<String>,
+    // where INCLUDE looks for files that aren't next to the file including them
+    pub include_paths: Vec<PathBuf>,
+    // assemble a module for the linker rather than a program: it's counted from 0 wherever it
+    // will go, and can use EXTERN and EXPORT
+    pub module: bool,
+    // symbols defined before the first line, as if by EQU, for IF and IFDEF to choose between
+    // versions of the program
+    pub defines: Vec<(String, i64)>,
+}
+
 // using the terminal input rules -> empty LOC indicated by leading space
 // changing ALF so characters are enclosed in "s instead of working by character count
 // not supporting remarks either, use comments instead
-pub fn read_source_string_as_instructions(source_content: &str) -> Vec<Word> {
-    let lines = split_tokens_by_line(tokenise(source_content));
-    return parse_lines(lines);
+pub fn assemble(source_content: &str) -> Result<Program, AssemblerError> {
+    assemble_with(source_content, &AssemblerOptions::default())
 }
 
-fn parse_lines(lines: Vec<Vec<Token>>) -> Vec<Word> {
-    let mut result = Vec::new();
-    for line in lines {
-        if let Some(x) = parse_line(line) {
-            result.push(x);
+pub fn assemble_with(
+    source_content: &str,
+    options: &AssemblerOptions,
+) -> Result<Program, AssemblerError> {
+    let sources = Sources::text(source_content, options);
+    Ok(assemble_sources(sources, options)?.0)
+}
+
+// The program and a listing of the source with the address and word each line assembled to.
+// Lines a macro expanded to come after the line that used it, with a + for each level of nesting,
+// and files that were included after the INCLUDE.
+pub fn assemble_with_listing(
+    source_content: &str,
+    options: &AssemblerOptions,
+) -> Result<(Program, String), AssemblerError> {
+    let sources = Sources::text(source_content, options);
+    let (program, assembled) = assemble_sources(sources, options)?;
+    let listing = assembled.listing(&program);
+    Ok((program, listing))
+}
+
+// Files assembled as one program with one symbol table, as if they were one after the other in a
+// single file, so only the last should have an END. Errors say which file they're in.
+pub fn assemble_files(
+    paths: &[impl AsRef<Path>],
+    options: &AssemblerOptions,
+) -> Result<Program, AssemblerError> {
+    Ok(assemble_sources(Sources::files(paths)?, options)?.0)
+}
+
+pub fn assemble_files_with_listing(
+    paths: &[impl AsRef<Path>],
+    options: &AssemblerOptions,
+) -> Result<(Program, String), AssemblerError> {
+    let (program, assembled) = assemble_sources(Sources::files(paths)?, options)?;
+    let listing = assembled.listing(&program);
+    Ok((program, listing))
+}
+
+// NAME=value or just NAME, which is 1, as given on the command line for AssemblerOptions::defines
+pub fn parse_define(definition: &str) -> Option<(String, i64)> {
+    let (name, value) = match definition.split_once('=') {
+        Some((name, value)) => (name, value.parse().ok()?),
+        None => (definition, 1),
+    };
+    match tokenise(name).as_slice() {
+        [Token::Symbol(x)] if !is_local_symbol(x) => Some((name.to_string(), value)),
+        _ => None,
+    }
+}
+
+// A symbol where it's written in the source, for editors. The source is read a line at a time as
+// it's written, without expanding macros, reading INCLUDEs or deciding IFs, so it works on source
+// that doesn't assemble yet.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SymbolUse {
+    pub name: String,
+    // from 1, like the line numbers in errors
+    pub line: usize,
+    // in characters from the start of the line
+    pub column: usize,
+    pub kind: SymbolKind,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SymbolKind {
+    // in the LOC field, including the name of a macro and dH
+    DEFINITION,
+    // in the address field
+    REFERENCE,
+    // in the op field, a mnemonic, pseudo-op, directive or macro
+    OP,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SourceField {
+    LOC,
+    OP,
+    ADDRESS,
+    // after the address, or anywhere on a comment line
+    REMARKS,
+}
+
+pub fn symbol_uses(source_content: &str) -> Vec<SymbolUse> {
+    let mut uses = Vec::new();
+    for (idx, text) in source_content.lines().enumerate() {
+        let tokens = tokenise_with_columns(text);
+        let plain: Vec<Token> = tokens.iter().map(|(x, _)| x.clone()).collect();
+        let statement = match split_line(&plain) {
+            Ok(Some(x)) => x,
+            _ => continue,
+        };
+        let mut add = |(token, column): &(Token, usize), kind| {
+            if let Token::Symbol(name) = token {
+                uses.push(SymbolUse {
+                    name: name.clone(),
+                    line: idx + 1,
+                    column: *column,
+                    kind,
+                });
+            }
+        };
+        // split_line has checked the LOC and op are where it expects them
+        let op = match statement.loc {
+            Some(_) => 2,
+            None => 1,
+        };
+        if statement.loc.is_some() {
+            add(&tokens[0], SymbolKind::DEFINITION);
+        }
+        add(&tokens[op], SymbolKind::OP);
+        // the parameters of a macro, file names and text aren't symbols
+        if matches!(statement.op.as_str(), "MACRO" | "INCLUDE" | "ALF") {
+            continue;
+        }
+        let address = tokens[op + 1..]
+            .iter()
+            .skip_while(|(x, _)| x == &Token::Space)
+            .take(statement.address.len());
+        for token in address {
+            add(token, SymbolKind::REFERENCE);
         }
     }
-    return result;
+    uses
 }
 
-fn parse_line(line: Vec<Token>) -> Option<Word> {
-    if line.is_empty() || line.first() == Some(&Token::Asterisk) {
-        return None;
+// the definition a symbol refers to, with dB and dF the nearest dH before or after its line
+pub fn definition_of<'a>(uses: &'a [SymbolUse], symbol: &SymbolUse) -> Option<&'a SymbolUse> {
+    let mut definitions = uses.iter().filter(|x| x.kind == SymbolKind::DEFINITION);
+    if symbol.kind == SymbolKind::DEFINITION {
+        return definitions.find(|x| *x == symbol);
     }
-    let mut iter = line.iter();
-    let loc = parse_loc(&mut iter);
-    let op = parse_op(&mut iter);
-    let addr = parse_address(&mut iter, &op);
-    if op.is_pseudo_instruction() {
-        handle_pseudo_instruction(loc, op, addr);
-        return None;
+    let here = |x: &&SymbolUse, digit| local_symbol_digit(&x.name, 'H') == Some(digit);
+    if let Some(digit) = local_symbol_digit(&symbol.name, 'B') {
+        return definitions.rfind(|x| here(x, digit) && x.line < symbol.line);
+    }
+    if let Some(digit) = local_symbol_digit(&symbol.name, 'F') {
+        return definitions.find(|x| here(x, digit) && x.line > symbol.line);
+    }
+    definitions.find(|x| x.name == symbol.name)
+}
+
+// which field of the line the column is in, for editors completing what's being typed there
+pub fn field_at(text: &str, column: usize) -> SourceField {
+    let before: String = text.chars().take(column).collect();
+    if before.starts_with('*') {
+        return SourceField::REMARKS;
+    }
+    // a line without a LOC starts with the space before its op
+    match tokenise(&before)
+        .iter()
+        .filter(|x| x == &&Token::Space)
+        .count()
+    {
+        0 => SourceField::LOC,
+        1 => SourceField::OP,
+        2 => SourceField::ADDRESS,
+        _ => SourceField::REMARKS,
     }
-    return Some(build_word(op, addr));
 }
 
-fn handle_pseudo_instruction(loc: Option<String>, op: Op, addr: Address) {
-    todo!()
+// A file of MIXAL, given to the assembler or read by INCLUDE.
+struct SourceFile {
+    name: String,
+    // where it was read from, so the files it includes can be looked for next to it
+    path: Option<PathBuf>,
+    text: String,
+    // the file and line of the INCLUDE that read it
+    included_from: Option<(usize, usize)>,
 }
 
-fn build_word(op: Op, addr: Address) -> Word {
-    Word::from_instruction_parts(
-        Sign::from_i32(addr.a),
-        addr.a.abs(),
-        addr.i,
-        8 * addr.f_left + addr.f_right,
-        op.to_code_value(),
-    )
-    .unwrap()
+// every file that went into a program, in the order they were read, which is also their order in
+// the source map
+#[derive(Default)]
+struct Sources {
+    files: Vec<SourceFile>,
+    // whether errors say which of the files given to the assembler they're in, which isn't needed
+    // when it was given just the one
+    named: bool,
+    // a file is only ever read once, so a library can be included by everything that needs it
+    read: HashSet<PathBuf>,
 }
 
-fn parse_loc(iter: &mut dyn Iterator<Item = &Token>) -> Option<String> {
-    match iter.next().expect("illegal null loc field") {
-        Token::Space => None,
-        Token::Symbol(s) => Some(s.to_string()),
-        x => panic!("Bad loc {:?}", x),
+impl Sources {
+    fn text(source_content: &str, options: &AssemblerOptions) -> Self {
+        let path = options.file_name.as_ref().map(PathBuf::from);
+        let mut sources = Self::default();
+        if let Some(x) = path.as_ref().and_then(|x| fs::canonicalize(x).ok()) {
+            sources.read.insert(x);
+        }
+        sources.files.push(SourceFile {
+            name: options
+                .file_name
+                .as_deref()
+                .unwrap_or("<input>")
+                .to_string(),
+            path,
+            text: source_content.to_string(),
+            included_from: None,
+        });
+        sources
+    }
+
+    fn files(paths: &[impl AsRef<Path>]) -> Result<Self, AssemblerError> {
+        if paths.is_empty() {
+            return Err(AssemblerError::BadLine("no source files".to_string()));
+        }
+        let mut sources = Self {
+            named: true,
+            ..Default::default()
+        };
+        for path in paths {
+            sources.read(path.as_ref(), None)?;
+        }
+        Ok(sources)
     }
+
+    // the index of the file, or None if it has already been read
+    fn read(
+        &mut self,
+        path: &Path,
+        included_from: Option<(usize, usize)>,
+    ) -> Result<Option<usize>, AssemblerError> {
+        let name = path.display().to_string();
+        let io_error = |e| AssemblerError::Io(name.clone(), e);
+        if !self.read.insert(fs::canonicalize(path).map_err(io_error)?) {
+            return Ok(None);
+        }
+        let text = fs::read_to_string(path).map_err(io_error)?;
+        self.files.push(SourceFile {
+            name,
+            path: Some(path.to_path_buf()),
+            text,
+            included_from,
+        });
+        Ok(Some(self.files.len() - 1))
+    }
+
+    fn name(&self, file: usize, e: AssemblerError) -> AssemblerError {
+        let source = &self.files[file];
+        match source.included_from.is_some() || self.named {
+            true => AssemblerError::InFile(source.name.clone(), Box::new(e)),
+            false => e,
+        }
+    }
+
+    // an error in a file, after the INCLUDE lines that led to it
+    fn in_file(&self, file: usize, e: AssemblerError) -> AssemblerError {
+        let e = self.name(file, e);
+        match self.files[file].included_from {
+            Some((parent, line)) => self.in_file(parent, AssemblerError::InLine(line, Box::new(e))),
+            None => e,
+        }
+    }
+}
+
+// what the listing needs once a program is assembled - the lines it came from once macros are
+// expanded, each with the index into program.words of the word it generated, and their files
+struct Assembled {
+    lines: Vec<(Line, Option<usize>)>,
+    sources: Sources,
 }
 
-fn parse_op(iter: &mut dyn Iterator<Item = &Token>) -> Op {
-    match iter.next().expect("illegal null op field") {
-        Token::Symbol(op) => Op::from_str(&op).expect("illegal op code"),
-        x => panic!("Bad op {:?}", x),
+fn assemble_sources(
+    sources: Sources,
+    options: &AssemblerOptions,
+) -> Result<(Program, Assembled), AssemblerError> {
+    let mut parser = Parser {
+        sources,
+        include_paths: options.include_paths.clone(),
+        byte_size: options.byte_size,
+        ..Default::default()
+    };
+    for (name, value) in options.defines.iter() {
+        parser.define(name, Value::absolute(*value))?;
     }
+    parser.parse()?;
+    let Parser { lines, sources, .. } = parser;
+    let symbols = define_symbols(&lines, &sources, options)?;
+    let mut assembly = Assembly {
+        byte_size: options.byte_size,
+        module: options.module,
+        ..Default::default()
+    };
+    assembly.source_map.files = sources.files.iter().map(|x| x.name.clone()).collect();
+    assembly.generate_words(&lines, &sources, &symbols)?;
+    // lines after END never get as far as generating anything
+    let mut line_words = std::mem::take(&mut assembly.line_words);
+    line_words.resize(lines.len(), None);
+    let lines = lines.into_iter().zip(line_words).collect();
+    let program = assembly.finish(symbols, &sources)?;
+    Ok((program, Assembled { lines, sources }))
+}
+
+impl Assembled {
+    fn listing(&self, program: &Program) -> String {
+        let mut listing = Listing {
+            program,
+            assembled: self,
+            next: 0,
+            text: format!("{:>5} {:>4}  {:<16}  {}\n", "LINE", "LOC", "WORD", "SOURCE"),
+        };
+        let files = &self.sources.files;
+        let given: Vec<usize> = (0..files.len())
+            .filter(|x| files[*x].included_from.is_none())
+            .collect();
+        for file in given.iter() {
+            if given.len() > 1 {
+                listing.heading(*file);
+            }
+            listing.file(*file);
+        }
+        listing.text
+    }
+}
+
+struct Listing<'a> {
+    program: &'a Program,
+    assembled: &'a Assembled,
+    // the first of the assembled lines not listed yet
+    next: usize,
+    text: String,
+}
+
+impl Listing<'_> {
+    fn row(&mut self, number: &str, word: Option<usize>, source: &str) {
+        let (address, word) = match word.map(|x| self.program.words[x]) {
+            Some((address, word)) => (address.to_string(), word.to_byte_string()),
+            None => (String::new(), String::new()),
+        };
+        let row = format!("{:>5} {:>4}  {:<16}  {}", number, address, word, source);
+        self.text += row.trim_end();
+        self.text += "\n";
+    }
+
+    // a comment line naming the file the rows after it are from
+    fn heading(&mut self, file: usize) {
+        let name = &self.assembled.sources.files[file].name;
+        self.row("", None, &format!("* {}", name));
+    }
+
+    fn file(&mut self, file: usize) {
+        let (lines, sources) = (&self.assembled.lines, &self.assembled.sources);
+        for (idx, source) in sources.files[file].text.lines().enumerate() {
+            let number = idx + 1;
+            let first = self.next;
+            while lines
+                .get(self.next)
+                .is_some_and(|(x, _)| x.file == file && x.number == number)
+            {
+                self.next += 1;
+            }
+            let own = lines[first..self.next]
+                .iter()
+                .rfind(|(line, _)| line.expansion.is_empty());
+            self.row(&number.to_string(), own.and_then(|x| x.1), source);
+            for (line, word) in lines[first..self.next].iter() {
+                if !line.expansion.is_empty() {
+                    let text = "+".repeat(line.expansion.len()) + &line.text;
+                    self.row("", *word, &text);
+                }
+            }
+            let included = (0..sources.files.len())
+                .find(|x| sources.files[*x].included_from == Some((file, number)));
+            if let Some(included) = included {
+                self.heading(included);
+                self.file(included);
+                self.heading(file);
+            }
+        }
+    }
+}
+
+pub fn read_source_string_as_instructions(
+    source_content: &str,
+) -> Result<Vec<Word>, AssemblerError> {
+    let program = assemble(source_content)?;
+    Ok(program.words.into_iter().map(|(_, word)| word).collect())
+}
+
+fn in_line(number: usize) -> impl Fn(AssemblerError) -> AssemblerError {
+    move |e| AssemblerError::InLine(number, Box::new(e))
+}
+
+// A line split into its fields, before we know whether the op is a MIX operation or a macro.
+struct Statement {
+    loc: Option<String>,
+    op: String,
+    address: Vec<Token>,
+    // whether anything came after the address, which only ALF allows
+    remarks: bool,
+}
+
+// Macros are defined with
+//
+//   NAME MACRO P1,P2
+//        ...
+//        ENDM
+//
+// and used like any other op, NAME A,B. Each use is replaced by the lines in between with every
+// parameter symbol replaced by its argument, or by nothing if there were fewer arguments. Symbols
+// defined in the LOC field of those lines are renamed for each use, to NAME%n, so a macro can
+// have labels of its own and be used more than once. The LOC of the line using the macro names
+// the first word it generates. Nothing inside "s is replaced, so ALF text is left alone, and #
+// pastes a parameter onto what's next to it.
+#[derive(Clone)]
+struct Macro {
+    parameters: Vec<String>,
+    // the symbols defined by the macro's own lines, which are renamed each time it's used
+    labels: Vec<String>,
+    // line numbers and text of the lines between MACRO and ENDM
+    body: Vec<(usize, String)>,
 }
 
-struct Address {
-    a: i32,
-    i: i32,
-    f_left: i32,
-    f_right: i32,
+// ops the parser deals with itself, which can't be used as macro names
+pub const DIRECTIVES: [&str; 8] = [
+    "MACRO", "ENDM", "INCLUDE", "IF", "IFDEF", "IFNDEF", "ELSE", "ENDIF",
+];
+
+// how deep macros can use other macros before we decide one must be using itself forever
+const MACRO_DEPTH: usize = 16;
+
+// first pass, before symbols - macros defined so far and the lines the source expands to
+#[derive(Default)]
+struct Parser {
+    sources: Sources,
+    include_paths: Vec<PathBuf>,
+    // the file being parsed
+    file: usize,
+    macros: HashMap<String, Macro>,
+    expansions: usize,
+    lines: Vec<Line>,
+    // LOCs of macro uses waiting for the first line they generate
+    labels: Vec<String>,
+    byte_size: ByteSize,
+    // symbols defined so far and the values of the ones IF can use, which are those that were
+    // given or defined by EQU as a number without using *
+    defined: HashSet<String>,
+    constants: SymbolTable,
+    // the IFs whose ENDIF hasn't been reached yet, innermost last, and how many of them were
+    // outside the file or macro being read, which has to end all the ones it starts
+    conditions: Vec<Condition>,
+    outside: usize,
+}
+
+// Parts of the source can be left out with
+//
+//   IF 1-DEBUG      IFDEF NAME      IFNDEF NAME
+//   ...             ...             ...
+//   ELSE            ENDIF           ENDIF
+//   ...
+//   ENDIF
+//
+// where IF keeps the lines up to ELSE if its expression isn't zero and the lines after it if it
+// is. They're decided as the source is read, so the expression can only use symbols given to
+// the assembler and ones defined by EQU before it, and IFDEF only knows about symbols defined
+// before it. In a macro they're decided each time it's used, once its parameters are replaced.
+struct Condition {
+    // the line of the IF, for when it has no ENDIF
+    number: usize,
+    keeping: bool,
+    // whether any part of it has been kept, so ELSE knows whether to keep what follows
+    kept: bool,
+    in_else: bool,
 }
 
-fn parse_address(iter: &mut dyn Iterator<Item = &Token>, op: &Op) -> Address {
-    match op {
-        Op::EQU | Op::ORIG | Op::CON | Op::END => parse_w_value(iter),
-        Op::ALF => parse_character_codes(iter),
-        _ => parse_a_and_i_and_f_values(iter),
+fn split_line(tokens: &[Token]) -> Result<Option<Statement>, AssemblerError> {
+    if tokens.iter().all(|x| x == &Token::Space) || tokens.first() == Some(&Token::Asterisk) {
+        return Ok(None);
     }
+    let mut iter = tokens.iter().peekable();
+    let loc = parse_loc(&mut iter)?;
+    let op = parse_op(&mut iter)?;
+    if iter.peek() == Some(&&Token::Space) {
+        iter.next();
+    }
+    let address: Vec<Token> = iter
+        .by_ref()
+        .take_while(|x| x != &&Token::Space)
+        .cloned()
+        .collect();
+    let remarks = iter.any(|x| x != &Token::Space);
+    Ok(Some(Statement {
+        loc,
+        op,
+        address,
+        remarks,
+    }))
 }
 
-fn parse_a_and_i_and_f_values(iter: &mut dyn Iterator<Item = &Token>) -> Address {
-    todo!()
+fn no_remarks(statement: &Statement) -> Result<(), AssemblerError> {
+    match statement.remarks {
+        true => Err(AssemblerError::BadLine(
+            "remarks are not supported, use a comment line instead".to_string(),
+        )),
+        false => Ok(()),
+    }
 }
 
-fn parse_w_value(iter: &mut dyn Iterator<Item = &Token>) -> Address {
-    todo!()
+// the line defining the LOC of a macro use when the line it would have named already has one
+fn label_line(label: String, file: usize, number: usize, expansion: Vec<(String, usize)>) -> Line {
+    Line {
+        file,
+        number,
+        text: format!("{} EQU *", label),
+        loc: Some(label),
+        op: Operation::Pseudo(PseudoOp::EQU),
+        address: vec![Token::Asterisk],
+        expansion,
+    }
 }
 
-fn parse_character_codes(iter: &mut dyn Iterator<Item = &Token>) -> Address {
-    todo!()
+impl Parser {
+    fn parse(&mut self) -> Result<(), AssemblerError> {
+        let given = self.sources.files.len();
+        for file in 0..given {
+            self.parse_file(file)?;
+        }
+        // a macro that generated nothing at the very end still defines its LOC
+        let number = self.sources.files[given - 1].text.lines().count();
+        for label in std::mem::take(&mut self.labels) {
+            let line = label_line(label, given - 1, number, Vec::new());
+            self.lines.push(line);
+        }
+        Ok(())
+    }
+
+    fn parse_file(&mut self, file: usize) -> Result<(), AssemblerError> {
+        let text = self.sources.files[file].text.clone();
+        let including = std::mem::replace(&mut self.file, file);
+        let outside = std::mem::replace(&mut self.outside, self.conditions.len());
+        let mut source = text.lines().enumerate().map(|(idx, text)| (idx + 1, text));
+        while let Some((number, text)) = source.next() {
+            self.parse_line(number, text, &mut source)
+                .map_err(|e| self.sources.name(file, e))?;
+        }
+        self.end_conditions()
+            .map_err(|(number, e)| self.sources.name(file, in_line(number)(e)))?;
+        self.file = including;
+        self.outside = outside;
+        Ok(())
+    }
+
+    // INCLUDE "name" reads the file in place of the line, unless it's already been read
+    fn include(
+        &mut self,
+        statement: &Statement,
+        number: usize,
+        text: &str,
+    ) -> Result<(), AssemblerError> {
+        if statement.loc.is_some() {
+            return Err(AssemblerError::BadLine(
+                "INCLUDE can't have a LOC".to_string(),
+            ));
+        }
+        let name = match text.split('"').nth(1) {
+            Some(x) if text.matches('"').count() == 2 => x,
+            _ => {
+                return Err(AssemblerError::BadLine(
+                    "INCLUDE takes a file name enclosed in \"s".to_string(),
+                ))
+            }
+        };
+        let path = self
+            .find(name)
+            .ok_or_else(|| AssemblerError::BadLine(format!("can't find {} to include", name)))?;
+        if let Some(file) = self.sources.read(&path, Some((self.file, number)))? {
+            self.parse_file(file)?;
+        }
+        Ok(())
+    }
+
+    // next to the file including it, then in each of the include paths
+    fn find(&self, name: &str) -> Option<PathBuf> {
+        let path = self.sources.files[self.file].path.as_deref();
+        let here = path.and_then(Path::parent).unwrap_or(Path::new(""));
+        std::iter::once(here)
+            .chain(self.include_paths.iter().map(PathBuf::as_path))
+            .map(|x| x.join(name))
+            .find(|x| x.is_file())
+    }
+
+    fn parse_line<'a>(
+        &mut self,
+        number: usize,
+        text: &str,
+        source: &mut impl Iterator<Item = (usize, &'a str)>,
+    ) -> Result<(), AssemblerError> {
+        let statement = match split_line(&tokenise(text)).map_err(in_line(number))? {
+            Some(x) => x,
+            None => return Ok(()),
+        };
+        if statement.op != "MACRO"
+            && self
+                .condition(&statement, number)
+                .map_err(in_line(number))?
+        {
+            return Ok(());
+        }
+        match statement.op.as_str() {
+            // a macro being left out still has to be read to find its ENDM
+            "MACRO" if self.skipping() => parse_macro(statement, number, source).map(|_| ()),
+            "MACRO" => {
+                let (name, definition) = parse_macro(statement, number, source)?;
+                if self.macros.contains_key(&name) {
+                    return Err(in_line(number)(AssemblerError::BadLine(format!(
+                        "macro {} is already defined",
+                        name
+                    ))));
+                }
+                self.macros.insert(name, definition);
+                Ok(())
+            }
+            "INCLUDE" => self
+                .include(&statement, number, text)
+                .map_err(in_line(number)),
+            _ => self
+                .add_statement(statement, number, text.to_string(), Vec::new())
+                .map_err(in_line(number)),
+        }
+    }
+
+    // adds a line to the program, or the lines it expands to if it uses a macro
+    fn add_statement(
+        &mut self,
+        statement: Statement,
+        number: usize,
+        text: String,
+        expansion: Vec<(String, usize)>,
+    ) -> Result<(), AssemblerError> {
+        match statement.op.as_str() {
+            "MACRO" => {
+                return Err(AssemblerError::BadLine(
+                    "macros can't be defined inside a macro".to_string(),
+                ))
+            }
+            "ENDM" => return Err(AssemblerError::BadLine("ENDM without MACRO".to_string())),
+            "INCLUDE" => {
+                return Err(AssemblerError::BadLine(
+                    "INCLUDE can't be used in a macro".to_string(),
+                ))
+            }
+            _ => (),
+        }
+        if let Some(definition) = self.macros.get(&statement.op).cloned() {
+            return self.expand(statement, &definition, number, expansion);
+        }
+        let op = Operation::from_str(&statement.op)?;
+        if op != Operation::Pseudo(PseudoOp::ALF) {
+            no_remarks(&statement)?;
+        }
+        self.push(Line {
+            file: self.file,
+            number,
+            loc: statement.loc,
+            op,
+            address: statement.address,
+            text,
+            expansion,
+        });
+        Ok(())
+    }
+
+    fn push(&mut self, mut line: Line) {
+        if let Some(loc) = &line.loc {
+            self.defined.insert(loc.clone());
+            if line.op == Operation::Pseudo(PseudoOp::EQU) {
+                if let Ok(value) = self.constant(&line.address) {
+                    // a duplicate is reported once symbols are defined properly
+                    let _ = self.constants.define(loc, 0, value);
+                }
+            }
+        }
+        for label in std::mem::take(&mut self.labels) {
+            match line.loc.is_none() && line.op != Operation::Pseudo(PseudoOp::EQU) {
+                true => {
+                    line.text = label.clone() + &line.text;
+                    line.loc = Some(label);
+                }
+                false => {
+                    let expansion = line.expansion.clone();
+                    let label_line = label_line(label, line.file, line.number, expansion);
+                    self.lines.push(label_line);
+                }
+            }
+        }
+        self.lines.push(line);
+    }
+
+    fn expand(
+        &mut self,
+        call: Statement,
+        definition: &Macro,
+        number: usize,
+        expansion: Vec<(String, usize)>,
+    ) -> Result<(), AssemblerError> {
+        if expansion.len() == MACRO_DEPTH {
+            return Err(AssemblerError::BadLine(format!(
+                "macros are nested more than {} deep, does {} use itself?",
+                MACRO_DEPTH, call.op
+            )));
+        }
+        no_remarks(&call)?;
+        let arguments = split_arguments(&call.address);
+        if arguments.len() > definition.parameters.len() {
+            return Err(AssemblerError::BadLine(format!(
+                "macro {} takes {} arguments, not {}",
+                call.op,
+                definition.parameters.len(),
+                arguments.len()
+            )));
+        }
+        self.expansions += 1;
+        let mut replacements: HashMap<&str, Vec<Token>> = HashMap::new();
+        for (idx, parameter) in definition.parameters.iter().enumerate() {
+            let argument = arguments.get(idx).cloned().unwrap_or_default();
+            replacements.insert(parameter, argument);
+        }
+        for label in definition.labels.iter() {
+            let renamed = Token::Symbol(format!("{}%{}", label, self.expansions));
+            replacements.entry(label).or_insert_with(|| vec![renamed]);
+        }
+        self.labels.extend(call.loc.clone());
+        let outside = std::mem::replace(&mut self.outside, self.conditions.len());
+        for (body_number, text) in definition.body.iter() {
+            let in_body = |e| AssemblerError::InMacro(call.op.clone(), *body_number, Box::new(e));
+            let (head, quoted) = text.split_at(text.find('"').unwrap_or(text.len()));
+            let head = substitute(&tokenise(head), &replacements);
+            let mut tokens = head.clone();
+            tokens.extend(tokenise(quoted));
+            let statement = match split_line(&tokens).map_err(in_body)? {
+                Some(x) => x,
+                None => continue,
+            };
+            if self.condition(&statement, *body_number).map_err(in_body)? {
+                continue;
+            }
+            let text = render(&head) + quoted;
+            let mut expansion = expansion.clone();
+            expansion.push((call.op.clone(), *body_number));
+            self.add_statement(statement, number, text, expansion)
+                .map_err(in_body)?;
+        }
+        self.end_conditions()
+            .map_err(|(number, e)| AssemblerError::InMacro(call.op.clone(), number, Box::new(e)))?;
+        self.outside = outside;
+        Ok(())
+    }
+
+    fn define(&mut self, name: &str, value: Value) -> Result<(), AssemblerError> {
+        self.defined.insert(name.to_string());
+        self.constants.define(name, 0, value)
+    }
+
+    fn constant(&self, tokens: &[Token]) -> Result<Value, AssemblerError> {
+        let context = Context {
+            symbols: &self.constants,
+            location: None,
+            position: 0,
+            byte_size: self.byte_size,
+            module: false,
+        };
+        evaluate_w_value_as_address(tokens, &context)
+    }
+
+    fn skipping(&self) -> bool {
+        self.conditions.iter().any(|x| !x.keeping)
+    }
+
+    // whether the statement was IF, ELSE or ENDIF or one of the lines being left out
+    fn condition(&mut self, statement: &Statement, number: usize) -> Result<bool, AssemblerError> {
+        let op = statement.op.as_str();
+        if !["IF", "IFDEF", "IFNDEF", "ELSE", "ENDIF"].contains(&op) {
+            return Ok(self.skipping());
+        }
+        if statement.loc.is_some() {
+            return Err(AssemblerError::BadLine(format!("{} can't have a LOC", op)));
+        }
+        no_remarks(statement)?;
+        let inside = self.conditions.len() > self.outside;
+        match op {
+            // the expression of an IF being left out might not make sense, so it isn't looked at
+            _ if op.starts_with("IF") && self.skipping() => self.conditions.push(Condition {
+                number,
+                keeping: false,
+                kept: true,
+                in_else: false,
+            }),
+            "IF" => {
+                let keeping = self.constant(&statement.address)?.number != 0;
+                self.open_condition(number, keeping);
+            }
+            _ if op.starts_with("IF") => {
+                let name = match statement.address.as_slice() {
+                    [Token::Symbol(x)] => x,
+                    _ => return Err(AssemblerError::BadLine(format!("{} takes a symbol", op))),
+                };
+                let keeping = self.defined.contains(name) == (op == "IFDEF");
+                self.open_condition(number, keeping);
+            }
+            "ELSE" if inside => {
+                let condition = self.conditions.last_mut().unwrap();
+                if condition.in_else {
+                    return Err(AssemblerError::BadLine(
+                        "IF has more than one ELSE".to_string(),
+                    ));
+                }
+                condition.in_else = true;
+                condition.keeping = !condition.kept;
+                condition.kept = true;
+            }
+            "ENDIF" if inside => {
+                self.conditions.pop();
+            }
+            _ => return Err(AssemblerError::BadLine(format!("{} without IF", op))),
+        }
+        Ok(true)
+    }
+
+    fn open_condition(&mut self, number: usize, keeping: bool) {
+        self.conditions.push(Condition {
+            number,
+            keeping,
+            kept: keeping,
+            in_else: false,
+        });
+    }
+
+    // the line and error for an IF in the file or macro just read that wasn't ended
+    fn end_conditions(&mut self) -> Result<(), (usize, AssemblerError)> {
+        match self.conditions.len() > self.outside {
+            true => {
+                let number = self.conditions[self.outside].number;
+                self.conditions.truncate(self.outside);
+                let message = "IF has no ENDIF".to_string();
+                Err((number, AssemblerError::BadLine(message)))
+            }
+            false => Ok(()),
+        }
+    }
 }
 
-fn split_tokens_by_line(tokens: Vec<Token>) -> Vec<Vec<Token>> {
-    let mut lines = Vec::new();
-    let mut line = Vec::new();
+fn parse_macro<'a>(
+    statement: Statement,
+    number: usize,
+    source: &mut impl Iterator<Item = (usize, &'a str)>,
+) -> Result<(String, Macro), AssemblerError> {
+    let bad_line = |message: String| in_line(number)(AssemblerError::BadLine(message));
+    no_remarks(&statement).map_err(in_line(number))?;
+    let name = match statement.loc {
+        Some(x) if is_local_symbol(&x) => {
+            return Err(bad_line(format!("{} can't name a macro", x)))
+        }
+        Some(x) => x,
+        None => return Err(bad_line("MACRO needs a name in the LOC field".to_string())),
+    };
+    if Operation::from_str(&name).is_ok() || DIRECTIVES.contains(&name.as_str()) {
+        return Err(bad_line(format!("{} is already an operation", name)));
+    }
+    let mut parameters: Vec<String> = Vec::new();
+    for (idx, token) in statement.address.iter().enumerate() {
+        match token {
+            Token::Symbol(x) if idx % 2 == 0 && !parameters.contains(x) => {
+                parameters.push(x.clone())
+            }
+            Token::Comma if idx % 2 == 1 && idx + 1 < statement.address.len() => (),
+            _ => {
+                return Err(bad_line(
+                    "MACRO parameters must be different symbols separated by commas".to_string(),
+                ))
+            }
+        }
+    }
+
+    let mut labels = Vec::new();
+    let mut body = Vec::new();
+    for (body_number, text) in source {
+        // lines that can't be split yet might once their parameters are replaced
+        match split_line(&tokenise(text)) {
+            Ok(Some(x)) if x.op == "ENDM" => {
+                return Ok((
+                    name,
+                    Macro {
+                        parameters,
+                        labels,
+                        body,
+                    },
+                ))
+            }
+            Ok(Some(x)) if x.op == "MACRO" => {
+                return Err(in_line(body_number)(AssemblerError::BadLine(
+                    "macros can't be defined inside a macro".to_string(),
+                )))
+            }
+            Ok(Some(Statement { loc: Some(x), .. })) if !is_local_symbol(&x) => labels.push(x),
+            Ok(None) => continue,
+            _ => (),
+        }
+        body.push((body_number, text.to_string()));
+    }
+    Err(bad_line(format!("macro {} has no ENDM", name)))
+}
+
+// the arguments of a macro use, split at the commas that aren't in a field or a literal
+fn split_arguments(tokens: &[Token]) -> Vec<Vec<Token>> {
+    if tokens.is_empty() {
+        return Vec::new();
+    }
+    let mut arguments = vec![Vec::new()];
+    let mut depth = 0;
+    let mut in_literal = false;
     for token in tokens {
         match token {
-            Token::LineBreak => {
-                lines.push(line.clone());
-                line.clear();
+            Token::LeftBracket => depth += 1,
+            Token::RightBracket => depth -= 1,
+            Token::Equals => in_literal = !in_literal,
+            Token::Comma if depth == 0 && !in_literal => {
+                arguments.push(Vec::new());
+                continue;
+            }
+            _ => (),
+        }
+        arguments.last_mut().unwrap().push(token.clone());
+    }
+    arguments
+}
+
+// # joins what's either side of it into one symbol once parameters are replaced, so INC#R with R
+// replaced by A is INCA
+fn substitute(tokens: &[Token], replacements: &HashMap<&str, Vec<Token>>) -> Vec<Token> {
+    let paste = Token::Illegal("#".to_string());
+    let mut result: Vec<Token> = Vec::new();
+    let mut pasting = false;
+    for token in tokens {
+        if token == &paste {
+            pasting = true;
+            continue;
+        }
+        let mut replacement = match token {
+            Token::Symbol(x) if replacements.contains_key(x.as_str()) => {
+                replacements[x.as_str()].clone().into_iter()
             }
-            x => line.push(x),
+            x => vec![x.clone()].into_iter(),
         };
+        if std::mem::take(&mut pasting) {
+            if let Some(right) = replacement.next() {
+                match result.pop() {
+                    Some(left) => result.extend(tokenise(&format!("{}{}", left, right))),
+                    None => result.push(right),
+                }
+            }
+        }
+        result.extend(replacement);
     }
-    return lines;
+    result
+}
+
+fn render(tokens: &[Token]) -> String {
+    tokens.iter().map(|x| x.to_string()).collect()
+}
+
+type TokenIter<'a> = Peekable<std::slice::Iter<'a, Token>>;
+
+fn parse_loc(iter: &mut TokenIter) -> Result<Option<String>, AssemblerError> {
+    match iter.next() {
+        Some(Token::Space) => Ok(None),
+        Some(Token::Symbol(s)) => match iter.next() {
+            Some(Token::Space) => Ok(Some(s.to_string())),
+            x => Err(AssemblerError::BadLine(format!("Bad loc {}", describe(x)))),
+        },
+        x => Err(AssemblerError::BadLine(format!("Bad loc {}", describe(x)))),
+    }
+}
+
+fn parse_op(iter: &mut TokenIter) -> Result<String, AssemblerError> {
+    match iter.next() {
+        Some(Token::Symbol(op)) => Ok(op.to_string()),
+        Some(x) => Err(AssemblerError::BadOpCode(x.clone())),
+        None => Err(AssemblerError::BadLine("illegal null op field".to_string())),
+    }
+}
+
+// What the linker adds to a value when it places the module: nothing for a plain number, where the
+// module went for an address in it, or where an exported symbol went for an address relative to
+// a symbol EXTERN says is in another module. Outside a module everything is a plain number.
+#[derive(Debug, PartialEq, Eq, Clone)]
+enum Base {
+    Absolute,
+    Module,
+    External(String),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Value {
+    number: i64,
+    base: Base,
+}
+
+impl Value {
+    fn absolute(number: i64) -> Self {
+        Value {
+            number,
+            base: Base::Absolute,
+        }
+    }
+
+    // index and field parts, field specifications and the like can't be moved by the linker
+    fn to_absolute(&self) -> Result<i64, AssemblerError> {
+        match self.base {
+            Base::Absolute => Ok(self.number),
+            _ => Err(AssemblerError::BadLine(
+                "an address the linker moves can't be used here".to_string(),
+            )),
+        }
+    }
+}
+
+struct LocalSymbol {
+    digit: char,
+    position: usize,
+    value: Value,
+}
+
+#[derive(Default)]
+struct SymbolTable {
+    globals: Vec<(String, Value)>,
+    index: HashMap<String, usize>,
+    locals: Vec<LocalSymbol>,
+}
+
+// dH defines a local symbol, dB and dF refer to the nearest dH before or after the current line
+fn local_symbol_digit(name: &str, kind: char) -> Option<char> {
+    let mut chars = name.chars();
+    match (chars.next(), chars.next(), chars.next()) {
+        (Some(digit), Some(x), None) if digit.is_ascii_digit() && x == kind => Some(digit),
+        _ => None,
+    }
+}
+
+pub fn is_local_symbol(name: &str) -> bool {
+    ['H', 'B', 'F']
+        .iter()
+        .any(|kind| local_symbol_digit(name, *kind).is_some())
+}
+
+impl SymbolTable {
+    fn define(&mut self, name: &str, position: usize, value: Value) -> Result<(), AssemblerError> {
+        if let Some(digit) = local_symbol_digit(name, 'H') {
+            self.locals.push(LocalSymbol {
+                digit,
+                position,
+                value,
+            });
+            return Ok(());
+        }
+        if is_local_symbol(name) {
+            return Err(AssemblerError::BadLine(format!(
+                "{} can only be used in the address field",
+                name
+            )));
+        }
+        if self.index.contains_key(name) {
+            return Err(AssemblerError::DuplicateSymbol(name.to_string()));
+        }
+        self.index.insert(name.to_string(), self.globals.len());
+        self.globals.push((name.to_string(), value));
+        Ok(())
+    }
+
+    fn lookup(&self, name: &str, position: usize) -> Option<&Value> {
+        if let Some(digit) = local_symbol_digit(name, 'B') {
+            return self
+                .locals
+                .iter()
+                .rev()
+                .find(|x| x.digit == digit && x.position < position)
+                .map(|x| &x.value);
+        }
+        if let Some(digit) = local_symbol_digit(name, 'F') {
+            return self
+                .locals
+                .iter()
+                .find(|x| x.digit == digit && x.position > position)
+                .map(|x| &x.value);
+        }
+        self.index.get(name).map(|idx| &self.globals[*idx].1)
+    }
+}
+
+struct Context<'a> {
+    symbols: &'a SymbolTable,
+    // None when * can't be known yet, for IF
+    location: Option<i32>,
+    // where the line is once macros are expanded, which is what orders local symbols
+    position: usize,
+    byte_size: ByteSize,
+    module: bool,
+}
+
+impl Context<'_> {
+    // the value of *
+    fn here(&self) -> Result<Value, AssemblerError> {
+        let base = match self.module {
+            true => Base::Module,
+            false => Base::Absolute,
+        };
+        let location = self.location.ok_or_else(|| {
+            AssemblerError::BadLine("* isn't known until the program is assembled".to_string())
+        })?;
+        Ok(Value {
+            number: location.into(),
+            base,
+        })
+    }
+}
+
+// first pass - work out where everything goes so that the second pass can use symbols before
+// they are defined
+fn define_symbols(
+    lines: &[Line],
+    sources: &Sources,
+    options: &AssemblerOptions,
+) -> Result<SymbolTable, AssemblerError> {
+    let mut symbols = SymbolTable::default();
+    for (name, value) in options.defines.iter() {
+        symbols.define(name, 0, Value::absolute(*value))?;
+    }
+    let mut location = 0;
+    for (position, line) in lines.iter().enumerate() {
+        location = define_line_symbols(line, position, &mut symbols, location, options)
+            .map_err(line.error(sources))?;
+        if line.op == Operation::Pseudo(PseudoOp::END) {
+            break;
+        }
+    }
+    Ok(symbols)
+}
+
+fn define_line_symbols(
+    line: &Line,
+    position: usize,
+    symbols: &mut SymbolTable,
+    location: i32,
+    options: &AssemblerOptions,
+) -> Result<i32, AssemblerError> {
+    let context = Context {
+        symbols,
+        location: Some(location),
+        position,
+        byte_size: options.byte_size,
+        module: options.module,
+    };
+    let here = context.here()?;
+    let (loc_value, next_location) = match line.op {
+        Operation::Pseudo(PseudoOp::EQU) => {
+            let value = evaluate_w_value_as_address(&line.address, &context)?;
+            (value, location)
+        }
+        Operation::Pseudo(PseudoOp::ORIG) => {
+            let value = evaluate_origin(&line.address, &context)?;
+            (here, value)
+        }
+        Operation::Pseudo(PseudoOp::EXTERN) => {
+            for name in linkage_symbols(&line.address, &context)? {
+                let value = Value {
+                    number: 0,
+                    base: Base::External(name.clone()),
+                };
+                symbols.define(&name, position, value)?;
+            }
+            (here, location)
+        }
+        Operation::Pseudo(PseudoOp::END | PseudoOp::EXPORT) => (here, location),
+        _ => (here, location + 1),
+    };
+    if let Some(loc) = &line.loc {
+        symbols.define(loc, position, loc_value)?;
+    }
+    Ok(next_location)
+}
+
+// a field of a word holding a value the linker moves, and what it's counted from
+type Relocation = (FieldSpecification, Base);
+
+// the A part of an instruction, which is where addresses usually go
+const ADDRESS_FIELD: FieldSpecification = FieldSpecification { left: 0, right: 2 };
+
+enum Fixup {
+    Literal(usize),
+    Undefined(usize),
+}
+
+// second pass state - literal constants and symbols that are never defined get placed after the
+// program once we know where it ends, so their addresses are patched in at the end
+#[derive(Default)]
+struct Assembly {
+    words: Vec<(i32, Word)>,
+    source_map: SourceMap,
+    literals: Vec<(Word, Vec<Relocation>)>,
+    undefined: Vec<String>,
+    fixups: Vec<(usize, Fixup)>,
+    location: i32,
+    start: Option<i32>,
+    byte_size: ByteSize,
+    module: bool,
+    // the index into words and the field of each value the linker has to move
+    relocations: Vec<(usize, Relocation)>,
+    exports: Vec<(String, Value)>,
+    // the index into words of the word each line generated, for the listing
+    line_words: Vec<Option<usize>>,
+}
+
+impl Assembly {
+    fn generate_words(
+        &mut self,
+        lines: &[Line],
+        sources: &Sources,
+        symbols: &SymbolTable,
+    ) -> Result<(), AssemblerError> {
+        for (position, line) in lines.iter().enumerate() {
+            let context = Context {
+                symbols,
+                location: Some(self.location),
+                position,
+                byte_size: self.byte_size,
+                module: self.module,
+            };
+            let generated = self.words.len();
+            self.generate_line(line, &context)
+                .map_err(line.error(sources))?;
+            let word = (self.words.len() > generated).then_some(generated);
+            self.line_words.push(word);
+            if line.op == Operation::Pseudo(PseudoOp::END) {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn generate_line(&mut self, line: &Line, context: &Context) -> Result<(), AssemblerError> {
+        if let Some(loc) = &line.loc {
+            if line.op != Operation::Pseudo(PseudoOp::EQU) && !is_local_symbol(loc) {
+                self.source_map.labels.push((loc.clone(), self.location));
+            }
+        }
+        match &line.op {
+            Operation::Pseudo(PseudoOp::EQU | PseudoOp::EXTERN) => Ok(()),
+            Operation::Pseudo(PseudoOp::ORIG) => {
+                self.location = evaluate_origin(&line.address, context)?;
+                Ok(())
+            }
+            // a module that is only used by others doesn't have to say where to start
+            Operation::Pseudo(PseudoOp::END) if self.module && line.address.is_empty() => Ok(()),
+            Operation::Pseudo(PseudoOp::END) => {
+                self.start = Some(evaluate_origin(&line.address, context)?);
+                Ok(())
+            }
+            Operation::Pseudo(PseudoOp::EXPORT) => {
+                for name in linkage_symbols(&line.address, context)? {
+                    if is_local_symbol(&name) {
+                        return Err(AssemblerError::BadLine(format!(
+                            "{} is a local symbol, which can't be exported",
+                            name
+                        )));
+                    }
+                    let value = match context.symbols.lookup(&name, context.position) {
+                        Some(Value {
+                            base: Base::External(_),
+                            ..
+                        }) => {
+                            return Err(AssemblerError::BadLine(format!(
+                                "{} is in another module, which is the one to export it",
+                                name
+                            )))
+                        }
+                        Some(x) => x.clone(),
+                        None => return Err(AssemblerError::UndefinedSymbol(name)),
+                    };
+                    self.exports.push((name, value));
+                }
+                Ok(())
+            }
+            Operation::Pseudo(PseudoOp::CON) => {
+                let (word, relocations) = evaluate_w_value(&line.address, context)?;
+                self.emit(word, relocations, line)
+            }
+            Operation::Pseudo(PseudoOp::ALF) => {
+                self.emit(parse_character_codes(&line.text)?, Vec::new(), line)
+            }
+            Operation::Machine(op) => {
+                let (word, relocation) = self.assemble_instruction(*op, &line.address, context)?;
+                let relocations = relocation.map(|x| (ADDRESS_FIELD, x)).into_iter().collect();
+                self.emit(word, relocations, line)
+            }
+        }
+    }
+
+    fn emit(
+        &mut self,
+        word: Word,
+        relocations: Vec<Relocation>,
+        line: &Line,
+    ) -> Result<(), AssemblerError> {
+        // negative locations are for a machine with interrupts, see computer::interrupts
+        if !(1 - memory::SIZE as i32..memory::SIZE as i32).contains(&self.location) {
+            return Err(AssemblerError::BadLine(format!(
+                "location {} is outside memory",
+                self.location
+            )));
+        }
+        for relocation in relocations {
+            self.relocations.push((self.words.len(), relocation));
+        }
+        self.words.push((self.location, word));
+        self.source_map
+            .lines
+            .push((self.location, line.file, line.number));
+        self.location += 1;
+        Ok(())
+    }
+
+    fn assemble_instruction(
+        &mut self,
+        op: Op,
+        tokens: &[Token],
+        context: &Context,
+    ) -> Result<(Word, Option<Base>), AssemblerError> {
+        let opcode = op.opcode();
+        if op.is_binary_only() && context.byte_size != ByteSize::BINARY {
+            return Err(AssemblerError::BadLine(format!(
+                "{} is only on binary MIX",
+                opcode.mnemonic
+            )));
+        }
+        let mut iter = tokens.iter().peekable();
+        let mut base = Base::Absolute;
+        let (sign, a) = match iter.peek() {
+            None | Some(Token::Comma) | Some(Token::LeftBracket) => (Sign::PLUS, 0),
+            Some(Token::Equals) => {
+                iter.next();
+                let literal: Vec<Token> = iter
+                    .by_ref()
+                    .take_while(|x| x != &&Token::Equals)
+                    .cloned()
+                    .collect();
+                let value = evaluate_w_value(&literal, context)?;
+                self.fixups
+                    .push((self.words.len(), Fixup::Literal(self.literals.len())));
+                self.literals.push(value);
+                (Sign::PLUS, 0)
+            }
+            Some(Token::Symbol(s)) if is_future_reference(s, tokens, context) => {
+                iter.next();
+                let idx = match self.undefined.iter().position(|x| x == s) {
+                    Some(x) => x,
+                    None => {
+                        self.undefined.push(s.to_string());
+                        self.undefined.len() - 1
+                    }
+                };
+                self.fixups.push((self.words.len(), Fixup::Undefined(idx)));
+                (Sign::PLUS, 0)
+            }
+            Some(x) => {
+                let negative = x == &&Token::Minus;
+                let value = evaluate_expression(&mut iter, context)?;
+                base = value.base;
+                match value.number < 0 || (negative && value.number == 0) {
+                    true => (Sign::MINUS, value.number.abs()),
+                    false => (Sign::PLUS, value.number),
+                }
+            }
+        };
+        let i = match iter.peek() {
+            Some(Token::Comma) => {
+                iter.next();
+                evaluate_expression(&mut iter, context)?.to_absolute()?
+            }
+            _ => 0,
+        };
+        let f = match iter.peek() {
+            Some(Token::LeftBracket) => {
+                iter.next();
+                let value = evaluate_expression(&mut iter, context)?.to_absolute()?;
+                expect_token(&mut iter, Token::RightBracket)?;
+                if opcode.operand == OperandKind::FIXED {
+                    return Err(AssemblerError::BadLine(format!(
+                        "{} does not take a field",
+                        opcode.mnemonic
+                    )));
+                }
+                value
+            }
+            _ => opcode.field.into(),
+        };
+        if let Some(x) = iter.next() {
+            return Err(AssemblerError::BadLine(format!(
+                "Unexpected {} in address",
+                describe(Some(x))
+            )));
+        }
+        let a = i32::try_from(a).unwrap_or(i32::MAX);
+        let i = i32::try_from(i).unwrap_or(-1);
+        let f = i32::try_from(f).unwrap_or(-1);
+        let word = Word::from_instruction_parts_in(sign, a, i, f, opcode.code, self.byte_size)
+            .map_err(|e| AssemblerError::BadLine(e.to_string()))?;
+        Ok((word, (base != Base::Absolute).then_some(base)))
+    }
+
+    fn finish(
+        mut self,
+        symbols: SymbolTable,
+        sources: &Sources,
+    ) -> Result<Program, AssemblerError> {
+        let literal_base = self.location;
+        let undefined_base = literal_base + self.literals.len() as i32;
+        for (word_idx, fixup) in self.fixups.iter() {
+            let address = match fixup {
+                Fixup::Literal(x) => literal_base + *x as i32,
+                Fixup::Undefined(x) => undefined_base + *x as i32,
+            };
+            // every literal and undefined symbol is used by at least one line, so checking here
+            // catches the pool running off the end of memory and blames a line that added to it
+            let (_, file, number) = self.source_map.lines[*word_idx];
+            let in_line = |e| sources.in_file(file, AssemblerError::InLine(number, Box::new(e)));
+            // a symbol another module exports needs EXTERN, and a silent CON 0 would hide one
+            // that's missing until the linked program ran
+            if let (true, Fixup::Undefined(x)) = (self.module, fixup) {
+                return Err(in_line(AssemblerError::BadLine(format!(
+                    "{} is undefined, declare it with EXTERN if another module exports it",
+                    self.undefined[*x]
+                ))));
+            }
+            if !(1 - memory::SIZE as i32..memory::SIZE as i32).contains(&address) {
+                let what = match fixup {
+                    Fixup::Literal(_) => "the literal".to_string(),
+                    Fixup::Undefined(x) => self.undefined[*x].clone(),
+                };
+                return Err(in_line(AssemblerError::BadLine(format!(
+                    "{} would be at location {}, outside memory",
+                    what, address
+                ))));
+            }
+            let (location, word) = self.words[*word_idx];
+            let patched = Word::from_instruction_parts_in(
+                word.sign,
+                address,
+                word.index(),
+                word.field().value(),
+                word.code(),
+                self.byte_size,
+            )
+            .map_err(|e| in_line(AssemblerError::BadLine(e.to_string())))?;
+            self.words[*word_idx] = (location, patched);
+            // in a module these are placed along with everything else
+            if self.module {
+                self.relocations
+                    .push((*word_idx, (ADDRESS_FIELD, Base::Module)));
+            }
+        }
+        // external symbols aren't anywhere in this module
+        let mut symbol_list: Vec<(String, i32)> = symbols
+            .globals
+            .into_iter()
+            .filter(|(_, value)| !matches!(value.base, Base::External(_)))
+            .map(|(name, value)| (name, value.number as i32))
+            .collect();
+        for (idx, (literal, relocations)) in self.literals.into_iter().enumerate() {
+            for relocation in relocations {
+                self.relocations.push((self.words.len(), relocation));
+            }
+            self.words.push((literal_base + idx as i32, literal));
+        }
+        for (idx, name) in self.undefined.into_iter().enumerate() {
+            let address = undefined_base + idx as i32;
+            self.words.push((address, Word::ZERO));
+            symbol_list.push((name, address));
+        }
+        let linkage = self.module.then(|| {
+            let mut linkage = Linkage::default();
+            for (name, value) in self.exports {
+                let relative = value.base == Base::Module;
+                linkage.exports.push((name, value.number as i32, relative));
+            }
+            for (word_idx, (field, base)) in self.relocations {
+                let address = self.words[word_idx].0;
+                match base {
+                    Base::Module => linkage.relocations.push((address, field)),
+                    Base::External(name) => linkage.externals.push((address, field, name)),
+                    Base::Absolute => (),
+                }
+            }
+            linkage
+        });
+        Ok(Program {
+            words: self.words,
+            start: self.start,
+            symbols: symbol_list,
+            byte_size: self.byte_size,
+            source_map: self.source_map,
+            linkage,
+        })
+    }
+}
+
+// a symbol that is never defined can still be used as the whole A part of an instruction, it
+// then refers to a word of zero placed after the program
+fn is_future_reference(symbol: &str, tokens: &[Token], context: &Context) -> bool {
+    let is_whole_a_part = matches!(
+        tokens.get(1),
+        None | Some(Token::Comma) | Some(Token::LeftBracket)
+    );
+    is_whole_a_part
+        && !is_local_symbol(symbol)
+        && context.symbols.lookup(symbol, context.position).is_none()
+}
+
+fn evaluate_expression(iter: &mut TokenIter, context: &Context) -> Result<Value, AssemblerError> {
+    let mut value = match iter.peek() {
+        Some(Token::Plus) => {
+            iter.next();
+            evaluate_atom(iter, context)?
+        }
+        Some(Token::Minus) => {
+            iter.next();
+            Value::absolute(-evaluate_atom(iter, context)?.to_absolute()?)
+        }
+        _ => evaluate_atom(iter, context)?,
+    };
+    while let Some(
+        operator @ (Token::Plus
+        | Token::Minus
+        | Token::Asterisk
+        | Token::Slash
+        | Token::DoubleSlash
+        | Token::Colon),
+    ) = iter.peek().map(|x| (*x).clone())
+    {
+        iter.next();
+        let operand = evaluate_atom(iter, context)?;
+        if operand.number == 0 && (operator == Token::Slash || operator == Token::DoubleSlash) {
+            return Err(AssemblerError::BadLine("division by zero".to_string()));
+        }
+        let base = combined_base(&operator, value.base, operand.base)?;
+        // binary operators are applied strictly left to right, there's no precedence in MIXAL
+        let (left, right) = (value.number as i128, operand.number as i128);
+        let result = match operator {
+            Token::Plus => left + right,
+            Token::Minus => left - right,
+            Token::Asterisk => left * right,
+            Token::Slash => left / right,
+            Token::DoubleSlash => left * context.byte_size.capacity(5) as i128 / right,
+            Token::Colon => 8 * left + right,
+            _ => unreachable!(),
+        };
+        if result.abs() >= context.byte_size.capacity(5) as i128 {
+            return Err(AssemblerError::BadLine(format!(
+                "expression value {} does not fit in a word",
+                result
+            )));
+        }
+        value = Value {
+            number: result as i64,
+            base,
+        };
+    }
+    Ok(value)
+}
+
+// The linker can only add to a value, so an address it moves can have numbers added to or taken
+// away from it and nothing else, although the distance between two addresses in the module is a
+// plain number.
+fn combined_base(operator: &Token, left: Base, right: Base) -> Result<Base, AssemblerError> {
+    match (operator, left, right) {
+        (_, Base::Absolute, Base::Absolute) => Ok(Base::Absolute),
+        (Token::Plus, x, Base::Absolute) | (Token::Plus, Base::Absolute, x) => Ok(x),
+        (Token::Minus, x, Base::Absolute) => Ok(x),
+        (Token::Minus, Base::Module, Base::Module) => Ok(Base::Absolute),
+        _ => Err(AssemblerError::BadLine(
+            "an address the linker moves can only have numbers added to or taken away from it"
+                .to_string(),
+        )),
+    }
+}
+
+fn evaluate_atom(iter: &mut TokenIter, context: &Context) -> Result<Value, AssemblerError> {
+    match iter.next() {
+        Some(Token::Number(x)) if *x >= context.byte_size.capacity(5) => Err(
+            AssemblerError::BadLine(format!("number {} does not fit in a word", x)),
+        ),
+        Some(Token::Number(x)) => Ok(Value::absolute(*x)),
+        Some(Token::Symbol(s)) => context
+            .symbols
+            .lookup(s, context.position)
+            .cloned()
+            .ok_or_else(|| AssemblerError::UndefinedSymbol(s.to_string())),
+        Some(Token::Asterisk) => context.here(),
+        x => Err(AssemblerError::BadLine(format!(
+            "Expected number, symbol or *, found {}",
+            describe(x)
+        ))),
+    }
+}
+
+// W-values are a comma separated list of E(F), each E is stored into field F of a word that
+// starts off as +0, as used by CON, EQU, ORIG, END and literal constants. Along with the word
+// come the fields the linker has to move.
+fn evaluate_w_value(
+    tokens: &[Token],
+    context: &Context,
+) -> Result<(Word, Vec<Relocation>), AssemblerError> {
+    let mut iter = tokens.iter().peekable();
+    let mut word = Word::ZERO;
+    let mut relocations = Vec::new();
+    loop {
+        let negative = iter.peek() == Some(&&Token::Minus);
+        let value = evaluate_expression(&mut iter, context)?;
+        let field = match iter.peek() {
+            Some(Token::LeftBracket) => {
+                iter.next();
+                let field = evaluate_expression(&mut iter, context)?.to_absolute()?;
+                expect_token(&mut iter, Token::RightBracket)?;
+                field
+            }
+            _ => 5,
+        };
+        word = store_in_field(word, value.number, negative, field, context.byte_size)?;
+        // a later part storing into the same field replaces what the linker would have moved
+        let field = FieldSpecification {
+            left: (field / 8) as i32,
+            right: (field % 8) as i32,
+        };
+        relocations.retain(|(x, _): &Relocation| x != &field);
+        if value.base != Base::Absolute {
+            relocations.push((field, value.base));
+        }
+        match iter.next() {
+            None => break,
+            Some(Token::Comma) => continue,
+            Some(x) => {
+                return Err(AssemblerError::BadLine(format!(
+                    "Unexpected {} in W-value",
+                    describe(Some(x))
+                )))
+            }
+        }
+    }
+    Ok((word, relocations))
+}
+
+// symbols and locations are addresses, so a W-value used as one has to be small enough, and
+// if the linker moves it, it has to be the whole word
+fn evaluate_w_value_as_address(
+    tokens: &[Token],
+    context: &Context,
+) -> Result<Value, AssemblerError> {
+    let (word, relocations) = evaluate_w_value(tokens, context)?;
+    let number = word.to_i64_in(context.byte_size);
+    if i32::try_from(number).is_err() {
+        return Err(AssemblerError::BadLine(format!(
+            "value {} is too large",
+            number
+        )));
+    }
+    let whole_word = FieldSpecification { left: 0, right: 5 };
+    let base = match relocations.as_slice() {
+        [] => Base::Absolute,
+        [(field, base)] if field == &whole_word => base.clone(),
+        _ => {
+            return Err(AssemblerError::BadLine(
+                "an address the linker moves has to be the whole W-value".to_string(),
+            ))
+        }
+    };
+    Ok(Value { number, base })
+}
+
+// where ORIG and END go, which in a module has to be somewhere in it
+fn evaluate_origin(tokens: &[Token], context: &Context) -> Result<i32, AssemblerError> {
+    let value = evaluate_w_value_as_address(tokens, context)?;
+    let base = match context.module {
+        true => Base::Module,
+        false => Base::Absolute,
+    };
+    if value.base != base {
+        return Err(AssemblerError::BadLine(
+            "an address in a module has to be counted from somewhere in it, like * or a label"
+                .to_string(),
+        ));
+    }
+    Ok(value.number as i32)
+}
+
+// the symbols EXTERN and EXPORT take, which they can only do in a module
+fn linkage_symbols(tokens: &[Token], context: &Context) -> Result<Vec<String>, AssemblerError> {
+    if !context.module {
+        return Err(AssemblerError::BadLine(
+            "EXTERN and EXPORT can only be used when assembling a module".to_string(),
+        ));
+    }
+    let mut names = Vec::new();
+    for (idx, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Symbol(x) if idx % 2 == 0 => names.push(x.clone()),
+            Token::Comma if idx % 2 == 1 && idx + 1 < tokens.len() => (),
+            _ => {
+                return Err(AssemblerError::BadLine(
+                    "EXTERN and EXPORT take symbols separated by commas".to_string(),
+                ))
+            }
+        }
+    }
+    if names.is_empty() {
+        return Err(AssemblerError::BadLine(
+            "EXTERN and EXPORT take symbols separated by commas".to_string(),
+        ));
+    }
+    Ok(names)
+}
+
+fn store_in_field(
+    word: Word,
+    value: i64,
+    negative: bool,
+    field: i64,
+    byte_size: ByteSize,
+) -> Result<Word, AssemblerError> {
+    let field_specification = FieldSpecification {
+        left: (field / 8) as i32,
+        right: (field % 8) as i32,
+    };
+    if field < 0 || !field_specification.is_valid() {
+        return Err(AssemblerError::BadLine(format!("illegal field {}", field)));
+    }
+    let mut bytes = [
+        word.bytes.0,
+        word.bytes.1,
+        word.bytes.2,
+        word.bytes.3,
+        word.bytes.4,
+    ];
+    let mut magnitude = value.abs();
+    for position in (field_specification.left.max(1)..=field_specification.right).rev() {
+        let base = byte_size.values() as i64;
+        bytes[(position - 1) as usize] =
+            Byte::from_i32_in((magnitude % base) as i32, byte_size).unwrap();
+        magnitude /= base;
+    }
+    let sign = match field_specification.left {
+        0 if value < 0 || (negative && value == 0) => Sign::MINUS,
+        0 => Sign::PLUS,
+        _ => word.sign,
+    };
+    Ok(Word {
+        sign,
+        bytes: (bytes[0], bytes[1], bytes[2], bytes[3], bytes[4]),
+    })
+}
+
+fn expect_token(iter: &mut TokenIter, expected: Token) -> Result<(), AssemblerError> {
+    match iter.next() {
+        Some(x) if x == &expected => Ok(()),
+        x => Err(AssemblerError::BadLine(format!(
+            "Expected {}, found {}",
+            describe(Some(&expected)),
+            describe(x)
+        ))),
+    }
+}
+
+fn parse_character_codes(text: &str) -> Result<Word, AssemblerError> {
+    let characters: Vec<char> = match text.split('"').nth(1) {
+        Some(x) if text.matches('"').count() >= 2 => x.chars().collect(),
+        _ => {
+            return Err(AssemblerError::BadLine(
+                "ALF characters must be enclosed in \"s".to_string(),
+            ))
+        }
+    };
+    if characters.len() > 5 {
+        return Err(AssemblerError::BadLine(
+            "ALF takes at most 5 characters".to_string(),
+        ));
+    }
+    let mut bytes = [Byte::ZERO; 5];
+    for (c, byte) in characters.iter().zip(bytes.iter_mut()) {
+        *byte = Byte::from_char(*c)
+            .map_err(|_| AssemblerError::BadLine(format!("{} has no MIX character code", c)))?;
+    }
+    Ok(Word {
+        sign: Sign::PLUS,
+        bytes: (bytes[0], bytes[1], bytes[2], bytes[3], bytes[4]),
+    })
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum Token {
     Illegal(String),
     Symbol(String),
-    Number(i32),
+    Number(i64),
     Plus,
     Minus,
     Asterisk,
@@ -448,21 +1938,71 @@ pub enum Token {
     Colon,
     LeftBracket,
     RightBracket,
+    Comma,
+    Equals,
     LineBreak,
     Space,
     DoubleQuote,
 }
 
+// how a token is named in an error, the end of the line if there isn't one
+fn describe(token: Option<&Token>) -> String {
+    match token {
+        None | Some(Token::LineBreak) => "the end of the line".to_string(),
+        Some(Token::Space) => "a space".to_string(),
+        Some(x) => format!("'{}'", x),
+    }
+}
+
+// the text a token was read from, give or take leading zeros and repeated spaces
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Illegal(x) | Self::Symbol(x) => write!(f, "{}", x),
+            Self::Number(x) => write!(f, "{}", x),
+            Self::Plus => write!(f, "+"),
+            Self::Minus => write!(f, "-"),
+            Self::Asterisk => write!(f, "*"),
+            Self::Slash => write!(f, "/"),
+            Self::DoubleSlash => write!(f, "//"),
+            Self::Colon => write!(f, ":"),
+            Self::LeftBracket => write!(f, "("),
+            Self::RightBracket => write!(f, ")"),
+            Self::Comma => write!(f, ","),
+            Self::Equals => write!(f, "="),
+            Self::LineBreak => writeln!(f),
+            Self::Space => write!(f, " "),
+            Self::DoubleQuote => write!(f, "\""),
+        }
+    }
+}
+
 fn tokenise(source_content: &str) -> Vec<Token> {
-    let mut iter = source_content.chars().into_iter().peekable();
+    let mut iter = source_content.chars().peekable();
     let mut result: Vec<Token> = Vec::new();
-    while iter.peek() != None {
+    while iter.peek().is_some() {
         result.push(match iter.next() {
             None => break,
             Some(x) => get_token(&mut iter, x),
         });
     }
-    return result;
+    result
+}
+
+// each token with the column, in characters, it starts at
+fn tokenise_with_columns(source_content: &str) -> Vec<(Token, usize)> {
+    let read = Cell::new(0);
+    let mut iter = source_content
+        .chars()
+        .inspect(|_| read.set(read.get() + 1))
+        .peekable();
+    let mut result = Vec::new();
+    // once next has returned a character nothing after it has been read, peeked at or not
+    while let Some(x) = iter.next() {
+        let column = read.get() - 1;
+        result.push((get_token(&mut iter, x), column));
+    }
+    result
 }
 
 #[test]
@@ -499,7 +2039,29 @@ fn should_tokenise_correctly() {
     }
 }
 
-fn get_token(iter: &mut Peekable<Chars>, current_value: char) -> Token {
+#[test]
+fn should_tokenise_with_columns() {
+    use Token::*;
+    let results = tokenise_with_columns("LOOP  LDA //X,1 \"É\"2H");
+    let expected = vec![
+        (Symbol("LOOP".to_string()), 0),
+        (Space, 4),
+        (Symbol("LDA".to_string()), 6),
+        (Space, 9),
+        (DoubleSlash, 10),
+        (Symbol("X".to_string()), 12),
+        (Comma, 13),
+        (Number(1), 14),
+        (Space, 15),
+        (DoubleQuote, 16),
+        (Illegal("É".to_string()), 17),
+        (DoubleQuote, 18),
+        (Symbol("2H".to_string()), 19),
+    ];
+    assert_eq!(results, expected);
+}
+
+fn get_token(iter: &mut Peekable<impl Iterator<Item = char>>, current_value: char) -> Token {
     use Token::*;
     match current_value {
         '\n' => LineBreak,
@@ -507,6 +2069,8 @@ fn get_token(iter: &mut Peekable<Chars>, current_value: char) -> Token {
         ')' => RightBracket,
         '(' => LeftBracket,
         ':' => Colon,
+        ',' => Comma,
+        '=' => Equals,
         '/' => handle_slash_or_double_slash(iter),
         '*' => Asterisk,
         '-' => Minus,
@@ -519,7 +2083,7 @@ fn get_token(iter: &mut Peekable<Chars>, current_value: char) -> Token {
     }
 }
 
-fn handle_slash_or_double_slash(iter: &mut Peekable<Chars>) -> Token {
+fn handle_slash_or_double_slash(iter: &mut Peekable<impl Iterator<Item = char>>) -> Token {
     match iter.peek() {
         Some('/') => {
             iter.next();
@@ -530,7 +2094,7 @@ fn handle_slash_or_double_slash(iter: &mut Peekable<Chars>) -> Token {
 }
 
 fn absorb_consecutive_alphanumerics_into_single_token(
-    iter: &mut Peekable<Chars>,
+    iter: &mut Peekable<impl Iterator<Item = char>>,
     current_value: char,
 ) -> Token {
     let mut buffer = vec![current_value];
@@ -551,15 +2115,17 @@ fn absorb_consecutive_alphanumerics_into_single_token(
     if buffer.len() > 10 {
         return Token::Illegal(string_value);
     }
-    return match string_value.parse::<i32>() {
+    match string_value.parse::<i64>() {
         Ok(z) => Token::Number(z),
         _ => Token::Symbol(string_value),
-    };
+    }
 }
 
-fn absorb_consecutive_spaces_into_single_token(iter: &mut Peekable<Chars>) -> Token {
+fn absorb_consecutive_spaces_into_single_token(
+    iter: &mut Peekable<impl Iterator<Item = char>>,
+) -> Token {
     while iter.peek() == Some(&' ') {
         iter.next();
     }
-    return Token::Space;
+    Token::Space
 }
@@ -1,20 +1,65 @@
-use mix_emu::assembler;
+use mix_emu::{assembler, data_types::ByteSize};
 use std::env;
 use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+const USAGE: &str = "USAGE: assemble <source.mixal>... [-o <output.mixo>] [--bytes <n>] \
+                     [--listing <file>] [-I <dir>]... [-D <name>[=<value>]]... [--module]";
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
-    if args.len() == 1 {
-        panic!("USAGE: pass in paths to MIXAL source files");
+    let args: Vec<String> = env::args().skip(1).collect();
+    // the sources come first, the files are assembled as one program in the order given
+    let count = args.iter().take_while(|x| !x.starts_with('-')).count();
+    let (paths, flags) = args.split_at(count);
+    if paths.is_empty() {
+        panic!("{}", USAGE);
+    }
+    let mut output_path = None;
+    let mut listing_path = None;
+    let mut options = assembler::AssemblerOptions::default();
+    let mut flags = flags.to_vec();
+    // --module is the only flag without a value
+    if let Some(idx) = flags.iter().position(|x| x == "--module") {
+        flags.remove(idx);
+        options.module = true;
     }
-    if args.len() != 2 {
-        panic!("USAGE: only pass in one MIXAL source file");
+    for pair in flags.chunks(2) {
+        match pair {
+            [flag, value] if flag == "-o" => output_path = Some(value),
+            [flag, value] if flag == "--listing" => listing_path = Some(value),
+            [flag, value] if flag == "-I" => options.include_paths.push(PathBuf::from(value)),
+            [flag, value] if flag == "-D" => options.defines.push(
+                assembler::parse_define(value)
+                    .expect("USAGE: -D takes a symbol, optionally followed by = and a number"),
+            ),
+            [flag, value] if flag == "--bytes" => {
+                options.byte_size = value
+                    .parse()
+                    .ok()
+                    .and_then(ByteSize::new)
+                    .expect("USAGE: --bytes takes a byte size from 64 to 100")
+            }
+            _ => panic!("{}", USAGE),
+        }
     }
 
-    let path = &args[1];
-    let source_content = fs::read_to_string(path).expect("Failed to read source file");
-    let instructions = assembler::read_source_string_as_instructions(&source_content);
-    for instruction in instructions.iter() {
-        println!("{:?}", instruction);
+    let (program, listing) = match assembler::assemble_files_with_listing(paths, &options) {
+        Ok(x) => x,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    if let Some(x) = listing_path {
+        fs::write(x, listing).expect("Failed to write listing");
+    }
+    match output_path {
+        Some(x) => program
+            .write_object_to_file(x)
+            .expect("Failed to write object file"),
+        None => program
+            .write_object(&mut io::stdout().lock())
+            .expect("Failed to write object file"),
     }
 }
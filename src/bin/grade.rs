@@ -0,0 +1,93 @@
+use mix_emu::{
+    computer::devices::TapeUnit,
+    data_types::ByteSize,
+    grader::{self, Assignment},
+};
+use std::{env, fs, process, thread};
+
+const USAGE: &str = "USAGE: grade <submissions> [options]
+  each .mixal file in the directory is a submission, as is each directory of .mixal files
+  --cards <file>         cards every submission reads on unit 16, one per line
+  --tape <n>=<image>     what every submission finds on tape unit n (0-7)
+  --printer <file>       the lines the printer (unit 18) should end up with
+  --punch <file>         the cards that should be punched on unit 17
+  --limit <count>        instructions each submission can run, 10000000 by default
+  --time <u>             time each submission can take
+  --bytes <n>            values per byte, 64 (binary, the default) to 100 (decimal)
+  --threads <n>          how many submissions to run at once, one per CPU by default
+  -o <file>              where to write the JSON report, stdout by default";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (directory, options) = match args.split_first() {
+        Some(x) => x,
+        None => usage(),
+    };
+    let mut assignment = Assignment {
+        instruction_limit: Some(10_000_000),
+        ..Default::default()
+    };
+    let mut threads = thread::available_parallelism().map_or(1, |x| x.get());
+    let mut output = None;
+    if options.len() % 2 != 0 {
+        usage();
+    }
+    for pair in options.chunks(2) {
+        let value = pair[1].as_str();
+        match pair[0].as_str() {
+            "--cards" => assignment.cards = read_lines(value),
+            "--tape" => {
+                let (unit, path) = value.split_once('=').unwrap_or_else(|| usage());
+                let unit = match unit.parse() {
+                    Ok(x) if x < 8 => x,
+                    _ => usage(),
+                };
+                let (tape, _) =
+                    TapeUnit::load_image(path).unwrap_or_else(|e| fail(format!("{}: {}", path, e)));
+                assignment.tapes.push((unit, tape.data));
+            }
+            "--printer" => assignment.expected_printer = Some(read_lines(value)),
+            "--punch" => assignment.expected_punch = Some(read_lines(value)),
+            "--limit" => assignment.instruction_limit = Some(number(value)),
+            "--time" => assignment.time_limit = Some(number(value)),
+            "--bytes" => {
+                assignment.byte_size =
+                    ByteSize::new(number(value) as i32).unwrap_or_else(|| usage())
+            }
+            "--threads" => threads = number(value) as usize,
+            "-o" => output = Some(value),
+            _ => usage(),
+        }
+    }
+
+    let submissions = grader::find_submissions(directory)
+        .unwrap_or_else(|e| fail(format!("{}: {}", directory, e)));
+    let reports = grader::grade_all(&assignment, &submissions, threads);
+    let json = grader::json_report(&reports);
+    match output {
+        Some(path) => fs::write(path, json).unwrap_or_else(|e| fail(format!("{}: {}", path, e))),
+        None => print!("{}", json),
+    }
+}
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(2);
+}
+
+fn fail(message: String) -> ! {
+    eprintln!("grade: {}", message);
+    process::exit(1);
+}
+
+fn number(value: &str) -> u64 {
+    value.parse().unwrap_or_else(|_| usage())
+}
+
+fn read_lines(path: &str) -> Vec<String> {
+    fs::read_to_string(path)
+        .unwrap_or_else(|e| fail(format!("{}: {}", path, e)))
+        .lines()
+        .map(|x| x.to_string())
+        .collect()
+}
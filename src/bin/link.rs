@@ -0,0 +1,47 @@
+use mix_emu::{linker, object::Program};
+use std::env;
+use std::io;
+
+const USAGE: &str = "USAGE: link <module.mixo>[@<origin>]... [-o <output.mixo>]";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    // the modules come first, each after the one before unless it's given an origin
+    let count = args.iter().take_while(|x| !x.starts_with('-')).count();
+    let (paths, flags) = args.split_at(count);
+    if paths.is_empty() {
+        panic!("{}", USAGE);
+    }
+    let output_path = match flags {
+        [] => None,
+        [flag, value] if flag == "-o" => Some(value),
+        _ => panic!("{}", USAGE),
+    };
+
+    let mut modules = Vec::new();
+    for path in paths {
+        let (path, origin) = match path.rsplit_once('@') {
+            Some((path, origin)) => (path, Some(origin.parse().expect(USAGE))),
+            None => (path.as_str(), None),
+        };
+        let program = Program::read_object_from_file(path)
+            .unwrap_or_else(|e| panic!("Failed to read {}: {:?}", path, e));
+        modules.push((program, origin));
+    }
+    let modules: Vec<(&Program, Option<i32>)> = modules.iter().map(|(x, y)| (x, *y)).collect();
+    let program = match linker::link(&modules) {
+        Ok(x) => x,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    match output_path {
+        Some(x) => program
+            .write_object_to_file(x)
+            .expect("Failed to write object file"),
+        None => program
+            .write_object(&mut io::stdout().lock())
+            .expect("Failed to write object file"),
+    }
+}
@@ -0,0 +1,327 @@
+use mix_emu::{
+    assembler,
+    computer::{
+        devices::{
+            image::{self, ImageFormat},
+            DiskUnit, TapeUnit, Terminal, TextInput, TextOutput,
+        },
+        profile::Profile,
+        Computer, StopReason,
+    },
+    data_types::{ByteSize, Word},
+    object::Program,
+};
+use std::{
+    env, fs,
+    io::{self, Write},
+    path::PathBuf,
+    process,
+};
+
+const USAGE: &str = "USAGE: mix run <program.mixal|program.mixo> [options]
+  --card-reader <file>   cards to read on unit 16, one per line
+  --punch <file>         where to write cards punched on unit 17
+  --printer <file>       where to write the line printer (unit 18), stdout by default
+  --terminal <file>      lines typed on the terminal (unit 19), output goes to stdout
+  --paper-tape <file>    lines to read on unit 20
+  --tape <n>=<image>     tape unit n (0-7), written back when the run ends
+  --disk <n>=<image>     disk unit n (8-15), written back when the run ends
+  --limit <count>        stop after this many instructions
+  --bytes <n>            values per byte, 64 (binary, the default) to 100 (decimal)
+  --include <dir>        where to look for files the program INCLUDEs, can be repeated
+  --define <name>[=<n>]  define a symbol for the program to test with IF, can be repeated
+  --profile <file>       write the source with execution counts and times to a file
+  --trace <file>         write each instruction with its source line as it runs
+  --interrupts <u>       turn on interrupts, with each I/O operation taking this long
+       mix tape create <image> <blocks> [--text|--alf]
+       mix tape show <image> [block]
+       mix tape convert <image> <new image> [--text|--alf]
+  images are binary unless --text or --alf is given, --alf writes words as ALF where it can";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match args.split_first() {
+        Some((command, rest)) if command == "run" => run(rest),
+        Some((command, rest)) if command == "tape" => tape(rest),
+        _ => usage(),
+    }
+}
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(2);
+}
+
+fn fail(message: String) -> ! {
+    eprintln!("mix: {}", message);
+    process::exit(1);
+}
+
+fn run(args: &[String]) {
+    let (path, options) = match args.split_first() {
+        Some(x) => x,
+        None => usage(),
+    };
+    let byte_size = byte_size_option(options);
+    let assembler_options = assembler::AssemblerOptions {
+        byte_size: byte_size.unwrap_or_default(),
+        include_paths: include_paths_option(options),
+        defines: defines_option(options),
+        ..Default::default()
+    };
+    let program = load(path, assembler_options);
+    if byte_size.is_some_and(|x| x != program.byte_size) {
+        fail(format!(
+            "{}: assembled for {} values per byte",
+            path,
+            program.byte_size.values()
+        ));
+    }
+    let mut computer = Computer::with_byte_size(program.byte_size);
+    let mut limit = None;
+    let mut profile = None;
+    let mut tapes = Vec::new();
+    let mut disks = Vec::new();
+    let mut options = options.iter();
+    while let Some(option) = options.next() {
+        let value = match options.next() {
+            Some(x) => x,
+            None => usage(),
+        };
+        let devices = &mut computer.devices;
+        match option.as_str() {
+            "--card-reader" => {
+                devices.card_reader = Some(TextInput::card_reader(read_lines(value)))
+            }
+            "--punch" => {
+                devices.card_punch = Some(TextOutput::card_punch().with_echo(create(value)))
+            }
+            "--printer" => {
+                devices.line_printer = Some(TextOutput::line_printer().with_echo(create(value)))
+            }
+            "--terminal" => {
+                let mut terminal = Terminal::new(read_lines(value));
+                terminal.output = terminal.output.with_echo(Box::new(io::stdout()));
+                devices.terminal = Some(terminal);
+            }
+            "--paper-tape" => devices.paper_tape = Some(TextInput::paper_tape(read_lines(value))),
+            "--tape" => {
+                let (unit, path) = unit_and_path(value, 0..=7);
+                let (tape, format) =
+                    TapeUnit::load_image(path).unwrap_or_else(|e| fail(format!("{}: {}", path, e)));
+                devices.tapes[unit] = Some(tape);
+                tapes.push((unit, path, format));
+            }
+            "--disk" => {
+                let (unit, path) = unit_and_path(value, 8..=15);
+                let (disk, format) =
+                    DiskUnit::load_image(path).unwrap_or_else(|e| fail(format!("{}: {}", path, e)));
+                devices.disks[unit - 8] = Some(disk);
+                disks.push((unit - 8, path, format));
+            }
+            "--limit" => limit = Some(value.parse().unwrap_or_else(|_| usage())),
+            "--profile" => {
+                // the listing is the source annotated, so an object file isn't enough
+                if path.ends_with(".mixo") {
+                    fail(format!("{}: --profile needs the MIXAL source", path));
+                }
+                computer.profile = Some(Profile::new());
+                profile = Some(value);
+            }
+            "--trace" => computer.trace = Some(Box::new(io::BufWriter::new(create(value)))),
+            "--interrupts" => {
+                computer.enable_interrupts();
+                let interrupts = computer.interrupts.as_mut().unwrap();
+                interrupts.io_time = value.parse().unwrap_or_else(|_| usage());
+            }
+            // already picked out by byte_size_option and friends
+            "--bytes" | "--include" | "--define" => (),
+            _ => usage(),
+        }
+    }
+    if computer.devices.terminal.is_none() {
+        let mut terminal = Terminal::new(Vec::new());
+        terminal.output = terminal.output.with_echo(Box::new(io::stdout()));
+        computer.devices.terminal = Some(terminal);
+    }
+    if let Err(e) = computer.load_program(&program) {
+        fail(format!("{}: {}", path, e));
+    }
+
+    let result = computer.run(limit);
+    // exiting doesn't drop the computer, so the trace has to be flushed here
+    if let Some(mut trace) = computer.trace.take() {
+        if let Err(e) = trace.flush() {
+            eprintln!("mix: trace: {}", e);
+        }
+    }
+    for (unit, path, format) in tapes {
+        let tape = computer.devices.tapes[unit].as_ref().unwrap();
+        if let Err(e) = tape.save_image(path, format) {
+            eprintln!("mix: {}: {}", path, e);
+        }
+    }
+    for (idx, path, format) in disks {
+        let disk = computer.devices.disks[idx].as_ref().unwrap();
+        if let Err(e) = disk.save_image(path, format) {
+            eprintln!("mix: {}: {}", path, e);
+        }
+    }
+    if let (Some(listing), Some(profile)) = (profile, &computer.profile) {
+        let source = fs::read_to_string(path).unwrap_or_else(|e| fail(format!("{}: {}", path, e)));
+        let written = fs::File::create(listing).and_then(|file| {
+            let mut writer = io::BufWriter::new(file);
+            profile.write_listing(&source, &program, &mut writer)?;
+            io::Write::flush(&mut writer)
+        });
+        if let Err(e) = written {
+            eprintln!("mix: {}: {}", listing, e);
+        }
+    }
+    let status = match &result {
+        Ok(StopReason::HALTED) => "halted".to_string(),
+        Ok(StopReason::LIMIT) => format!("stopped after {} instructions", limit.unwrap()),
+        Err(fault) => fault.to_string(),
+    };
+    eprintln!("status: {}", status);
+    eprintln!("instructions: {}", computer.instruction_count);
+    eprintln!("time: {}u", computer.time);
+    if !matches!(result, Ok(StopReason::HALTED)) {
+        process::exit(1);
+    }
+}
+
+// the machine has to be built with the right byte size before any other option can be applied
+fn byte_size_option(options: &[String]) -> Option<ByteSize> {
+    let idx = options.iter().position(|x| x == "--bytes")?;
+    let value = options.get(idx + 1).unwrap_or_else(|| usage());
+    Some(
+        value
+            .parse()
+            .ok()
+            .and_then(ByteSize::new)
+            .unwrap_or_else(|| usage()),
+    )
+}
+
+fn include_paths_option(options: &[String]) -> Vec<PathBuf> {
+    options
+        .chunks(2)
+        .filter(|x| x[0] == "--include")
+        .map(|x| PathBuf::from(x.get(1).unwrap_or_else(|| usage())))
+        .collect()
+}
+
+fn defines_option(options: &[String]) -> Vec<(String, i64)> {
+    options
+        .chunks(2)
+        .filter(|x| x[0] == "--define")
+        .map(|x| x.get(1).and_then(|x| assembler::parse_define(x)))
+        .map(|x| x.unwrap_or_else(|| usage()))
+        .collect()
+}
+
+// object files know their own byte size, source files are assembled for the one asked for
+fn load(path: &str, options: assembler::AssemblerOptions) -> Program {
+    if path.ends_with(".mixo") {
+        let program = Program::read_object_from_file(path)
+            .unwrap_or_else(|e| fail(format!("{}: {:?}", path, e)));
+        if program.linkage.is_some() {
+            fail(format!("{}: is a module, link it first", path));
+        }
+        return program;
+    }
+    let source = fs::read_to_string(path).unwrap_or_else(|e| fail(format!("{}: {}", path, e)));
+    let options = assembler::AssemblerOptions {
+        file_name: Some(path.to_string()),
+        ..options
+    };
+    assembler::assemble_with(&source, &options).unwrap_or_else(|e| fail(format!("{}: {}", path, e)))
+}
+
+fn read_lines(path: &str) -> Vec<String> {
+    fs::read_to_string(path)
+        .unwrap_or_else(|e| fail(format!("{}: {}", path, e)))
+        .lines()
+        .map(|x| x.to_string())
+        .collect()
+}
+
+fn create(path: &str) -> Box<dyn io::Write + Send> {
+    Box::new(fs::File::create(path).unwrap_or_else(|e| fail(format!("{}: {}", path, e))))
+}
+
+fn unit_and_path(value: &str, units: std::ops::RangeInclusive<usize>) -> (usize, &str) {
+    let (unit, path) = value.split_once('=').unwrap_or_else(|| usage());
+    match unit.parse() {
+        Ok(x) if units.contains(&x) => (x, path),
+        _ => usage(),
+    }
+}
+
+fn tape(args: &[String]) {
+    let args: Vec<&str> = args.iter().map(|x| x.as_str()).collect();
+    match args.as_slice() {
+        ["create", path, blocks, format @ ..] => {
+            let blocks = blocks.parse().unwrap_or_else(|_| usage());
+            let tape = TapeUnit::new(blocks);
+            save(&tape.data, output_format(format), path);
+        }
+        ["show", path] => show(path, None),
+        ["show", path, block] => show(path, Some(block.parse().unwrap_or_else(|_| usage()))),
+        ["convert", from, to, format @ ..] => {
+            let (blocks, _) = read(from);
+            save(&blocks, output_format(format), to);
+        }
+        _ => usage(),
+    }
+}
+
+fn output_format(flags: &[&str]) -> ImageFormat {
+    match flags {
+        [] => ImageFormat::BINARY,
+        ["--text"] => ImageFormat::TEXT,
+        ["--alf"] => ImageFormat::ALF,
+        _ => usage(),
+    }
+}
+
+fn read(path: &str) -> (Vec<image::Block>, ImageFormat) {
+    image::read_image_from_file(path).unwrap_or_else(|e| fail(format!("{}: {}", path, e)))
+}
+
+fn save(blocks: &[image::Block], format: ImageFormat, path: &str) {
+    image::write_image_to_file(blocks, format, path)
+        .unwrap_or_else(|e| fail(format!("{}: {}", path, e)));
+}
+
+// lists the words other than +0 in each block, or in just the one asked for
+fn show(path: &str, only: Option<usize>) {
+    let (blocks, format) = read(path);
+    let format = match format {
+        ImageFormat::BINARY => "binary",
+        _ => "text",
+    };
+    println!("{}: {} image, {} blocks", path, format, blocks.len());
+    if only.is_some_and(|x| x >= blocks.len()) {
+        fail(format!("{}: there is no block {}", path, only.unwrap()));
+    }
+    for (block_idx, block) in blocks.iter().enumerate() {
+        if only.is_some_and(|x| x != block_idx) {
+            continue;
+        }
+        for (word_idx, word) in block.iter().enumerate() {
+            if *word == Word::ZERO {
+                continue;
+            }
+            println!(
+                "{:5} {:3}  {:<17} {:>11}  {}",
+                block_idx,
+                word_idx,
+                word.to_byte_string(),
+                image::format_word(word),
+                image::alf_text(word).unwrap_or_default()
+            );
+        }
+    }
+}
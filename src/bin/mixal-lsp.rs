@@ -0,0 +1,32 @@
+use mix_emu::language_server::{self, Server};
+use std::{io, process};
+
+// The MIXAL language server, which editors start themselves and talk to over stdin and stdout.
+fn main() {
+    if std::env::args().len() > 1 {
+        eprintln!("USAGE: mixal-lsp\n  speaks the Language Server Protocol on stdin and stdout");
+        process::exit(2);
+    }
+    let mut server = Server::new();
+    let mut input = io::stdin().lock();
+    let mut output = io::stdout().lock();
+    loop {
+        let message = match language_server::read_message(&mut input) {
+            Ok(Some(x)) => x,
+            // the editor went away without asking us to exit
+            Ok(None) => process::exit(1),
+            Err(e) => fail(e),
+        };
+        for reply in server.handle(&message) {
+            language_server::write_message(&mut output, &reply).unwrap_or_else(|e| fail(e));
+        }
+        if let Some(code) = server.exit {
+            process::exit(code);
+        }
+    }
+}
+
+fn fail(e: io::Error) -> ! {
+    eprintln!("mixal-lsp: {}", e);
+    process::exit(1);
+}
@@ -1,9 +1,26 @@
+pub mod devices;
+pub mod floating_point;
+pub mod interrupts;
 pub mod memory;
+pub mod profile;
 pub mod registers;
-
-use crate::data_types::{Byte, FieldSpecification, Index, JumpAddress, Sign, Word};
-
-use self::{memory::Memory, registers::Registers};
+pub mod snapshot;
+
+use crate::{
+    data_types::{Byte, ByteSize, FieldSpecification, Index, JumpAddress, Sign, Word},
+    disassembler,
+    object::Program,
+    opcodes::{self, Op, OpCode, OperandKind},
+    source_map::{SourceLocation, SourceMap},
+};
+
+use self::{
+    devices::{DeviceError, Devices, TapeUnit, TextOutput},
+    interrupts::Interrupts,
+    memory::{Memory, SetError},
+    profile::Profile,
+    registers::Registers,
+};
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum ComparisonIndicatorState {
@@ -12,67 +29,69 @@ pub enum ComparisonIndicatorState {
     LESS,
 }
 
-pub struct TapeUnit {
-    pub ready: bool,
-    pub capacity: usize,
-    pub data: Vec<[Word; 100]>,
+// Something went wrong that MIX itself has no way to report, so the machine stops
+#[derive(Debug, PartialEq, Eq)]
+pub enum FaultKind {
+    InvalidAddress(i32),
+    InvalidInstruction(Word),
+    // an index register was set to something that doesn't fit in two bytes
+    IndexOverflow(i64),
+    Device(i32, DeviceError),
 }
 
-impl TapeUnit {
-    pub fn block_size(&self) -> usize {
-        100
-    }
-
-    pub fn capacity(&self) -> usize {
-        self.capacity
-    }
-
-    fn is_position_valid(&self, pos: usize) -> bool {
-        pos < self.capacity()
-    }
+#[derive(Debug, PartialEq, Eq)]
+pub struct Fault {
+    // location of the instruction that faulted
+    pub address: i32,
+    // where that instruction came from, when the program had a source map
+    pub source: Option<SourceLocation>,
+    pub kind: FaultKind,
+}
 
-    pub fn new(capacity: usize) -> Self {
-        Self {
-            ready: true,
-            capacity,
-            data: vec![[Word::ZERO; 100]; capacity],
+impl std::fmt::Display for Fault {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.source {
+            Some(source) => write!(f, "fault at {} ({}): ", self.address, source)?,
+            None => write!(f, "fault at {}: ", self.address)?,
         }
-    }
-
-    pub fn read(&self, position: i32) -> [Word; 100] {
-        let position: usize = position.try_into().unwrap();
-        if !self.is_position_valid(position) {
-            panic!("invalid read position");
+        match &self.kind {
+            FaultKind::InvalidAddress(x) => write!(f, "invalid address {}", x),
+            FaultKind::InvalidInstruction(x) => {
+                write!(f, "invalid instruction {}", x.to_byte_string())
+            }
+            FaultKind::IndexOverflow(x) => write!(f, "index register overflow to {}", x),
+            FaultKind::Device(unit, e) => write!(f, "unit {}: {}", unit, e),
         }
-        self.data[position]
     }
+}
 
-    pub fn write(&mut self, position: i32, words: [Word; 100]) {
-        let position: usize = position.try_into().unwrap();
-        if !self.is_position_valid(position) {
-            panic!("invalid write position");
-        }
-        self.data[position] = words;
-    }
+#[derive(Debug, PartialEq, Eq)]
+pub enum StopReason {
+    HALTED,
+    LIMIT,
 }
 
-pub struct LinePrinter {}
+#[derive(Debug, PartialEq, Eq)]
+pub enum LoadError {
+    InvalidAddress(i32),
+    // the program was assembled for bytes of this size, which the machine doesn't have
+    ByteSize(ByteSize),
+}
 
-impl LinePrinter {
-    pub fn new() -> Self {
-        Self {}
+impl From<SetError> for LoadError {
+    fn from(value: SetError) -> Self {
+        match value {
+            SetError::InvalidAddress(x) => Self::InvalidAddress(x),
+        }
     }
+}
 
-    pub fn write(&self, words: [Word; 24]) {
-        let mut line = String::new();
-        for word in words {
-            line.push(word.bytes.0.to_char().unwrap());
-            line.push(word.bytes.1.to_char().unwrap());
-            line.push(word.bytes.2.to_char().unwrap());
-            line.push(word.bytes.3.to_char().unwrap());
-            line.push(word.bytes.4.to_char().unwrap());
+impl std::fmt::Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidAddress(x) => write!(f, "location {} is outside memory", x),
+            Self::ByteSize(x) => write!(f, "assembled for {} values per byte", x.values()),
         }
-        println!("{}", line);
     }
 }
 
@@ -83,12 +102,36 @@ pub struct Computer {
     pub overflow: bool,
     pub comparison_indicator: Option<ComparisonIndicatorState>,
     pub running: bool,
-    pub tape_unit: TapeUnit,
-    pub line_printer: LinePrinter,
+    pub devices: Devices,
+    // set when an instruction faults, which also stops the machine
+    pub fault: Option<Fault>,
+    pub instruction_count: u64,
+    // total execution time in units of u
+    pub time: u64,
+    // fixed when the machine is built, the same program should behave the same with either size
+    pub byte_size: ByteSize,
+    // counts and times for each address when profiling, which is off unless one is attached
+    pub profile: Option<Profile>,
+    // from the last program loaded, for describing addresses in faults and traces
+    pub source_map: SourceMap,
+    // each instruction is written here before it runs
+    pub trace: Option<Box<dyn std::io::Write + Send>>,
+    // the interrupt facility, which is off unless enable_interrupts is called
+    pub interrupts: Option<Interrupts>,
+    instruction_address: i32,
 }
 
 impl Computer {
+    // tape unit 0 and a line printer writing to stdout are attached, anything else is up to the caller
     pub fn new() -> Self {
+        Self::with_byte_size(ByteSize::BINARY)
+    }
+
+    pub fn with_byte_size(byte_size: ByteSize) -> Self {
+        let mut devices = Devices::new();
+        devices.tapes[0] = Some(TapeUnit::new(1000));
+        devices.line_printer =
+            Some(TextOutput::line_printer().with_echo(Box::new(std::io::stdout())));
         Self {
             current_instruction_address: 0,
             registers: Registers::new(),
@@ -96,8 +139,42 @@ impl Computer {
             comparison_indicator: None,
             memory: Memory::ZERO,
             running: false,
-            tape_unit: TapeUnit::new(1000),
-            line_printer: LinePrinter::new(),
+            devices,
+            fault: None,
+            instruction_count: 0,
+            time: 0,
+            byte_size,
+            profile: None,
+            source_map: SourceMap::default(),
+            trace: None,
+            interrupts: None,
+            instruction_address: 0,
+        }
+    }
+
+    // a program for a machine with interrupts can have words in the negative locations, and starts
+    // in control state when it starts at one of them. Its words only mean the same thing on a
+    // machine with the byte size it was assembled for.
+    pub fn load_program(&mut self, program: &Program) -> Result<(), LoadError> {
+        if program.byte_size != self.byte_size {
+            return Err(LoadError::ByteSize(program.byte_size));
+        }
+        for (address, word) in program.words.iter() {
+            self.memory.set_in_control_state(*address, *word)?;
+        }
+        if let Some(start) = program.start {
+            self.current_instruction_address = start;
+            self.memory.control_state = self.interrupts.is_some() && start < 0;
+        }
+        self.source_map = program.source_map.clone();
+        Ok(())
+    }
+
+    // an address the way the source has it, e.g. primes.mixal:18 LOOP+2, or just the number
+    pub fn describe_address(&self, address: i32) -> String {
+        match self.source_map.locate(address) {
+            Some(x) => x.to_string(),
+            None => address.to_string(),
         }
     }
 
@@ -113,149 +190,367 @@ impl Computer {
         if !self.running {
             return;
         }
-        let current_instruction = self.memory.get(self.current_instruction_address).unwrap();
+        if self.interrupts.is_some() {
+            self.check_interrupts();
+        }
+        self.instruction_address = self.current_instruction_address;
+        let current_instruction = match self.memory.get(self.current_instruction_address) {
+            Ok(x) => x,
+            Err(_) => {
+                self.raise(FaultKind::InvalidAddress(self.current_instruction_address));
+                return;
+            }
+        };
         self.current_instruction_address += 1;
+        if self.trace.is_some() {
+            self.write_trace(current_instruction);
+        }
+        let (count, time) = (self.instruction_count, self.time);
         self.handle_instruction(current_instruction);
+        // instructions that couldn't be decoded didn't run
+        if let Some(profile) = &mut self.profile {
+            if self.instruction_count > count {
+                profile.record(self.instruction_address, self.time - time);
+            }
+        }
+        if self.interrupts.is_some() {
+            self.count_down_timer(self.time - time);
+        }
     }
 
-    pub fn handle_instruction(&mut self, instruction: Word) {
+    // An operand the instruction can't be carried out with, which stops the machine before it runs
+    // rather than part way through: an index byte above 6, a field that isn't (L:R) with
+    // L <= R <= 5, a memory operand outside memory, a negative shift count or an index register
+    // that wouldn't fit its new value.
+    fn operand_fault(&self, opcode: &OpCode, instruction: Word) -> Option<FaultKind> {
+        if instruction.index() > 6 {
+            return Some(FaultKind::InvalidInstruction(instruction));
+        }
+        let reads_memory = match opcode.op {
+            Op::NOP => false,
+            Op::FADD | Op::FSUB | Op::FMUL | Op::FDIV | Op::FCMP => true,
+            _ => opcode.operand == OperandKind::FIELD,
+        };
+        if reads_memory {
+            if opcode.operand == OperandKind::FIELD && !instruction.field().is_valid() {
+                return Some(FaultKind::InvalidInstruction(instruction));
+            }
+            let address = self.modified_address(instruction);
+            if self.memory.get(address).is_err() {
+                return Some(FaultKind::InvalidAddress(address));
+            }
+        }
+        let shift = matches!(
+            opcode.op,
+            Op::SLA | Op::SRA | Op::SLAX | Op::SRAX | Op::SLC | Op::SRC | Op::SLB | Op::SRB
+        );
+        if shift && self.modified_address(instruction) < 0 {
+            return Some(FaultKind::InvalidInstruction(instruction));
+        }
+        let limit = i64::from(self.byte_size.values()).pow(2);
+        match self.index_result(instruction) {
+            Some(x) if x.abs() >= limit => Some(FaultKind::IndexOverflow(x)),
+            _ => None,
+        }
+    }
+
+    // what LDi, LDiN, ENTi, ENNi, INCi or DECi would leave in the index register, which Knuth
+    // leaves undefined when it takes more than two bytes
+    fn index_result(&self, instruction: Word) -> Option<i64> {
+        let address = self.modified_address(instruction);
         match instruction.code() {
-            0 => (),
-            1 => self.add(instruction),
-            2 => self.sub(instruction),
-            3 => self.mul(instruction),
-            4 => self.div(instruction),
-            5 => self.handle_5(instruction),
-            6 => self.handle_6(instruction),
-            7 => self.mov(instruction),
-            8 => self.lda(instruction),
-            9 => self.ld1(instruction),
-            10 => self.ld2(instruction),
-            11 => self.ld3(instruction),
-            12 => self.ld4(instruction),
-            13 => self.ld5(instruction),
-            14 => self.ld6(instruction),
-            15 => self.ldx(instruction),
-            16 => self.ldan(instruction),
-            17 => self.ld1n(instruction),
-            18 => self.ld2n(instruction),
-            19 => self.ld3n(instruction),
-            20 => self.ld4n(instruction),
-            21 => self.ld5n(instruction),
-            22 => self.ld6n(instruction),
-            23 => self.ldxn(instruction),
-            24 => self.sta(instruction),
-            25 => self.st1(instruction),
-            26 => self.st2(instruction),
-            27 => self.st3(instruction),
-            28 => self.st4(instruction),
-            29 => self.st5(instruction),
-            30 => self.st6(instruction),
-            31 => self.stx(instruction),
-            32 => self.stj(instruction),
-            33 => self.stz(instruction),
-            34 => self.jbus(instruction),
-            35 => self.ioc(instruction),
-            36 => self.input(instruction),
-            37 => self.output(instruction),
-            38 => self.jred(instruction),
-            39 => self.handle_39(instruction),
-            40 => self.handle_40(instruction),
-            41 => self.handle_41(instruction),
-            42 => self.handle_42(instruction),
-            43 => self.handle_43(instruction),
-            44 => self.handle_44(instruction),
-            45 => self.handle_45(instruction),
-            46 => self.handle_46(instruction),
-            47 => self.handle_47(instruction),
-            48 => self.handle_48(instruction),
-            49 => self.handle_49(instruction),
-            50 => self.handle_50(instruction),
-            51 => self.handle_51(instruction),
-            52 => self.handle_52(instruction),
-            53 => self.handle_53(instruction),
-            54 => self.handle_54(instruction),
-            55 => self.handle_55(instruction),
-            56 => self.cmpa(instruction),
-            57 => self.cmp1(instruction),
-            58 => self.cmp2(instruction),
-            59 => self.cmp3(instruction),
-            60 => self.cmp4(instruction),
-            61 => self.cmp5(instruction),
-            62 => self.cmp6(instruction),
-            63 => self.cmpx(instruction),
-            _ => panic!("Invalid instruction code"),
+            9..=14 | 17..=22 => {
+                let contents = self.memory.get(address).ok()?;
+                Some(
+                    contents
+                        .get_field(instruction.field())
+                        .to_i64_in(self.byte_size),
+                )
+            }
+            code @ 49..=54 => {
+                let register = i64::from(self.index_register(code - 48).to_i32_in(self.byte_size));
+                let address = i64::from(address);
+                match instruction.field().value() {
+                    0 => Some(register + address),
+                    1 => Some(register - address),
+                    2 | 3 => Some(address),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    // INT needs the interrupt facility and some instructions only exist on binary MIX
+    fn has_instruction(&self, op: Op) -> bool {
+        match op {
+            Op::INT => self.interrupts.is_some(),
+            _ => !op.is_binary_only() || self.byte_size == ByteSize::BINARY,
+        }
+    }
+
+    // runs until HLT, a fault, or limit instructions have been executed
+    pub fn run(&mut self, limit: Option<u64>) -> Result<StopReason, Fault> {
+        self.running = true;
+        let mut executed = 0;
+        while self.running {
+            if limit.is_some_and(|x| executed >= x) {
+                return Ok(StopReason::LIMIT);
+            }
+            self.handle_next_instruction();
+            executed += 1;
+            if let Some(fault) = self.fault.take() {
+                return Err(fault);
+            }
+        }
+        Ok(StopReason::HALTED)
+    }
+
+    fn raise(&mut self, kind: FaultKind) {
+        self.running = false;
+        self.fault = Some(Fault {
+            address: self.instruction_address,
+            source: self.source_map.locate(self.instruction_address),
+            kind,
+        });
+    }
+
+    // 3008 primes.mixal:16 START+8: ENTA 0
+    fn write_trace(&mut self, instruction: Word) {
+        let mut line = format!("{:>4}", self.instruction_address);
+        if let Some(source) = self.source_map.locate(self.instruction_address) {
+            line += &format!(" {}", source);
+        }
+        line += &format!(
+            ": {}",
+            disassembler::disassemble_in(instruction, self.byte_size)
+        );
+        let trace = self.trace.as_mut().unwrap();
+        // a trace that can't be written isn't worth stopping the program for
+        if writeln!(trace, "{}", line).is_err() {
+            self.trace = None;
+        }
+    }
+
+    pub fn handle_instruction(&mut self, instruction: Word) {
+        let opcode = match opcodes::decode(instruction) {
+            Some(x) if self.has_instruction(x.op) => x,
+            _ => {
+                self.raise(FaultKind::InvalidInstruction(instruction));
+                return;
+            }
+        };
+        if let Some(kind) = self.operand_fault(opcode, instruction) {
+            self.raise(kind);
+            return;
+        }
+        self.instruction_count += 1;
+        self.time += opcode.time as u64;
+        if opcode.op == Op::MOVE {
+            self.time += 2 * instruction.field().value() as u64;
+        }
+        match opcode.op {
+            Op::NOP => (),
+            Op::ADD => self.add(instruction),
+            Op::FADD => self.fadd(instruction),
+            Op::SUB => self.sub(instruction),
+            Op::FSUB => self.fsub(instruction),
+            Op::MUL => self.mul(instruction),
+            Op::FMUL => self.fmul(instruction),
+            Op::DIV => self.div(instruction),
+            Op::FDIV => self.fdiv(instruction),
+            Op::NUM => self.num(),
+            Op::CHAR => self.char(),
+            Op::HLT => self.hlt(),
+            Op::FLOT => self.flot(),
+            Op::FIX => self.fix(),
+            Op::INT => self.int(),
+            Op::SLA => self.sla(instruction),
+            Op::SRA => self.sra(instruction),
+            Op::SLAX => self.slax(instruction),
+            Op::SRAX => self.srax(instruction),
+            Op::SLC => self.slc(instruction),
+            Op::SRC => self.src(instruction),
+            Op::SLB => self.slb(instruction),
+            Op::SRB => self.srb(instruction),
+            Op::MOVE => self.mov(instruction),
+            Op::LDA => self.lda(instruction),
+            Op::LD1 => self.ld1(instruction),
+            Op::LD2 => self.ld2(instruction),
+            Op::LD3 => self.ld3(instruction),
+            Op::LD4 => self.ld4(instruction),
+            Op::LD5 => self.ld5(instruction),
+            Op::LD6 => self.ld6(instruction),
+            Op::LDX => self.ldx(instruction),
+            Op::LDAN => self.ldan(instruction),
+            Op::LD1N => self.ld1n(instruction),
+            Op::LD2N => self.ld2n(instruction),
+            Op::LD3N => self.ld3n(instruction),
+            Op::LD4N => self.ld4n(instruction),
+            Op::LD5N => self.ld5n(instruction),
+            Op::LD6N => self.ld6n(instruction),
+            Op::LDXN => self.ldxn(instruction),
+            Op::STA => self.sta(instruction),
+            Op::ST1 => self.st1(instruction),
+            Op::ST2 => self.st2(instruction),
+            Op::ST3 => self.st3(instruction),
+            Op::ST4 => self.st4(instruction),
+            Op::ST5 => self.st5(instruction),
+            Op::ST6 => self.st6(instruction),
+            Op::STX => self.stx(instruction),
+            Op::STJ => self.stj(instruction),
+            Op::STZ => self.stz(instruction),
+            Op::JBUS => self.jbus(instruction),
+            Op::IOC => self.ioc(instruction),
+            Op::IN => self.input(instruction),
+            Op::OUT => self.output(instruction),
+            Op::JRED => self.jred(instruction),
+            Op::JMP => self.jmp(instruction),
+            Op::JSJ => self.jsj(instruction),
+            Op::JOV => self.jov(instruction),
+            Op::JNOV => self.jnov(instruction),
+            Op::JL => self.jl(instruction),
+            Op::JE => self.je(instruction),
+            Op::JG => self.jg(instruction),
+            Op::JGE => self.jge(instruction),
+            Op::JNE => self.jne(instruction),
+            Op::JLE => self.jle(instruction),
+            Op::JAN => self.jan(instruction),
+            Op::JAZ => self.jaz(instruction),
+            Op::JAP => self.jap(instruction),
+            Op::JANN => self.jann(instruction),
+            Op::JANZ => self.janz(instruction),
+            Op::JANP => self.janp(instruction),
+            Op::JAE => self.jae(instruction),
+            Op::JAO => self.jao(instruction),
+            Op::J1N => self.j1n(instruction),
+            Op::J1Z => self.j1z(instruction),
+            Op::J1P => self.j1p(instruction),
+            Op::J1NN => self.j1nn(instruction),
+            Op::J1NZ => self.j1nz(instruction),
+            Op::J1NP => self.j1np(instruction),
+            Op::J2N => self.j2n(instruction),
+            Op::J2Z => self.j2z(instruction),
+            Op::J2P => self.j2p(instruction),
+            Op::J2NN => self.j2nn(instruction),
+            Op::J2NZ => self.j2nz(instruction),
+            Op::J2NP => self.j2np(instruction),
+            Op::J3N => self.j3n(instruction),
+            Op::J3Z => self.j3z(instruction),
+            Op::J3P => self.j3p(instruction),
+            Op::J3NN => self.j3nn(instruction),
+            Op::J3NZ => self.j3nz(instruction),
+            Op::J3NP => self.j3np(instruction),
+            Op::J4N => self.j4n(instruction),
+            Op::J4Z => self.j4z(instruction),
+            Op::J4P => self.j4p(instruction),
+            Op::J4NN => self.j4nn(instruction),
+            Op::J4NZ => self.j4nz(instruction),
+            Op::J4NP => self.j4np(instruction),
+            Op::J5N => self.j5n(instruction),
+            Op::J5Z => self.j5z(instruction),
+            Op::J5P => self.j5p(instruction),
+            Op::J5NN => self.j5nn(instruction),
+            Op::J5NZ => self.j5nz(instruction),
+            Op::J5NP => self.j5np(instruction),
+            Op::J6N => self.j6n(instruction),
+            Op::J6Z => self.j6z(instruction),
+            Op::J6P => self.j6p(instruction),
+            Op::J6NN => self.j6nn(instruction),
+            Op::J6NZ => self.j6nz(instruction),
+            Op::J6NP => self.j6np(instruction),
+            Op::JXN => self.jxn(instruction),
+            Op::JXZ => self.jxz(instruction),
+            Op::JXP => self.jxp(instruction),
+            Op::JXNN => self.jxnn(instruction),
+            Op::JXNZ => self.jxnz(instruction),
+            Op::JXNP => self.jxnp(instruction),
+            Op::JXE => self.jxe(instruction),
+            Op::JXO => self.jxo(instruction),
+            Op::INCA => self.inca(instruction),
+            Op::DECA => self.deca(instruction),
+            Op::ENTA => self.enta(instruction),
+            Op::ENNA => self.enna(instruction),
+            Op::INC1 => self.inc1(instruction),
+            Op::DEC1 => self.dec1(instruction),
+            Op::ENT1 => self.ent1(instruction),
+            Op::ENN1 => self.enn1(instruction),
+            Op::INC2 => self.inc2(instruction),
+            Op::DEC2 => self.dec2(instruction),
+            Op::ENT2 => self.ent2(instruction),
+            Op::ENN2 => self.enn2(instruction),
+            Op::INC3 => self.inc3(instruction),
+            Op::DEC3 => self.dec3(instruction),
+            Op::ENT3 => self.ent3(instruction),
+            Op::ENN3 => self.enn3(instruction),
+            Op::INC4 => self.inc4(instruction),
+            Op::DEC4 => self.dec4(instruction),
+            Op::ENT4 => self.ent4(instruction),
+            Op::ENN4 => self.enn4(instruction),
+            Op::INC5 => self.inc5(instruction),
+            Op::DEC5 => self.dec5(instruction),
+            Op::ENT5 => self.ent5(instruction),
+            Op::ENN5 => self.enn5(instruction),
+            Op::INC6 => self.inc6(instruction),
+            Op::DEC6 => self.dec6(instruction),
+            Op::ENT6 => self.ent6(instruction),
+            Op::ENN6 => self.enn6(instruction),
+            Op::INCX => self.incx(instruction),
+            Op::DECX => self.decx(instruction),
+            Op::ENTX => self.entx(instruction),
+            Op::ENNX => self.ennx(instruction),
+            Op::CMPA => self.cmpa(instruction),
+            Op::FCMP => self.fcmp(instruction),
+            Op::CMP1 => self.cmp1(instruction),
+            Op::CMP2 => self.cmp2(instruction),
+            Op::CMP3 => self.cmp3(instruction),
+            Op::CMP4 => self.cmp4(instruction),
+            Op::CMP5 => self.cmp5(instruction),
+            Op::CMP6 => self.cmp6(instruction),
+            Op::CMPX => self.cmpx(instruction),
         }
     }
 
     fn modified_address(&self, instruction: Word) -> i32 {
         let index_modifier = match instruction.index() {
             0 => 0,
-            1 => self.registers.i1.to_i32(),
-            2 => self.registers.i2.to_i32(),
-            3 => self.registers.i3.to_i32(),
-            4 => self.registers.i4.to_i32(),
-            5 => self.registers.i5.to_i32(),
-            6 => self.registers.i6.to_i32(),
-            _ => panic!("Invalid index"),
+            x => self.index_register(x).to_i32_in(self.byte_size),
         };
-        instruction.address() + index_modifier
+        instruction.address_in(self.byte_size) + index_modifier
     }
 
-    fn sign_to_load_or_store(
-        field_specifier: &FieldSpecification,
-        contents: Word,
-        original_value: Sign,
-    ) -> Sign {
-        match field_specifier.inclusive_range().contains(&0) {
-            true => contents.sign,
-            false => original_value,
+    fn index_register(&self, number: i32) -> Index {
+        match number {
+            1 => self.registers.i1,
+            2 => self.registers.i2,
+            3 => self.registers.i3,
+            4 => self.registers.i4,
+            5 => self.registers.i5,
+            6 => self.registers.i6,
+            _ => panic!("Invalid index"),
         }
     }
 
-    fn bytes_to_load_word(
-        field_specifier: &FieldSpecification,
-        contents: Word,
-    ) -> (Byte, Byte, Byte, Byte, Byte) {
-        let mut bytes_to_load = Vec::<Byte>::new();
-        match field_specifier.inclusive_range().contains(&1) {
-            true => bytes_to_load.push(contents.bytes.0),
-            false => bytes_to_load.insert(0, Byte::ZERO),
-        };
-        match field_specifier.inclusive_range().contains(&2) {
-            true => bytes_to_load.push(contents.bytes.1),
-            false => bytes_to_load.insert(0, Byte::ZERO),
-        };
-        match field_specifier.inclusive_range().contains(&3) {
-            true => bytes_to_load.push(contents.bytes.2),
-            false => bytes_to_load.insert(0, Byte::ZERO),
-        };
-        match field_specifier.inclusive_range().contains(&4) {
-            true => bytes_to_load.push(contents.bytes.3),
-            false => bytes_to_load.insert(0, Byte::ZERO),
-        };
-        match field_specifier.inclusive_range().contains(&5) {
-            true => bytes_to_load.push(contents.bytes.4),
-            false => bytes_to_load.insert(0, Byte::ZERO),
-        };
-        (
-            bytes_to_load[0],
-            bytes_to_load[1],
-            bytes_to_load[2],
-            bytes_to_load[3],
-            bytes_to_load[4],
+    // index registers act like words with the first three bytes zero
+    fn index_to_word(index: &Index) -> Word {
+        Word::from_byte_array(
+            index.sign,
+            [
+                Byte::ZERO,
+                Byte::ZERO,
+                Byte::ZERO,
+                index.bytes.0,
+                index.bytes.1,
+            ],
         )
     }
 
-    fn bytes_to_load_index(field_specifier: &FieldSpecification, contents: Word) -> (Byte, Byte) {
-        let bytes_for_word = Self::bytes_to_load_word(field_specifier, contents);
-        if bytes_for_word.0 != Byte::ZERO
-            || bytes_for_word.1 != Byte::ZERO
-            || bytes_for_word.2 != Byte::ZERO
-        {
-            panic!("Attempting to load too many bytes into index register");
+    // operand_fault has stopped the machine if the field's value takes more than two bytes
+    fn word_to_index(word: Word) -> Index {
+        Index {
+            sign: word.sign,
+            bytes: (word.bytes.3, word.bytes.4),
         }
-        (bytes_for_word.3, bytes_for_word.4)
     }
 
     fn field_specifier_and_contents(&self, instruction: Word) -> (FieldSpecification, Word) {
@@ -268,123 +563,81 @@ impl Computer {
         (field_specifier, contents)
     }
 
-    fn get_v(&self, instruction: Word) -> i32 {
+    // V, the field of the word at M that an instruction works on
+    fn get_v(&self, instruction: Word) -> Word {
         let (field_specifier, contents) = self.field_specifier_and_contents(instruction);
-        let needed_bytes = Self::bytes_to_load_word(&field_specifier, contents);
-        contents.sign.value()
-            * (needed_bytes.0.to_i32() * 64_i32.pow(4)
-                + needed_bytes.1.to_i32() * 64_i32.pow(3)
-                + needed_bytes.2.to_i32() * 64_i32.pow(2)
-                + needed_bytes.3.to_i32() * 64
-                + needed_bytes.4.to_i32())
+        contents.get_field(field_specifier)
     }
 
-    fn add(&mut self, instruction: Word) {
-        let result = self.registers.a.to_i32() + self.get_v(instruction);
-        if result == 0 {
-            self.registers.a.bytes = (Byte::ZERO, Byte::ZERO, Byte::ZERO, Byte::ZERO, Byte::ZERO);
-            return;
-        }
-        if result / 64_i32.pow(5) != 0 {
+    // the value of a register after adding to it: a zero result keeps the register's sign, and one
+    // that doesn't fit sets overflow and keeps only the low five bytes
+    fn register_sum(&mut self, register: Word, value: Word) -> Word {
+        let (result, overflow) = register.overflowing_add_in(value, self.byte_size);
+        if overflow {
             self.overflow = true;
         }
-        self.registers.a = Word::from_i32(result % 64_i32.pow(5)).unwrap();
+        result
+    }
+
+    fn add(&mut self, instruction: Word) {
+        let v = self.get_v(instruction);
+        self.registers.a = self.register_sum(self.registers.a, v);
     }
 
     fn sub(&mut self, instruction: Word) {
-        let result = self.registers.a.to_i32() - self.get_v(instruction);
-        if result == 0 {
-            self.registers.a.bytes = (Byte::ZERO, Byte::ZERO, Byte::ZERO, Byte::ZERO, Byte::ZERO);
-            return;
-        }
-        if result / 64_i32.pow(5) != 0 {
-            self.overflow = true;
-        }
-        self.registers.a = Word::from_i32(result % 64_i32.pow(5)).unwrap();
+        let v = self.get_v(instruction);
+        self.registers.a = self.register_sum(self.registers.a, -v);
     }
 
-    fn mul(&mut self, instruction: Word) {
-        // Word::MAX * Word::MAX too large to store in i32
-        let result: i64 = <i32 as Into<i64>>::into(self.registers.a.to_i32())
-            * <i32 as Into<i64>>::into(self.get_v(instruction));
-        let (_, contents) = self.field_specifier_and_contents(instruction);
-        let result_sign = match self.registers.a.sign == contents.sign {
+    // the sign of a product or quotient, which zero has too
+    fn product_sign(u: Sign, v: Sign) -> Sign {
+        match u == v {
             true => Sign::PLUS,
             false => Sign::MINUS,
-        };
-        let x_value = result.abs() % 64_i64.pow(5);
-        let full_a_value = result.abs() / 64_i64.pow(5);
-        let a_value = full_a_value % 64_i64.pow(5);
+        }
+    }
+
+    fn mul(&mut self, instruction: Word) {
+        let v = self.get_v(instruction);
+        // a decimal Word::MAX squared doesn't even fit in i64
+        let result = self.registers.a.to_i64_in(self.byte_size).unsigned_abs() as u128
+            * v.to_i64_in(self.byte_size).unsigned_abs() as u128;
+        let result_sign = Self::product_sign(self.registers.a.sign, v.sign);
+        let limit = self.byte_size.capacity(5) as u128;
         // multiplication cannot overflow, consider the largest possible absolute value
-        // (64^5-1)*(64^5-1) < 64^10-1
+        // (b^5-1)*(b^5-1) < b^10-1
         // so result can always fit in 10 bytes!
-        self.registers.a = Word::from_i32(a_value.try_into().unwrap())
+        let x_value = result % limit;
+        let a_value = result / limit;
+        self.registers.a = Word::from_i64_in(a_value as i64, self.byte_size)
             .unwrap()
             .with_sign(result_sign);
-        self.registers.x = Word::from_i32(x_value.try_into().unwrap())
+        self.registers.x = Word::from_i64_in(x_value as i64, self.byte_size)
             .unwrap()
             .with_sign(result_sign);
     }
 
     fn div(&mut self, instruction: Word) {
-        let numerator: i64 = <i32 as Into<i64>>::into(self.registers.a.to_i32()) * 64_i64.pow(5)
-            + <i32 as Into<i64>>::into(self.registers.x.to_i32());
         let v = self.get_v(instruction);
-        if v == 0 {
+        let divisor = v.to_i64_in(self.byte_size).unsigned_abs() as u128;
+        let limit = self.byte_size.capacity(5) as u128;
+        // rAX is a ten byte number with the sign of rA, rX's own sign doesn't come into it
+        let a_magnitude = self.registers.a.to_i64_in(self.byte_size).unsigned_abs() as u128;
+        let x_magnitude = self.registers.x.to_i64_in(self.byte_size).unsigned_abs() as u128;
+        // this covers dividing by zero too
+        if a_magnitude >= divisor {
             self.overflow = true;
             return;
         }
-
-        let x_sign = self.registers.a.sign;
-        let (_, contents) = self.field_specifier_and_contents(instruction);
-        let a_sign = match self.registers.a.sign == contents.sign {
-            true => Sign::PLUS,
-            false => Sign::MINUS,
-        };
-
-        let a_result_value: i32 = match (numerator / <i32 as Into<i64>>::into(v)).try_into() {
-            Ok(x) => x,
-            Err(_) => {
-                self.overflow = true;
-                return;
-            }
-        };
-        let a_result_word = match Word::from_i32(a_result_value) {
-            Ok(x) => x,
-            Err(_) => {
-                self.overflow = true;
-                return;
-            }
-        }
-        .with_sign(a_sign);
-
-        let x_result_value: i32 = match (numerator % <i32 as Into<i64>>::into(v)).try_into() {
-            Ok(x) => x,
-            Err(_) => {
-                self.overflow = true;
-                return;
-            }
-        };
-        let x_result_word = match Word::from_i32(x_result_value) {
-            Ok(x) => x,
-            Err(_) => {
-                self.overflow = true;
-                return;
-            }
-        }
-        .with_sign(x_sign);
-
-        self.registers.x = x_result_word;
-        self.registers.a = a_result_word;
-    }
-
-    fn handle_5(&mut self, instruction: Word) {
-        match instruction.field().value() {
-            0 => self.num(),
-            1 => self.char(),
-            2 => self.hlt(),
-            _ => panic!("illegal field in code 5 instruction"),
-        }
+        let numerator = a_magnitude * limit + x_magnitude;
+        let quotient = Word::from_i64_in((numerator / divisor) as i64, self.byte_size)
+            .unwrap()
+            .with_sign(Self::product_sign(self.registers.a.sign, v.sign));
+        let remainder = Word::from_i64_in((numerator % divisor) as i64, self.byte_size)
+            .unwrap()
+            .with_sign(self.registers.a.sign);
+        self.registers.a = quotient;
+        self.registers.x = remainder;
     }
 
     fn num(&mut self) {
@@ -401,59 +654,112 @@ impl Computer {
             self.registers.x.bytes.3,
             self.registers.x.bytes.4,
         ];
-        let mut value = 0;
+        let mut value: i64 = 0;
         for byte in bytes {
-            value = value * 10 + byte.to_i32() % 10;
+            value = value * 10 + byte.to_i32() as i64 % 10;
         }
-        self.registers.a = Word::from_i32(value % 64_i32.pow(5))
+        self.registers.a = Word::from_i64_in(value % self.byte_size.capacity(5), self.byte_size)
             .unwrap()
             .with_sign(sign);
     }
 
     fn char(&mut self) {
-        let mut value: i64 = self.registers.a.to_i32().abs().into();
+        let mut value = self.registers.a.to_i64_in(self.byte_size).abs();
         self.registers.x.bytes.4 = Byte::from_i32((value % 10 + 30).try_into().unwrap()).unwrap();
-        value = value / 10;
+        value /= 10;
         self.registers.x.bytes.3 = Byte::from_i32((value % 10 + 30).try_into().unwrap()).unwrap();
-        value = value / 10;
+        value /= 10;
         self.registers.x.bytes.2 = Byte::from_i32((value % 10 + 30).try_into().unwrap()).unwrap();
-        value = value / 10;
+        value /= 10;
         self.registers.x.bytes.1 = Byte::from_i32((value % 10 + 30).try_into().unwrap()).unwrap();
-        value = value / 10;
+        value /= 10;
         self.registers.x.bytes.0 = Byte::from_i32((value % 10 + 30).try_into().unwrap()).unwrap();
-        value = value / 10;
+        value /= 10;
         self.registers.a.bytes.4 = Byte::from_i32((value % 10 + 30).try_into().unwrap()).unwrap();
-        value = value / 10;
+        value /= 10;
         self.registers.a.bytes.3 = Byte::from_i32((value % 10 + 30).try_into().unwrap()).unwrap();
-        value = value / 10;
+        value /= 10;
         self.registers.a.bytes.2 = Byte::from_i32((value % 10 + 30).try_into().unwrap()).unwrap();
-        value = value / 10;
+        value /= 10;
         self.registers.a.bytes.1 = Byte::from_i32((value % 10 + 30).try_into().unwrap()).unwrap();
-        value = value / 10;
+        value /= 10;
         self.registers.a.bytes.0 = Byte::from_i32((value % 10 + 30).try_into().unwrap()).unwrap();
     }
 
-    fn hlt(&mut self) {
-        self.running = false;
+    // floating point operands are always the whole word, F selects the operation
+    fn float_operand(&self, instruction: Word) -> Word {
+        self.memory.get(self.modified_address(instruction)).unwrap()
     }
 
-    fn handle_6(&mut self, instruction: Word) {
-        match instruction.field().value() {
-            0 => self.sla(instruction),
-            1 => self.sra(instruction),
-            2 => self.slax(instruction),
-            3 => self.srax(instruction),
-            4 => self.slc(instruction),
-            5 => self.src(instruction),
-            _ => panic!("illegal field in code 6 instruction"),
+    fn set_float_result(&mut self, (result, overflow): (Word, bool)) {
+        self.registers.a = result;
+        if overflow {
+            self.overflow = true;
         }
     }
 
+    fn fadd(&mut self, instruction: Word) {
+        let v = self.float_operand(instruction);
+        self.set_float_result(floating_point::add(self.registers.a, v, self.byte_size));
+    }
+
+    fn fsub(&mut self, instruction: Word) {
+        let v = self.float_operand(instruction);
+        self.set_float_result(floating_point::subtract(
+            self.registers.a,
+            v,
+            self.byte_size,
+        ));
+    }
+
+    fn fmul(&mut self, instruction: Word) {
+        let v = self.float_operand(instruction);
+        self.set_float_result(floating_point::multiply(
+            self.registers.a,
+            v,
+            self.byte_size,
+        ));
+    }
+
+    fn fdiv(&mut self, instruction: Word) {
+        let v = self.float_operand(instruction);
+        match floating_point::divide(self.registers.a, v, self.byte_size) {
+            Some(x) => self.set_float_result(x),
+            // like DIV, dividing by zero leaves rA alone and sets overflow
+            None => self.overflow = true,
+        }
+    }
+
+    fn flot(&mut self) {
+        self.set_float_result(floating_point::from_integer(
+            self.registers.a,
+            self.byte_size,
+        ));
+    }
+
+    fn fix(&mut self) {
+        self.set_float_result(floating_point::to_integer(self.registers.a, self.byte_size));
+    }
+
+    // the tolerance for FCMP is the floating point number in location 0
+    fn fcmp(&mut self, instruction: Word) {
+        let v = self.float_operand(instruction);
+        let epsilon = self.memory.get(0).unwrap();
+        self.comparison_indicator = Some(
+            match floating_point::compare(self.registers.a, v, epsilon, self.byte_size) {
+                std::cmp::Ordering::Less => ComparisonIndicatorState::LESS,
+                std::cmp::Ordering::Equal => ComparisonIndicatorState::EQUAL,
+                std::cmp::Ordering::Greater => ComparisonIndicatorState::GREATER,
+            },
+        );
+    }
+
+    fn hlt(&mut self) {
+        self.running = false;
+    }
+
     fn sla(&mut self, instruction: Word) {
         let shift_count = i32::min(self.modified_address(instruction), 5);
-        if shift_count < 0 {
-            panic!("illegal - negative shift count");
-        }
         let mut register_bytes = self.registers.a.bytes;
         for _ in 0..shift_count {
             register_bytes.0 = register_bytes.1;
@@ -467,9 +773,6 @@ impl Computer {
 
     fn sra(&mut self, instruction: Word) {
         let shift_count = i32::min(self.modified_address(instruction), 5);
-        if shift_count < 0 {
-            panic!("illegal - negative shift count");
-        }
         let mut register_bytes = self.registers.a.bytes;
         for _ in 0..shift_count {
             register_bytes.4 = register_bytes.3;
@@ -483,9 +786,6 @@ impl Computer {
 
     fn slax(&mut self, instruction: Word) {
         let shift_count = i32::min(self.modified_address(instruction), 10);
-        if shift_count < 0 {
-            panic!("illegal - negative shift count");
-        }
         let mut a_bytes = self.registers.a.bytes;
         let mut x_bytes = self.registers.x.bytes;
         for _ in 0..shift_count {
@@ -506,9 +806,6 @@ impl Computer {
 
     fn srax(&mut self, instruction: Word) {
         let shift_count = i32::min(self.modified_address(instruction), 10);
-        if shift_count < 0 {
-            panic!("illegal - negative shift count");
-        }
         let mut a_bytes = self.registers.a.bytes;
         let mut x_bytes = self.registers.x.bytes;
         for _ in 0..shift_count {
@@ -529,9 +826,6 @@ impl Computer {
 
     fn slc(&mut self, instruction: Word) {
         let shift_count = self.modified_address(instruction) % 10;
-        if shift_count < 0 {
-            panic!("illegal - negative shift count");
-        }
         let mut a_bytes = self.registers.a.bytes;
         let mut x_bytes = self.registers.x.bytes;
         for _ in 0..shift_count {
@@ -553,9 +847,6 @@ impl Computer {
 
     fn src(&mut self, instruction: Word) {
         let shift_count = self.modified_address(instruction) % 10;
-        if shift_count < 0 {
-            panic!("illegal - negative shift count");
-        }
         let mut a_bytes = self.registers.a.bytes;
         let mut x_bytes = self.registers.x.bytes;
         for _ in 0..shift_count {
@@ -575,515 +866,334 @@ impl Computer {
         self.registers.x.bytes = x_bytes;
     }
 
+    // the bytes of rA and rX as one 60 bit number, only on binary MIX where a byte is 6 bits
+    fn ax_bits(&self) -> u64 {
+        let bytes = [self.registers.a.byte_array(), self.registers.x.byte_array()];
+        bytes
+            .concat()
+            .iter()
+            .fold(0, |bits, x| (bits << 6) | x.to_i32() as u64)
+    }
+
+    fn set_ax_bits(&mut self, bits: u64) {
+        let byte = |idx: u64| Byte::from_i32((bits >> (6 * (9 - idx)) & 63) as i32).unwrap();
+        let (a_sign, x_sign) = (self.registers.a.sign, self.registers.x.sign);
+        self.registers.a = Word::from_byte_array(a_sign, [0, 1, 2, 3, 4].map(byte));
+        self.registers.x = Word::from_byte_array(x_sign, [5, 6, 7, 8, 9].map(byte));
+    }
+
+    fn slb(&mut self, instruction: Word) {
+        let shift_count = self.modified_address(instruction);
+        let bits = match shift_count {
+            0..60 => (self.ax_bits() << shift_count) & ((1 << 60) - 1),
+            _ => 0,
+        };
+        self.set_ax_bits(bits);
+    }
+
+    fn srb(&mut self, instruction: Word) {
+        let shift_count = self.modified_address(instruction);
+        let bits = match shift_count {
+            0..60 => self.ax_bits() >> shift_count,
+            _ => 0,
+        };
+        self.set_ax_bits(bits);
+    }
+
+    // Knuth defines MOVE as moving one word at a time, each from the next location after M to the
+    // location in rI1, with rI1 going up by one after each. So a destination just after the source
+    // copies the first word over and over, the usual way to clear a block, and a fault part way
+    // through leaves the words already moved and rI1 pointing at the one that couldn't be.
     fn mov(&mut self, instruction: Word) {
         let copy_number = instruction.field().value();
-        if copy_number == 0 {
-            return;
-        };
         let from_address = self.modified_address(instruction);
-        let to_address = self.registers.i1.to_i32();
         for idx in 0..copy_number {
-            let value = self.memory.get(from_address + idx).unwrap();
-            self.memory.set(to_address + idx, value).unwrap();
+            let value = match self.memory.get(from_address + idx) {
+                Ok(x) => x,
+                Err(_) => return self.raise(FaultKind::InvalidAddress(from_address + idx)),
+            };
+            let to_address = self.registers.i1.to_i32_in(self.byte_size);
+            if self.memory.set(to_address, value).is_err() {
+                return self.raise(FaultKind::InvalidAddress(to_address));
+            }
+            // memory ends before an index register runs out, so rI1 can only get too big for
+            // itself if it could already address a word past the end
+            match Index::from_i32_in(to_address + 1, self.byte_size) {
+                Ok(x) => self.registers.i1 = x,
+                Err(_) => return self.raise(FaultKind::InvalidAddress(to_address + 1)),
+            }
         }
-        self.registers.i1 = Index::from_i32(to_address + copy_number).unwrap();
     }
 
     fn lda(&mut self, instruction: Word) {
         let (field_specifier, contents) = self.field_specifier_and_contents(instruction);
-        self.registers.a = Word {
-            sign: Self::sign_to_load_or_store(&field_specifier, contents, self.registers.a.sign),
-            bytes: Self::bytes_to_load_word(&field_specifier, contents),
-        };
+        self.registers.a = contents.get_field(field_specifier);
     }
 
     fn ld1(&mut self, instruction: Word) {
         let (field_specifier, contents) = self.field_specifier_and_contents(instruction);
-        self.registers.i1 = Index {
-            sign: Self::sign_to_load_or_store(&field_specifier, contents, self.registers.i1.sign),
-            bytes: Self::bytes_to_load_index(&field_specifier, contents),
-        };
+        self.registers.i1 = Self::word_to_index(contents.get_field(field_specifier));
     }
 
     fn ld2(&mut self, instruction: Word) {
         let (field_specifier, contents) = self.field_specifier_and_contents(instruction);
-        self.registers.i2 = Index {
-            sign: Self::sign_to_load_or_store(&field_specifier, contents, self.registers.i2.sign),
-            bytes: Self::bytes_to_load_index(&field_specifier, contents),
-        };
+        self.registers.i2 = Self::word_to_index(contents.get_field(field_specifier));
     }
 
     fn ld3(&mut self, instruction: Word) {
         let (field_specifier, contents) = self.field_specifier_and_contents(instruction);
-        self.registers.i3 = Index {
-            sign: Self::sign_to_load_or_store(&field_specifier, contents, self.registers.i3.sign),
-            bytes: Self::bytes_to_load_index(&field_specifier, contents),
-        };
+        self.registers.i3 = Self::word_to_index(contents.get_field(field_specifier));
     }
 
     fn ld4(&mut self, instruction: Word) {
         let (field_specifier, contents) = self.field_specifier_and_contents(instruction);
-        self.registers.i4 = Index {
-            sign: Self::sign_to_load_or_store(&field_specifier, contents, self.registers.i4.sign),
-            bytes: Self::bytes_to_load_index(&field_specifier, contents),
-        };
+        self.registers.i4 = Self::word_to_index(contents.get_field(field_specifier));
     }
 
     fn ld5(&mut self, instruction: Word) {
         let (field_specifier, contents) = self.field_specifier_and_contents(instruction);
-        self.registers.i5 = Index {
-            sign: Self::sign_to_load_or_store(&field_specifier, contents, self.registers.i5.sign),
-            bytes: Self::bytes_to_load_index(&field_specifier, contents),
-        };
+        self.registers.i5 = Self::word_to_index(contents.get_field(field_specifier));
     }
 
     fn ld6(&mut self, instruction: Word) {
         let (field_specifier, contents) = self.field_specifier_and_contents(instruction);
-        self.registers.i6 = Index {
-            sign: Self::sign_to_load_or_store(&field_specifier, contents, self.registers.i6.sign),
-            bytes: Self::bytes_to_load_index(&field_specifier, contents),
-        };
+        self.registers.i6 = Self::word_to_index(contents.get_field(field_specifier));
     }
 
     fn ldx(&mut self, instruction: Word) {
         let (field_specifier, contents) = self.field_specifier_and_contents(instruction);
-        self.registers.x = Word {
-            sign: Self::sign_to_load_or_store(&field_specifier, contents, self.registers.x.sign),
-            bytes: Self::bytes_to_load_word(&field_specifier, contents),
-        };
+        self.registers.x = contents.get_field(field_specifier);
     }
 
     fn ldan(&mut self, instruction: Word) {
         let (field_specifier, contents) = self.field_specifier_and_contents(instruction);
-        self.registers.a = Word {
-            sign: Self::sign_to_load_or_store(&field_specifier, contents, self.registers.a.sign)
-                .opposite(),
-            bytes: Self::bytes_to_load_word(&field_specifier, contents),
-        };
+        self.registers.a = contents.get_field(field_specifier).with_opposite_sign();
     }
 
     fn ld1n(&mut self, instruction: Word) {
         let (field_specifier, contents) = self.field_specifier_and_contents(instruction);
-        self.registers.i1 = Index {
-            sign: Self::sign_to_load_or_store(&field_specifier, contents, self.registers.i1.sign)
-                .opposite(),
-            bytes: Self::bytes_to_load_index(&field_specifier, contents),
-        };
+        self.registers.i1 =
+            Self::word_to_index(contents.get_field(field_specifier)).with_opposite_sign();
     }
 
     fn ld2n(&mut self, instruction: Word) {
         let (field_specifier, contents) = self.field_specifier_and_contents(instruction);
-        self.registers.i2 = Index {
-            sign: Self::sign_to_load_or_store(&field_specifier, contents, self.registers.i2.sign)
-                .opposite(),
-            bytes: Self::bytes_to_load_index(&field_specifier, contents),
-        };
+        self.registers.i2 =
+            Self::word_to_index(contents.get_field(field_specifier)).with_opposite_sign();
     }
 
     fn ld3n(&mut self, instruction: Word) {
         let (field_specifier, contents) = self.field_specifier_and_contents(instruction);
-        self.registers.i3 = Index {
-            sign: Self::sign_to_load_or_store(&field_specifier, contents, self.registers.i3.sign)
-                .opposite(),
-            bytes: Self::bytes_to_load_index(&field_specifier, contents),
-        };
+        self.registers.i3 =
+            Self::word_to_index(contents.get_field(field_specifier)).with_opposite_sign();
     }
 
     fn ld4n(&mut self, instruction: Word) {
         let (field_specifier, contents) = self.field_specifier_and_contents(instruction);
-        self.registers.i4 = Index {
-            sign: Self::sign_to_load_or_store(&field_specifier, contents, self.registers.i4.sign)
-                .opposite(),
-            bytes: Self::bytes_to_load_index(&field_specifier, contents),
-        };
+        self.registers.i4 =
+            Self::word_to_index(contents.get_field(field_specifier)).with_opposite_sign();
     }
 
     fn ld5n(&mut self, instruction: Word) {
         let (field_specifier, contents) = self.field_specifier_and_contents(instruction);
-        self.registers.i5 = Index {
-            sign: Self::sign_to_load_or_store(&field_specifier, contents, self.registers.i5.sign)
-                .opposite(),
-            bytes: Self::bytes_to_load_index(&field_specifier, contents),
-        };
+        self.registers.i5 =
+            Self::word_to_index(contents.get_field(field_specifier)).with_opposite_sign();
     }
 
     fn ld6n(&mut self, instruction: Word) {
         let (field_specifier, contents) = self.field_specifier_and_contents(instruction);
-        self.registers.i6 = Index {
-            sign: Self::sign_to_load_or_store(&field_specifier, contents, self.registers.i6.sign)
-                .opposite(),
-            bytes: Self::bytes_to_load_index(&field_specifier, contents),
-        };
+        self.registers.i6 =
+            Self::word_to_index(contents.get_field(field_specifier)).with_opposite_sign();
     }
 
     fn ldxn(&mut self, instruction: Word) {
         let (field_specifier, contents) = self.field_specifier_and_contents(instruction);
-        self.registers.x = Word {
-            sign: Self::sign_to_load_or_store(&field_specifier, contents, self.registers.x.sign)
-                .opposite(),
-            bytes: Self::bytes_to_load_word(&field_specifier, contents),
-        };
-    }
-
-    fn bytes_to_store(
-        memory_contents: Word,
-        register_contents: Word,
-        field_specifier: &FieldSpecification,
-    ) -> (Byte, Byte, Byte, Byte, Byte) {
-        let mut bytes_to_store = memory_contents.bytes;
-        let mut number_of_bytes_altered = 0;
-        let get_next_byte =
-            |number_of_bytes_altered: i32, bytes: (Byte, Byte, Byte, Byte, Byte)| -> Byte {
-                match number_of_bytes_altered {
-                    0 => bytes.4,
-                    1 => bytes.3,
-                    2 => bytes.2,
-                    3 => bytes.1,
-                    4 => bytes.0,
-                    _ => panic!("invalid byte access"),
-                }
-            };
-        let mut next_byte = get_next_byte(number_of_bytes_altered, register_contents.bytes);
-
-        if field_specifier.inclusive_range().contains(&5) {
-            bytes_to_store.4 = next_byte;
-            number_of_bytes_altered += 1;
-            next_byte = get_next_byte(number_of_bytes_altered, register_contents.bytes);
-        }
-        if field_specifier.inclusive_range().contains(&4) {
-            bytes_to_store.3 = next_byte;
-            number_of_bytes_altered += 1;
-            next_byte = get_next_byte(number_of_bytes_altered, register_contents.bytes);
-        }
-        if field_specifier.inclusive_range().contains(&3) {
-            bytes_to_store.2 = next_byte;
-            number_of_bytes_altered += 1;
-            next_byte = get_next_byte(number_of_bytes_altered, register_contents.bytes);
-        }
-        if field_specifier.inclusive_range().contains(&2) {
-            bytes_to_store.1 = next_byte;
-            number_of_bytes_altered += 1;
-            next_byte = get_next_byte(number_of_bytes_altered, register_contents.bytes);
-        }
-        if field_specifier.inclusive_range().contains(&1) {
-            bytes_to_store.0 = next_byte;
-        }
-        bytes_to_store
+        self.registers.x = contents.get_field(field_specifier).with_opposite_sign();
     }
 
     fn sta(&mut self, instruction: Word) {
         let (field_specifier, contents) = self.field_specifier_and_contents(instruction);
-        let original_sign = self
-            .memory
-            .get(self.modified_address(instruction))
-            .unwrap()
-            .sign;
-
+        let stored = contents.with_field(field_specifier, self.registers.a);
         self.memory
-            .set(
-                self.modified_address(instruction),
-                Word {
-                    sign: Self::sign_to_load_or_store(
-                        &field_specifier,
-                        self.registers.a,
-                        original_sign,
-                    ),
-                    bytes: Self::bytes_to_store(contents, self.registers.a, &field_specifier),
-                },
-            )
+            .set(self.modified_address(instruction), stored)
             .unwrap();
     }
 
     fn st1(&mut self, instruction: Word) {
         let (field_specifier, contents) = self.field_specifier_and_contents(instruction);
-        let original_sign = self
-            .memory
-            .get(self.modified_address(instruction))
-            .unwrap()
-            .sign;
-        let word_from_index = Word::from_i32(self.registers.i1.to_i32()).unwrap();
-
+        let stored = contents.with_field(field_specifier, Self::index_to_word(&self.registers.i1));
         self.memory
-            .set(
-                self.modified_address(instruction),
-                Word {
-                    sign: Self::sign_to_load_or_store(
-                        &field_specifier,
-                        word_from_index,
-                        original_sign,
-                    ),
-                    bytes: Self::bytes_to_store(contents, word_from_index, &field_specifier),
-                },
-            )
+            .set(self.modified_address(instruction), stored)
             .unwrap();
     }
 
     fn st2(&mut self, instruction: Word) {
         let (field_specifier, contents) = self.field_specifier_and_contents(instruction);
-        let original_sign = self
-            .memory
-            .get(self.modified_address(instruction))
-            .unwrap()
-            .sign;
-        let word_from_index = Word::from_i32(self.registers.i2.to_i32()).unwrap();
-
+        let stored = contents.with_field(field_specifier, Self::index_to_word(&self.registers.i2));
         self.memory
-            .set(
-                self.modified_address(instruction),
-                Word {
-                    sign: Self::sign_to_load_or_store(
-                        &field_specifier,
-                        word_from_index,
-                        original_sign,
-                    ),
-                    bytes: Self::bytes_to_store(contents, word_from_index, &field_specifier),
-                },
-            )
+            .set(self.modified_address(instruction), stored)
             .unwrap();
     }
 
     fn st3(&mut self, instruction: Word) {
         let (field_specifier, contents) = self.field_specifier_and_contents(instruction);
-        let original_sign = self
-            .memory
-            .get(self.modified_address(instruction))
-            .unwrap()
-            .sign;
-        let word_from_index = Word::from_i32(self.registers.i3.to_i32()).unwrap();
-
+        let stored = contents.with_field(field_specifier, Self::index_to_word(&self.registers.i3));
         self.memory
-            .set(
-                self.modified_address(instruction),
-                Word {
-                    sign: Self::sign_to_load_or_store(
-                        &field_specifier,
-                        word_from_index,
-                        original_sign,
-                    ),
-                    bytes: Self::bytes_to_store(contents, word_from_index, &field_specifier),
-                },
-            )
+            .set(self.modified_address(instruction), stored)
             .unwrap();
     }
 
     fn st4(&mut self, instruction: Word) {
         let (field_specifier, contents) = self.field_specifier_and_contents(instruction);
-        let original_sign = self
-            .memory
-            .get(self.modified_address(instruction))
-            .unwrap()
-            .sign;
-        let word_from_index = Word::from_i32(self.registers.i4.to_i32()).unwrap();
-
+        let stored = contents.with_field(field_specifier, Self::index_to_word(&self.registers.i4));
         self.memory
-            .set(
-                self.modified_address(instruction),
-                Word {
-                    sign: Self::sign_to_load_or_store(
-                        &field_specifier,
-                        word_from_index,
-                        original_sign,
-                    ),
-                    bytes: Self::bytes_to_store(contents, word_from_index, &field_specifier),
-                },
-            )
+            .set(self.modified_address(instruction), stored)
             .unwrap();
     }
 
     fn st5(&mut self, instruction: Word) {
         let (field_specifier, contents) = self.field_specifier_and_contents(instruction);
-        let original_sign = self
-            .memory
-            .get(self.modified_address(instruction))
-            .unwrap()
-            .sign;
-        let word_from_index = Word::from_i32(self.registers.i5.to_i32()).unwrap();
-
+        let stored = contents.with_field(field_specifier, Self::index_to_word(&self.registers.i5));
         self.memory
-            .set(
-                self.modified_address(instruction),
-                Word {
-                    sign: Self::sign_to_load_or_store(
-                        &field_specifier,
-                        word_from_index,
-                        original_sign,
-                    ),
-                    bytes: Self::bytes_to_store(contents, word_from_index, &field_specifier),
-                },
-            )
+            .set(self.modified_address(instruction), stored)
             .unwrap();
     }
 
     fn st6(&mut self, instruction: Word) {
         let (field_specifier, contents) = self.field_specifier_and_contents(instruction);
-        let original_sign = self
-            .memory
-            .get(self.modified_address(instruction))
-            .unwrap()
-            .sign;
-        let word_from_index = Word::from_i32(self.registers.i6.to_i32()).unwrap();
-
+        let stored = contents.with_field(field_specifier, Self::index_to_word(&self.registers.i6));
         self.memory
-            .set(
-                self.modified_address(instruction),
-                Word {
-                    sign: Self::sign_to_load_or_store(
-                        &field_specifier,
-                        word_from_index,
-                        original_sign,
-                    ),
-                    bytes: Self::bytes_to_store(contents, word_from_index, &field_specifier),
-                },
-            )
+            .set(self.modified_address(instruction), stored)
             .unwrap();
     }
 
     fn stx(&mut self, instruction: Word) {
         let (field_specifier, contents) = self.field_specifier_and_contents(instruction);
-        let original_sign = self
-            .memory
-            .get(self.modified_address(instruction))
-            .unwrap()
-            .sign;
-
+        let stored = contents.with_field(field_specifier, self.registers.x);
         self.memory
-            .set(
-                self.modified_address(instruction),
-                Word {
-                    sign: Self::sign_to_load_or_store(
-                        &field_specifier,
-                        self.registers.x,
-                        original_sign,
-                    ),
-                    bytes: Self::bytes_to_store(contents, self.registers.x, &field_specifier),
-                },
-            )
+            .set(self.modified_address(instruction), stored)
             .unwrap();
     }
 
     fn stj(&mut self, instruction: Word) {
         let (field_specifier, contents) = self.field_specifier_and_contents(instruction);
-        let original_sign = self
-            .memory
-            .get(self.modified_address(instruction))
-            .unwrap()
-            .sign;
-        let word_from_jump_address = Word::from_i32(self.registers.j.to_i32()).unwrap();
-
+        let (b1, b2) = self.registers.j.bytes();
+        let jump_address =
+            Word::from_byte_array(Sign::PLUS, [Byte::ZERO, Byte::ZERO, Byte::ZERO, b1, b2]);
+        let stored = contents.with_field(field_specifier, jump_address);
         self.memory
-            .set(
-                self.modified_address(instruction),
-                Word {
-                    sign: Self::sign_to_load_or_store(
-                        &field_specifier,
-                        word_from_jump_address,
-                        original_sign,
-                    ),
-                    bytes: Self::bytes_to_store(contents, word_from_jump_address, &field_specifier),
-                },
-            )
+            .set(self.modified_address(instruction), stored)
             .unwrap();
     }
 
     fn stz(&mut self, instruction: Word) {
         let (field_specifier, contents) = self.field_specifier_and_contents(instruction);
-        let original_sign = self
-            .memory
-            .get(self.modified_address(instruction))
-            .unwrap()
-            .sign;
-
+        let stored = contents.with_field(field_specifier, Word::ZERO);
         self.memory
-            .set(
-                self.modified_address(instruction),
-                Word {
-                    sign: Self::sign_to_load_or_store(&field_specifier, Word::ZERO, original_sign),
-                    bytes: Self::bytes_to_store(contents, Word::ZERO, &field_specifier),
-                },
-            )
+            .set(self.modified_address(instruction), stored)
             .unwrap();
     }
 
-    fn jbus(&mut self, instruction: Word) {
-        if !instruction.field().value() != 0 {
-            panic!("illegal field in jbus instruction");
+    fn is_unit_ready(&mut self, instruction: Word) -> Option<bool> {
+        let unit = instruction.field().value();
+        let busy = self
+            .interrupts
+            .as_ref()
+            .is_some_and(|x| x.is_busy(unit, self.time));
+        match self.devices.unit(unit) {
+            Ok(device) => Some(device.is_ready() && !busy),
+            Err(e) => {
+                self.raise(FaultKind::Device(unit, e));
+                None
+            }
         }
-        if self.tape_unit.ready {
+    }
+
+    fn jbus(&mut self, instruction: Word) {
+        if self.is_unit_ready(instruction) == Some(false) {
             self.jmp(instruction);
         }
     }
 
-    fn ioc(&mut self, _instruction: Word) {
-        // do nothing - let's worry about these device specifics later
+    // rX as a block number for the devices that need one, anything too big to be one is just
+    // passed on as an invalid position
+    fn position(&self) -> i32 {
+        i32::try_from(self.registers.x.to_i64_in(self.byte_size)).unwrap_or(i32::MAX)
     }
 
-    fn input(&mut self, instruction: Word) {
+    fn ioc(&mut self, instruction: Word) {
         let unit = instruction.field().value();
-        if unit != 0 {
-            panic!("illegal field value in IN instruction - I haven't implemented all the input devices yet!");
+        let operation = self.modified_address(instruction);
+        let position = self.position();
+        let result = self
+            .devices
+            .unit(unit)
+            .and_then(|device| device.control(operation, position));
+        match result {
+            Ok(_) => self.start_io(unit),
+            Err(e) => self.raise(FaultKind::Device(unit, e)),
         }
-        let target_device = &self.tape_unit;
-        let read_words = target_device.read(self.registers.x.to_i32());
-        for (idx, word) in read_words.iter().enumerate() {
-            let idx: i32 = idx.try_into().unwrap();
-            self.memory
-                .set(self.modified_address(instruction) + idx, *word)
-                .unwrap();
+    }
+
+    fn input(&mut self, instruction: Word) {
+        let unit = instruction.field().value();
+        let modified_address = self.modified_address(instruction);
+        let position = self.position();
+        let result = self.devices.unit(unit).and_then(|device| {
+            device
+                .as_readable()
+                .ok_or(DeviceError::NotReadable(unit))?
+                .read(position)
+        });
+        let read_words = match result {
+            Ok(x) => x,
+            Err(e) => return self.raise(FaultKind::Device(unit, e)),
+        };
+        for (idx, word) in (0..).zip(read_words) {
+            if self.memory.set(modified_address + idx, word).is_err() {
+                return self.raise(FaultKind::InvalidAddress(modified_address + idx));
+            }
         }
+        self.start_io(unit);
     }
 
     fn output(&mut self, instruction: Word) {
         let unit = instruction.field().value();
-        if unit != 0 && unit != 18 {
-            panic!("illegal field value in IN instruction - I haven't implemented all the input devices yet!");
-        }
         let modified_address = self.modified_address(instruction);
-        if unit == 0 {
-            let target_device = &mut self.tape_unit;
-            let mut write_words: [Word; 100] = [Word::ZERO; 100];
-            for i in 0..100 {
-                let idx: i32 = i.try_into().unwrap();
-                write_words[i] = self.memory.get(modified_address + idx).unwrap();
-            }
-            target_device.write(self.registers.x.to_i32(), write_words);
-        } else if unit == 18 {
-            let target_device = &self.line_printer;
-            let mut write_words: [Word; 24] = [Word::ZERO; 24];
-            for i in 0..24 {
-                let idx: i32 = i.try_into().unwrap();
-                write_words[i] = self.memory.get(modified_address + idx).unwrap()
+        let position = self.position();
+        let block_size = match self.devices.unit(unit) {
+            Ok(device) => device.block_size() as i32,
+            Err(e) => return self.raise(FaultKind::Device(unit, e)),
+        };
+        let mut write_words = Vec::with_capacity(block_size as usize);
+        for address in modified_address..modified_address + block_size {
+            match self.memory.get(address) {
+                Ok(x) => write_words.push(x),
+                Err(_) => return self.raise(FaultKind::InvalidAddress(address)),
             }
-            target_device.write(write_words);
+        }
+        let result = self.devices.unit(unit).and_then(|device| {
+            device
+                .as_writeable()
+                .ok_or(DeviceError::NotWriteable(unit))?
+                .write(position, &write_words)
+        });
+        match result {
+            Ok(_) => self.start_io(unit),
+            Err(e) => self.raise(FaultKind::Device(unit, e)),
         }
     }
 
     fn jred(&mut self, instruction: Word) {
-        if instruction.field().value() != 0 {
-            panic!("illegal field value in jred");
-        }
-        if self.tape_unit.ready {
+        if self.is_unit_ready(instruction) == Some(true) {
             self.jmp(instruction);
         }
     }
 
-    fn handle_39(&mut self, instruction: Word) {
-        match instruction.field().value() {
-            0 => self.jmp(instruction),
-            1 => self.jsj(instruction),
-            2 => self.jov(instruction),
-            3 => self.jnov(instruction),
-            4 => self.jl(instruction),
-            5 => self.je(instruction),
-            6 => self.jg(instruction),
-            7 => self.jge(instruction),
-            8 => self.jne(instruction),
-            9 => self.jle(instruction),
-            _ => panic!("Illegal field in code 39 instruction"),
-        };
-    }
-
     fn jmp(&mut self, instruction: Word) {
-        self.registers.j = JumpAddress::from_i32(self.current_instruction_address).unwrap();
+        // rJ has no sign, so a jump from a negative location in control state leaves its magnitude
+        let from = self.current_instruction_address.abs();
+        self.registers.j = JumpAddress::from_i32_in(from, self.byte_size).unwrap();
         self.current_instruction_address = self.modified_address(instruction);
     }
 
@@ -1148,430 +1258,369 @@ impl Computer {
         }
     }
 
-    fn handle_40(&mut self, instruction: Word) {
-        match instruction.field().value() {
-            0 => self.jan(instruction),
-            1 => self.jaz(instruction),
-            2 => self.jap(instruction),
-            3 => self.jann(instruction),
-            4 => self.janz(instruction),
-            5 => self.janp(instruction),
-            _ => panic!("Illegal field for code 40 instruction)"),
-        };
-    }
-
     fn jan(&mut self, instruction: Word) {
-        if self.registers.a.to_i32() < 0 {
+        if self.registers.a.to_i64_in(self.byte_size) < 0 {
             self.jmp(instruction);
         }
     }
 
     fn jaz(&mut self, instruction: Word) {
-        if self.registers.a.to_i32() == 0 {
+        if self.registers.a.to_i64_in(self.byte_size) == 0 {
             self.jmp(instruction);
         }
     }
 
     fn jap(&mut self, instruction: Word) {
-        if self.registers.a.to_i32() > 0 {
+        if self.registers.a.to_i64_in(self.byte_size) > 0 {
             self.jmp(instruction);
         }
     }
 
     fn jann(&mut self, instruction: Word) {
-        if self.registers.a.to_i32() >= 0 {
+        if self.registers.a.to_i64_in(self.byte_size) >= 0 {
             self.jmp(instruction);
         }
     }
 
     fn janz(&mut self, instruction: Word) {
-        if self.registers.a.to_i32() != 0 {
+        if self.registers.a.to_i64_in(self.byte_size) != 0 {
             self.jmp(instruction);
         }
     }
 
     fn janp(&mut self, instruction: Word) {
-        if self.registers.a.to_i32() <= 0 {
+        if self.registers.a.to_i64_in(self.byte_size) <= 0 {
             self.jmp(instruction);
         }
     }
 
-    fn handle_41(&mut self, instruction: Word) {
-        match instruction.field().value() {
-            0 => self.j1n(instruction),
-            1 => self.j1z(instruction),
-            2 => self.j1p(instruction),
-            3 => self.j1nn(instruction),
-            4 => self.j1nz(instruction),
-            5 => self.j1np(instruction),
-            _ => panic!("illegal field in code 41 instruction"),
+    fn jae(&mut self, instruction: Word) {
+        if self.registers.a.to_i64_in(self.byte_size) % 2 == 0 {
+            self.jmp(instruction);
+        }
+    }
+
+    fn jao(&mut self, instruction: Word) {
+        if self.registers.a.to_i64_in(self.byte_size) % 2 != 0 {
+            self.jmp(instruction);
         }
     }
 
     fn j1n(&mut self, instruction: Word) {
-        if self.registers.i1.to_i32() < 0 {
+        if self.registers.i1.to_i32_in(self.byte_size) < 0 {
             self.jmp(instruction);
         }
     }
 
     fn j1z(&mut self, instruction: Word) {
-        if self.registers.i1.to_i32() == 0 {
+        if self.registers.i1.to_i32_in(self.byte_size) == 0 {
             self.jmp(instruction);
         }
     }
 
     fn j1p(&mut self, instruction: Word) {
-        if self.registers.i1.to_i32() > 0 {
+        if self.registers.i1.to_i32_in(self.byte_size) > 0 {
             self.jmp(instruction);
         }
     }
 
     fn j1nn(&mut self, instruction: Word) {
-        if self.registers.i1.to_i32() >= 0 {
+        if self.registers.i1.to_i32_in(self.byte_size) >= 0 {
             self.jmp(instruction);
         }
     }
 
     fn j1nz(&mut self, instruction: Word) {
-        if self.registers.i1.to_i32() != 0 {
+        if self.registers.i1.to_i32_in(self.byte_size) != 0 {
             self.jmp(instruction);
         }
     }
 
     fn j1np(&mut self, instruction: Word) {
-        if self.registers.i1.to_i32() <= 0 {
+        if self.registers.i1.to_i32_in(self.byte_size) <= 0 {
             self.jmp(instruction);
         }
     }
 
-    fn handle_42(&mut self, instruction: Word) {
-        match instruction.field().value() {
-            0 => self.j2n(instruction),
-            1 => self.j2z(instruction),
-            2 => self.j2p(instruction),
-            3 => self.j2nn(instruction),
-            4 => self.j2nz(instruction),
-            5 => self.j2np(instruction),
-            _ => panic!("illegal field in code 42 instruction"),
-        }
-    }
-
     fn j2n(&mut self, instruction: Word) {
-        if self.registers.i2.to_i32() < 0 {
+        if self.registers.i2.to_i32_in(self.byte_size) < 0 {
             self.jmp(instruction);
         }
     }
 
     fn j2z(&mut self, instruction: Word) {
-        if self.registers.i2.to_i32() == 0 {
+        if self.registers.i2.to_i32_in(self.byte_size) == 0 {
             self.jmp(instruction);
         }
     }
 
     fn j2p(&mut self, instruction: Word) {
-        if self.registers.i2.to_i32() > 0 {
+        if self.registers.i2.to_i32_in(self.byte_size) > 0 {
             self.jmp(instruction);
         }
     }
 
     fn j2nn(&mut self, instruction: Word) {
-        if self.registers.i2.to_i32() >= 0 {
+        if self.registers.i2.to_i32_in(self.byte_size) >= 0 {
             self.jmp(instruction);
         }
     }
 
     fn j2nz(&mut self, instruction: Word) {
-        if self.registers.i2.to_i32() != 0 {
+        if self.registers.i2.to_i32_in(self.byte_size) != 0 {
             self.jmp(instruction);
         }
     }
 
     fn j2np(&mut self, instruction: Word) {
-        if self.registers.i2.to_i32() <= 0 {
+        if self.registers.i2.to_i32_in(self.byte_size) <= 0 {
             self.jmp(instruction);
         }
     }
 
-    fn handle_43(&mut self, instruction: Word) {
-        match instruction.field().value() {
-            0 => self.j3n(instruction),
-            1 => self.j3z(instruction),
-            2 => self.j3p(instruction),
-            3 => self.j3nn(instruction),
-            4 => self.j3nz(instruction),
-            5 => self.j3np(instruction),
-            _ => panic!("illegal field in code 43 instruction"),
-        }
-    }
-
     fn j3n(&mut self, instruction: Word) {
-        if self.registers.i3.to_i32() < 0 {
+        if self.registers.i3.to_i32_in(self.byte_size) < 0 {
             self.jmp(instruction);
         }
     }
 
     fn j3z(&mut self, instruction: Word) {
-        if self.registers.i3.to_i32() == 0 {
+        if self.registers.i3.to_i32_in(self.byte_size) == 0 {
             self.jmp(instruction);
         }
     }
 
     fn j3p(&mut self, instruction: Word) {
-        if self.registers.i3.to_i32() > 0 {
+        if self.registers.i3.to_i32_in(self.byte_size) > 0 {
             self.jmp(instruction);
         }
     }
 
     fn j3nn(&mut self, instruction: Word) {
-        if self.registers.i3.to_i32() >= 0 {
+        if self.registers.i3.to_i32_in(self.byte_size) >= 0 {
             self.jmp(instruction);
         }
     }
 
     fn j3nz(&mut self, instruction: Word) {
-        if self.registers.i3.to_i32() != 0 {
+        if self.registers.i3.to_i32_in(self.byte_size) != 0 {
             self.jmp(instruction);
         }
     }
 
     fn j3np(&mut self, instruction: Word) {
-        if self.registers.i3.to_i32() <= 0 {
+        if self.registers.i3.to_i32_in(self.byte_size) <= 0 {
             self.jmp(instruction);
         }
     }
 
-    fn handle_44(&mut self, instruction: Word) {
-        match instruction.field().value() {
-            0 => self.j4n(instruction),
-            1 => self.j4z(instruction),
-            2 => self.j4p(instruction),
-            3 => self.j4nn(instruction),
-            4 => self.j4nz(instruction),
-            5 => self.j4np(instruction),
-            _ => panic!("illegal field in code 44 instruction"),
-        }
-    }
-
     fn j4n(&mut self, instruction: Word) {
-        if self.registers.i4.to_i32() < 0 {
+        if self.registers.i4.to_i32_in(self.byte_size) < 0 {
             self.jmp(instruction);
         }
     }
 
     fn j4z(&mut self, instruction: Word) {
-        if self.registers.i4.to_i32() == 0 {
+        if self.registers.i4.to_i32_in(self.byte_size) == 0 {
             self.jmp(instruction);
         }
     }
 
     fn j4p(&mut self, instruction: Word) {
-        if self.registers.i4.to_i32() > 0 {
+        if self.registers.i4.to_i32_in(self.byte_size) > 0 {
             self.jmp(instruction);
         }
     }
 
     fn j4nn(&mut self, instruction: Word) {
-        if self.registers.i4.to_i32() >= 0 {
+        if self.registers.i4.to_i32_in(self.byte_size) >= 0 {
             self.jmp(instruction);
         }
     }
 
     fn j4nz(&mut self, instruction: Word) {
-        if self.registers.i4.to_i32() != 0 {
+        if self.registers.i4.to_i32_in(self.byte_size) != 0 {
             self.jmp(instruction);
         }
     }
 
     fn j4np(&mut self, instruction: Word) {
-        if self.registers.i4.to_i32() <= 0 {
+        if self.registers.i4.to_i32_in(self.byte_size) <= 0 {
             self.jmp(instruction);
         }
     }
 
-    fn handle_45(&mut self, instruction: Word) {
-        match instruction.field().value() {
-            0 => self.j5n(instruction),
-            1 => self.j5z(instruction),
-            2 => self.j5p(instruction),
-            3 => self.j5nn(instruction),
-            4 => self.j5nz(instruction),
-            5 => self.j5np(instruction),
-            _ => panic!("illegal field in code 45 instruction"),
-        }
-    }
-
     fn j5n(&mut self, instruction: Word) {
-        if self.registers.i5.to_i32() < 0 {
+        if self.registers.i5.to_i32_in(self.byte_size) < 0 {
             self.jmp(instruction);
         }
     }
 
     fn j5z(&mut self, instruction: Word) {
-        if self.registers.i5.to_i32() == 0 {
+        if self.registers.i5.to_i32_in(self.byte_size) == 0 {
             self.jmp(instruction);
         }
     }
 
     fn j5p(&mut self, instruction: Word) {
-        if self.registers.i5.to_i32() > 0 {
+        if self.registers.i5.to_i32_in(self.byte_size) > 0 {
             self.jmp(instruction);
         }
     }
 
     fn j5nn(&mut self, instruction: Word) {
-        if self.registers.i5.to_i32() >= 0 {
+        if self.registers.i5.to_i32_in(self.byte_size) >= 0 {
             self.jmp(instruction);
         }
     }
 
     fn j5nz(&mut self, instruction: Word) {
-        if self.registers.i5.to_i32() != 0 {
+        if self.registers.i5.to_i32_in(self.byte_size) != 0 {
             self.jmp(instruction);
         }
     }
 
     fn j5np(&mut self, instruction: Word) {
-        if self.registers.i5.to_i32() <= 0 {
+        if self.registers.i5.to_i32_in(self.byte_size) <= 0 {
             self.jmp(instruction);
         }
     }
 
-    fn handle_46(&mut self, instruction: Word) {
-        match instruction.field().value() {
-            0 => self.j6n(instruction),
-            1 => self.j6z(instruction),
-            2 => self.j6p(instruction),
-            3 => self.j6nn(instruction),
-            4 => self.j6nz(instruction),
-            5 => self.j6np(instruction),
-            _ => panic!("illegal field in code 46 instruction"),
-        }
-    }
-
     fn j6n(&mut self, instruction: Word) {
-        if self.registers.i6.to_i32() < 0 {
+        if self.registers.i6.to_i32_in(self.byte_size) < 0 {
             self.jmp(instruction);
         }
     }
 
     fn j6z(&mut self, instruction: Word) {
-        if self.registers.i6.to_i32() == 0 {
+        if self.registers.i6.to_i32_in(self.byte_size) == 0 {
             self.jmp(instruction);
         }
     }
 
     fn j6p(&mut self, instruction: Word) {
-        if self.registers.i6.to_i32() > 0 {
+        if self.registers.i6.to_i32_in(self.byte_size) > 0 {
             self.jmp(instruction);
         }
     }
 
     fn j6nn(&mut self, instruction: Word) {
-        if self.registers.i6.to_i32() >= 0 {
+        if self.registers.i6.to_i32_in(self.byte_size) >= 0 {
             self.jmp(instruction);
         }
     }
 
     fn j6nz(&mut self, instruction: Word) {
-        if self.registers.i6.to_i32() != 0 {
+        if self.registers.i6.to_i32_in(self.byte_size) != 0 {
             self.jmp(instruction);
         }
     }
 
     fn j6np(&mut self, instruction: Word) {
-        if self.registers.i6.to_i32() <= 0 {
+        if self.registers.i6.to_i32_in(self.byte_size) <= 0 {
             self.jmp(instruction);
         }
     }
 
-    fn handle_47(&mut self, instruction: Word) {
-        match instruction.field().value() {
-            0 => self.jxn(instruction),
-            1 => self.jxz(instruction),
-            2 => self.jxp(instruction),
-            3 => self.jxnn(instruction),
-            4 => self.jxnz(instruction),
-            5 => self.jxnp(instruction),
-            _ => panic!("illegal field in code 47 instruction"),
-        }
-    }
-
     fn jxn(&mut self, instruction: Word) {
-        if self.registers.x.to_i32() < 0 {
+        if self.registers.x.to_i64_in(self.byte_size) < 0 {
             self.jmp(instruction);
         }
     }
 
     fn jxz(&mut self, instruction: Word) {
-        if self.registers.x.to_i32() == 0 {
+        if self.registers.x.to_i64_in(self.byte_size) == 0 {
             self.jmp(instruction);
         }
     }
 
     fn jxp(&mut self, instruction: Word) {
-        if self.registers.x.to_i32() > 0 {
+        if self.registers.x.to_i64_in(self.byte_size) > 0 {
             self.jmp(instruction);
         }
     }
 
     fn jxnn(&mut self, instruction: Word) {
-        if self.registers.x.to_i32() >= 0 {
+        if self.registers.x.to_i64_in(self.byte_size) >= 0 {
             self.jmp(instruction);
         }
     }
 
     fn jxnz(&mut self, instruction: Word) {
-        if self.registers.x.to_i32() != 0 {
+        if self.registers.x.to_i64_in(self.byte_size) != 0 {
             self.jmp(instruction);
         }
     }
 
     fn jxnp(&mut self, instruction: Word) {
-        if self.registers.x.to_i32() <= 0 {
+        if self.registers.x.to_i64_in(self.byte_size) <= 0 {
             self.jmp(instruction);
         }
     }
 
+    fn jxe(&mut self, instruction: Word) {
+        if self.registers.x.to_i64_in(self.byte_size) % 2 == 0 {
+            self.jmp(instruction);
+        }
+    }
+
+    fn jxo(&mut self, instruction: Word) {
+        if self.registers.x.to_i64_in(self.byte_size) % 2 != 0 {
+            self.jmp(instruction);
+        }
+    }
+
+    // M as a word for ENTA, INCA and so on, M can be bigger than an index register holds
+    fn address_word(&self, instruction: Word) -> Word {
+        Word::from_i64_in(self.modified_address(instruction).into(), self.byte_size).unwrap()
+    }
+
     fn word_to_enter(&self, instruction: Word) -> Word {
-        let mut m = Word::from_i32(self.modified_address(instruction)).unwrap();
-        if m.to_i32() == 0 {
+        let mut m = self.address_word(instruction);
+        if m.to_i64_in(self.byte_size) == 0 {
             m = m.with_sign(instruction.sign);
         }
         m
     }
 
-    fn index_to_enter(&self, instruction: Word) -> Index {
-        let mut m = Index::from_i32(self.modified_address(instruction)).unwrap();
-        if m.to_i32() == 0 {
-            m = m.with_sign(instruction.sign);
+    // INCi and DECi, told apart by F, where a zero result keeps the register's sign like ADD.
+    // operand_fault has already stopped the machine if index_result doesn't fit the register.
+    fn index_sum(&self, index: Index, instruction: Word) -> Index {
+        let result = index.to_i32_in(self.byte_size) + self.index_change(instruction);
+        debug_assert_eq!(self.index_result(instruction), Some(i64::from(result)));
+        if result == 0 {
+            return Index::ZERO.with_sign(index.sign);
         }
-        m
+        Index::from_i32_in(result, self.byte_size).expect("checked by operand_fault")
     }
 
-    fn handle_48(&mut self, instruction: Word) {
+    // M for INCi and -M for DECi
+    fn index_change(&self, instruction: Word) -> i32 {
         match instruction.field().value() {
-            0 => self.inca(instruction),
-            1 => self.deca(instruction),
-            2 => self.enta(instruction),
-            3 => self.enna(instruction),
-            _ => panic!("Illegal field in code 48 instruction"),
-        };
+            1 => -self.modified_address(instruction),
+            _ => self.modified_address(instruction),
+        }
     }
 
-    fn inca(&mut self, instruction: Word) {
-        let result = self.registers.a.to_i32() + self.modified_address(instruction);
-        if result == 0 {
-            self.registers.a.bytes = (Byte::ZERO, Byte::ZERO, Byte::ZERO, Byte::ZERO, Byte::ZERO);
-            return;
-        }
-        if result / 64_i32.pow(5) != 0 {
-            self.overflow = true;
+    // as for index_sum, operand_fault has checked that M fits
+    fn index_to_enter(&self, instruction: Word) -> Index {
+        let address = self.modified_address(instruction);
+        debug_assert_eq!(self.index_result(instruction), Some(i64::from(address)));
+        let mut m = Index::from_i32_in(address, self.byte_size).expect("checked by operand_fault");
+        if m.to_i32_in(self.byte_size) == 0 {
+            m = m.with_sign(instruction.sign);
         }
-        self.registers.a = Word::from_i32(result % 64_i32.pow(5)).unwrap();
+        m
+    }
+
+    fn inca(&mut self, instruction: Word) {
+        let m = self.address_word(instruction);
+        self.registers.a = self.register_sum(self.registers.a, m);
     }
 
     fn deca(&mut self, instruction: Word) {
-        self.inca(instruction.with_opposite_sign());
+        let m = self.address_word(instruction);
+        self.registers.a = self.register_sum(self.registers.a, -m);
     }
 
     fn enta(&mut self, instruction: Word) {
@@ -1582,27 +1631,12 @@ impl Computer {
         self.registers.a = self.word_to_enter(instruction).with_opposite_sign();
     }
 
-    fn handle_49(&mut self, instruction: Word) {
-        match instruction.field().value() {
-            0 => self.inc1(instruction),
-            1 => self.dec1(instruction),
-            2 => self.ent1(instruction),
-            3 => self.enn1(instruction),
-            _ => panic!("Illegal field in code 49 instruction"),
-        }
-    }
-
     fn inc1(&mut self, instruction: Word) {
-        let result = self.registers.i1.to_i32() + self.modified_address(instruction);
-        if result == 0 {
-            self.registers.i1.bytes = (Byte::ZERO, Byte::ZERO);
-            return;
-        }
-        self.registers.i1 = Index::from_i32(result).unwrap();
+        self.registers.i1 = self.index_sum(self.registers.i1, instruction);
     }
 
     fn dec1(&mut self, instruction: Word) {
-        self.inc1(instruction.with_opposite_sign());
+        self.registers.i1 = self.index_sum(self.registers.i1, instruction);
     }
 
     fn ent1(&mut self, instruction: Word) {
@@ -1613,27 +1647,12 @@ impl Computer {
         self.registers.i1 = self.index_to_enter(instruction).with_opposite_sign();
     }
 
-    fn handle_50(&mut self, instruction: Word) {
-        match instruction.field().value() {
-            0 => self.inc2(instruction),
-            1 => self.dec2(instruction),
-            2 => self.ent2(instruction),
-            3 => self.enn2(instruction),
-            _ => panic!("Illegal field in code 50 instruction"),
-        };
-    }
-
     fn inc2(&mut self, instruction: Word) {
-        let result = self.registers.i2.to_i32() + self.modified_address(instruction);
-        if result == 0 {
-            self.registers.i2.bytes = (Byte::ZERO, Byte::ZERO);
-            return;
-        }
-        self.registers.i2 = Index::from_i32(result).unwrap();
+        self.registers.i2 = self.index_sum(self.registers.i2, instruction);
     }
 
     fn dec2(&mut self, instruction: Word) {
-        self.inc2(instruction.with_opposite_sign());
+        self.registers.i2 = self.index_sum(self.registers.i2, instruction);
     }
 
     fn ent2(&mut self, instruction: Word) {
@@ -1644,27 +1663,12 @@ impl Computer {
         self.registers.i2 = self.index_to_enter(instruction).with_opposite_sign();
     }
 
-    fn handle_51(&mut self, instruction: Word) {
-        match instruction.field().value() {
-            0 => self.inc3(instruction),
-            1 => self.dec3(instruction),
-            2 => self.ent3(instruction),
-            3 => self.enn3(instruction),
-            _ => panic!("Illegal field in code 51 instruction"),
-        };
-    }
-
     fn inc3(&mut self, instruction: Word) {
-        let result = self.registers.i3.to_i32() + self.modified_address(instruction);
-        if result == 0 {
-            self.registers.i3.bytes = (Byte::ZERO, Byte::ZERO);
-            return;
-        }
-        self.registers.i3 = Index::from_i32(result).unwrap();
+        self.registers.i3 = self.index_sum(self.registers.i3, instruction);
     }
 
     fn dec3(&mut self, instruction: Word) {
-        self.inc3(instruction.with_opposite_sign());
+        self.registers.i3 = self.index_sum(self.registers.i3, instruction);
     }
 
     fn ent3(&mut self, instruction: Word) {
@@ -1675,27 +1679,12 @@ impl Computer {
         self.registers.i3 = self.index_to_enter(instruction).with_opposite_sign();
     }
 
-    fn handle_52(&mut self, instruction: Word) {
-        match instruction.field().value() {
-            0 => self.inc4(instruction),
-            1 => self.dec4(instruction),
-            2 => self.ent4(instruction),
-            3 => self.enn4(instruction),
-            _ => panic!("Illegal field in code 52 instruction"),
-        };
-    }
-
     fn inc4(&mut self, instruction: Word) {
-        let result = self.registers.i4.to_i32() + self.modified_address(instruction);
-        if result == 0 {
-            self.registers.i4.bytes = (Byte::ZERO, Byte::ZERO);
-            return;
-        }
-        self.registers.i4 = Index::from_i32(result).unwrap();
+        self.registers.i4 = self.index_sum(self.registers.i4, instruction);
     }
 
     fn dec4(&mut self, instruction: Word) {
-        self.inc4(instruction.with_opposite_sign());
+        self.registers.i4 = self.index_sum(self.registers.i4, instruction);
     }
 
     fn ent4(&mut self, instruction: Word) {
@@ -1706,27 +1695,12 @@ impl Computer {
         self.registers.i4 = self.index_to_enter(instruction).with_opposite_sign();
     }
 
-    fn handle_53(&mut self, instruction: Word) {
-        match instruction.field().value() {
-            0 => self.inc5(instruction),
-            1 => self.dec5(instruction),
-            2 => self.ent5(instruction),
-            3 => self.enn5(instruction),
-            _ => panic!("Illegal field in code 53 instruction"),
-        };
-    }
-
     fn inc5(&mut self, instruction: Word) {
-        let result = self.registers.i5.to_i32() + self.modified_address(instruction);
-        if result == 0 {
-            self.registers.i5.bytes = (Byte::ZERO, Byte::ZERO);
-            return;
-        }
-        self.registers.i5 = Index::from_i32(result).unwrap();
+        self.registers.i5 = self.index_sum(self.registers.i5, instruction);
     }
 
     fn dec5(&mut self, instruction: Word) {
-        self.inc5(instruction.with_opposite_sign());
+        self.registers.i5 = self.index_sum(self.registers.i5, instruction);
     }
 
     fn ent5(&mut self, instruction: Word) {
@@ -1737,27 +1711,12 @@ impl Computer {
         self.registers.i5 = self.index_to_enter(instruction).with_opposite_sign();
     }
 
-    fn handle_54(&mut self, instruction: Word) {
-        match instruction.field().value() {
-            0 => self.inc6(instruction),
-            1 => self.dec6(instruction),
-            2 => self.ent6(instruction),
-            3 => self.enn6(instruction),
-            _ => panic!("Illegal field in code 54 instruction"),
-        };
-    }
-
     fn inc6(&mut self, instruction: Word) {
-        let result = self.registers.i6.to_i32() + self.modified_address(instruction);
-        if result == 0 {
-            self.registers.i6.bytes = (Byte::ZERO, Byte::ZERO);
-            return;
-        }
-        self.registers.i6 = Index::from_i32(result).unwrap();
+        self.registers.i6 = self.index_sum(self.registers.i6, instruction);
     }
 
     fn dec6(&mut self, instruction: Word) {
-        self.inc6(instruction.with_opposite_sign());
+        self.registers.i6 = self.index_sum(self.registers.i6, instruction);
     }
 
     fn ent6(&mut self, instruction: Word) {
@@ -1768,30 +1727,14 @@ impl Computer {
         self.registers.i6 = self.index_to_enter(instruction).with_opposite_sign();
     }
 
-    fn handle_55(&mut self, instruction: Word) {
-        match instruction.field().value() {
-            0 => self.incx(instruction),
-            1 => self.decx(instruction),
-            2 => self.entx(instruction),
-            3 => self.ennx(instruction),
-            _ => panic!("Illegal field in code 55 instruction"),
-        }
-    }
-
     fn incx(&mut self, instruction: Word) {
-        let result = self.registers.x.to_i32() + self.modified_address(instruction);
-        if result == 0 {
-            self.registers.x.bytes = (Byte::ZERO, Byte::ZERO, Byte::ZERO, Byte::ZERO, Byte::ZERO);
-            return;
-        }
-        if result / 64_i32.pow(5) != 0 {
-            self.overflow = true;
-        }
-        self.registers.x = Word::from_i32(result % 64_i32.pow(5)).unwrap();
+        let m = self.address_word(instruction);
+        self.registers.x = self.register_sum(self.registers.x, m);
     }
 
     fn decx(&mut self, instruction: Word) {
-        self.incx(instruction.with_opposite_sign());
+        let m = self.address_word(instruction);
+        self.registers.x = self.register_sum(self.registers.x, -m);
     }
 
     fn entx(&mut self, instruction: Word) {
@@ -1803,120 +1746,82 @@ impl Computer {
     }
 
     fn do_comparison(&mut self, register_word: Word, memory_word: Word) {
-        self.comparison_indicator = Some(match register_word.to_i32() - memory_word.to_i32() {
-            ..=-1 => ComparisonIndicatorState::LESS,
-            0 => ComparisonIndicatorState::EQUAL,
-            1.. => ComparisonIndicatorState::GREATER,
-        });
+        self.comparison_indicator = Some(
+            match register_word.to_i64_in(self.byte_size) - memory_word.to_i64_in(self.byte_size) {
+                ..=-1 => ComparisonIndicatorState::LESS,
+                0 => ComparisonIndicatorState::EQUAL,
+                1.. => ComparisonIndicatorState::GREATER,
+            },
+        );
     }
 
     fn cmpa(&mut self, instruction: Word) {
         let (field_specifier, memory_contents) = self.field_specifier_and_contents(instruction);
-        let register_word = Word {
-            sign: Self::sign_to_load_or_store(&field_specifier, self.registers.a, Sign::PLUS),
-            bytes: Self::bytes_to_load_word(&field_specifier, self.registers.a),
-        };
-        let memory_word = Word {
-            sign: Self::sign_to_load_or_store(&field_specifier, memory_contents, Sign::PLUS),
-            bytes: Self::bytes_to_load_word(&field_specifier, memory_contents),
-        };
-        self.do_comparison(register_word, memory_word);
+        self.do_comparison(
+            self.registers.a.get_field(field_specifier),
+            memory_contents.get_field(field_specifier),
+        );
     }
 
     fn cmp1(&mut self, instruction: Word) {
         let (field_specifier, memory_contents) = self.field_specifier_and_contents(instruction);
-        let memory_word = Word {
-            sign: Self::sign_to_load_or_store(&field_specifier, memory_contents, Sign::PLUS),
-            bytes: Self::bytes_to_load_word(&field_specifier, memory_contents),
-        };
-        let index_value_as_word = Word::from_i32(self.registers.i1.to_i32()).unwrap();
-        let register_word = Word {
-            sign: Self::sign_to_load_or_store(&field_specifier, index_value_as_word, Sign::PLUS),
-            bytes: Self::bytes_to_load_word(&field_specifier, index_value_as_word),
-        };
-        self.do_comparison(register_word, memory_word);
+        self.do_comparison(
+            Self::index_to_word(&self.registers.i1).get_field(field_specifier),
+            memory_contents.get_field(field_specifier),
+        );
     }
 
     fn cmp2(&mut self, instruction: Word) {
         let (field_specifier, memory_contents) = self.field_specifier_and_contents(instruction);
-        let memory_word = Word {
-            sign: Self::sign_to_load_or_store(&field_specifier, memory_contents, Sign::PLUS),
-            bytes: Self::bytes_to_load_word(&field_specifier, memory_contents),
-        };
-        let index_value_as_word = Word::from_i32(self.registers.i2.to_i32()).unwrap();
-        let register_word = Word {
-            sign: Self::sign_to_load_or_store(&field_specifier, index_value_as_word, Sign::PLUS),
-            bytes: Self::bytes_to_load_word(&field_specifier, index_value_as_word),
-        };
-        self.do_comparison(register_word, memory_word);
+        self.do_comparison(
+            Self::index_to_word(&self.registers.i2).get_field(field_specifier),
+            memory_contents.get_field(field_specifier),
+        );
     }
 
     fn cmp3(&mut self, instruction: Word) {
         let (field_specifier, memory_contents) = self.field_specifier_and_contents(instruction);
-        let memory_word = Word {
-            sign: Self::sign_to_load_or_store(&field_specifier, memory_contents, Sign::PLUS),
-            bytes: Self::bytes_to_load_word(&field_specifier, memory_contents),
-        };
-        let index_value_as_word = Word::from_i32(self.registers.i3.to_i32()).unwrap();
-        let register_word = Word {
-            sign: Self::sign_to_load_or_store(&field_specifier, index_value_as_word, Sign::PLUS),
-            bytes: Self::bytes_to_load_word(&field_specifier, index_value_as_word),
-        };
-        self.do_comparison(register_word, memory_word);
+        self.do_comparison(
+            Self::index_to_word(&self.registers.i3).get_field(field_specifier),
+            memory_contents.get_field(field_specifier),
+        );
     }
 
     fn cmp4(&mut self, instruction: Word) {
         let (field_specifier, memory_contents) = self.field_specifier_and_contents(instruction);
-        let memory_word = Word {
-            sign: Self::sign_to_load_or_store(&field_specifier, memory_contents, Sign::PLUS),
-            bytes: Self::bytes_to_load_word(&field_specifier, memory_contents),
-        };
-        let index_value_as_word = Word::from_i32(self.registers.i4.to_i32()).unwrap();
-        let register_word = Word {
-            sign: Self::sign_to_load_or_store(&field_specifier, index_value_as_word, Sign::PLUS),
-            bytes: Self::bytes_to_load_word(&field_specifier, index_value_as_word),
-        };
-        self.do_comparison(register_word, memory_word);
+        self.do_comparison(
+            Self::index_to_word(&self.registers.i4).get_field(field_specifier),
+            memory_contents.get_field(field_specifier),
+        );
     }
 
     fn cmp5(&mut self, instruction: Word) {
         let (field_specifier, memory_contents) = self.field_specifier_and_contents(instruction);
-        let memory_word = Word {
-            sign: Self::sign_to_load_or_store(&field_specifier, memory_contents, Sign::PLUS),
-            bytes: Self::bytes_to_load_word(&field_specifier, memory_contents),
-        };
-        let index_value_as_word = Word::from_i32(self.registers.i5.to_i32()).unwrap();
-        let register_word = Word {
-            sign: Self::sign_to_load_or_store(&field_specifier, index_value_as_word, Sign::PLUS),
-            bytes: Self::bytes_to_load_word(&field_specifier, index_value_as_word),
-        };
-        self.do_comparison(register_word, memory_word);
+        self.do_comparison(
+            Self::index_to_word(&self.registers.i5).get_field(field_specifier),
+            memory_contents.get_field(field_specifier),
+        );
     }
 
     fn cmp6(&mut self, instruction: Word) {
         let (field_specifier, memory_contents) = self.field_specifier_and_contents(instruction);
-        let memory_word = Word {
-            sign: Self::sign_to_load_or_store(&field_specifier, memory_contents, Sign::PLUS),
-            bytes: Self::bytes_to_load_word(&field_specifier, memory_contents),
-        };
-        let index_value_as_word = Word::from_i32(self.registers.i6.to_i32()).unwrap();
-        let register_word = Word {
-            sign: Self::sign_to_load_or_store(&field_specifier, index_value_as_word, Sign::PLUS),
-            bytes: Self::bytes_to_load_word(&field_specifier, index_value_as_word),
-        };
-        self.do_comparison(register_word, memory_word);
+        self.do_comparison(
+            Self::index_to_word(&self.registers.i6).get_field(field_specifier),
+            memory_contents.get_field(field_specifier),
+        );
     }
 
     fn cmpx(&mut self, instruction: Word) {
         let (field_specifier, memory_contents) = self.field_specifier_and_contents(instruction);
-        let register_word = Word {
-            sign: Self::sign_to_load_or_store(&field_specifier, self.registers.x, Sign::PLUS),
-            bytes: Self::bytes_to_load_word(&field_specifier, self.registers.x),
-        };
-        let memory_word = Word {
-            sign: Self::sign_to_load_or_store(&field_specifier, memory_contents, Sign::PLUS),
-            bytes: Self::bytes_to_load_word(&field_specifier, memory_contents),
-        };
-        self.do_comparison(register_word, memory_word);
+        self.do_comparison(
+            self.registers.x.get_field(field_specifier),
+            memory_contents.get_field(field_specifier),
+        );
+    }
+}
+
+impl Default for Computer {
+    fn default() -> Self {
+        Self::new()
     }
 }
@@ -0,0 +1,555 @@
+pub mod image;
+
+use std::{io::Write, path::Path};
+
+use crate::data_types::{Byte, Sign, Word};
+
+use self::image::{ImageError, ImageFormat};
+
+// Unit numbers follow Knuth: 0-7 are tape units, 8-15 disks, 16 the card reader, 17 the card punch,
+// 18 the line printer, 19 the typewriter terminal and 20 paper tape. Transfers finish as soon as
+// the instruction runs, so devices are never busy unless a test marks one as not ready.
+pub const UNIT_COUNT: i32 = 21;
+pub const CARD_READER: i32 = 16;
+pub const CARD_PUNCH: i32 = 17;
+pub const LINE_PRINTER: i32 = 18;
+pub const TERMINAL: i32 = 19;
+pub const PAPER_TAPE: i32 = 20;
+
+pub const TAPE_BLOCK_SIZE: usize = image::BLOCK_SIZE;
+pub const DISK_BLOCK_SIZE: usize = image::BLOCK_SIZE;
+pub const CARD_BLOCK_SIZE: usize = 16;
+pub const PRINTER_BLOCK_SIZE: usize = 24;
+pub const TERMINAL_BLOCK_SIZE: usize = 14;
+pub const PAPER_TAPE_BLOCK_SIZE: usize = 14;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DeviceError {
+    NoSuchUnit(i32),
+    NotAttached(i32),
+    NotReadable(i32),
+    NotWriteable(i32),
+    InvalidPosition(i32),
+    EndOfInput,
+    LineTooLong(usize),
+    BadCharacter(char),
+    BadByte(i32),
+    Io(String),
+}
+
+impl std::fmt::Display for DeviceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoSuchUnit(x) => write!(f, "there is no unit {}", x),
+            Self::NotAttached(x) => write!(f, "nothing is attached to unit {}", x),
+            Self::NotReadable(x) => write!(f, "unit {} can't be read from", x),
+            Self::NotWriteable(x) => write!(f, "unit {} can't be written to", x),
+            Self::InvalidPosition(x) => write!(f, "invalid position {}", x),
+            Self::EndOfInput => write!(f, "no input left"),
+            Self::LineTooLong(x) => write!(f, "input line longer than {} characters", x),
+            Self::BadCharacter(x) => write!(f, "{:?} is not a MIX character", x),
+            Self::BadByte(x) => write!(f, "byte {} has no character", x),
+            Self::Io(x) => write!(f, "{}", x),
+        }
+    }
+}
+
+pub trait Device {
+    fn block_size(&self) -> usize;
+
+    fn is_ready(&self) -> bool {
+        true
+    }
+
+    // IOC, with M as the operation and rX for devices that use it
+    fn control(&mut self, _operation: i32, _position: i32) -> Result<(), DeviceError> {
+        Ok(())
+    }
+
+    fn as_readable(&mut self) -> Option<&mut dyn Readable> {
+        None
+    }
+
+    fn as_writeable(&mut self) -> Option<&mut dyn Writeable> {
+        None
+    }
+}
+
+pub trait Readable: Device {
+    // position is the contents of rX, only disks look at it
+    fn read(&mut self, position: i32) -> Result<Vec<Word>, DeviceError>;
+}
+
+pub trait Writeable: Device {
+    fn write(&mut self, position: i32, words: &[Word]) -> Result<(), DeviceError>;
+}
+
+pub struct TapeUnit {
+    pub ready: bool,
+    pub capacity: usize,
+    pub position: usize,
+    pub data: Vec<[Word; TAPE_BLOCK_SIZE]>,
+}
+
+impl TapeUnit {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            ready: true,
+            capacity,
+            position: 0,
+            data: vec![[Word::ZERO; TAPE_BLOCK_SIZE]; capacity],
+        }
+    }
+
+    // fills blocks from the start of the tape, padding the last one with +0
+    pub fn from_words(capacity: usize, words: &[Word]) -> Self {
+        let mut tape = Self::new(capacity.max(words.len().div_ceil(TAPE_BLOCK_SIZE)));
+        for (idx, word) in words.iter().enumerate() {
+            tape.data[idx / TAPE_BLOCK_SIZE][idx % TAPE_BLOCK_SIZE] = *word;
+        }
+        tape
+    }
+
+    pub fn from_blocks(data: Vec<[Word; TAPE_BLOCK_SIZE]>) -> Self {
+        Self {
+            ready: true,
+            capacity: data.len(),
+            position: 0,
+            data,
+        }
+    }
+
+    // the format is handed back so the tape can be saved the way it came
+    pub fn load_image(path: impl AsRef<Path>) -> Result<(Self, ImageFormat), ImageError> {
+        let (blocks, format) = image::read_image_from_file(path)?;
+        Ok((Self::from_blocks(blocks), format))
+    }
+
+    pub fn save_image(&self, path: impl AsRef<Path>, format: ImageFormat) -> std::io::Result<()> {
+        image::write_image_to_file(&self.data, format, path)
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    fn current_block(&self) -> Result<usize, DeviceError> {
+        if self.position < self.capacity {
+            Ok(self.position)
+        } else {
+            Err(DeviceError::InvalidPosition(self.position as i32))
+        }
+    }
+}
+
+impl Device for TapeUnit {
+    fn block_size(&self) -> usize {
+        TAPE_BLOCK_SIZE
+    }
+
+    fn is_ready(&self) -> bool {
+        self.ready
+    }
+
+    // M = 0 rewinds, otherwise the tape skips M blocks forwards or backwards
+    fn control(&mut self, operation: i32, _position: i32) -> Result<(), DeviceError> {
+        if operation == 0 {
+            self.position = 0;
+            return Ok(());
+        }
+        // skipping back stops at the start of the tape, as rewinding does
+        let position = (self.position as i64 + operation as i64).max(0);
+        if position > self.capacity as i64 {
+            return Err(DeviceError::InvalidPosition(position as i32));
+        }
+        self.position = position as usize;
+        Ok(())
+    }
+
+    fn as_readable(&mut self) -> Option<&mut dyn Readable> {
+        Some(self)
+    }
+
+    fn as_writeable(&mut self) -> Option<&mut dyn Writeable> {
+        Some(self)
+    }
+}
+
+impl Readable for TapeUnit {
+    fn read(&mut self, _position: i32) -> Result<Vec<Word>, DeviceError> {
+        let block = self.current_block()?;
+        self.position += 1;
+        Ok(self.data[block].to_vec())
+    }
+}
+
+impl Writeable for TapeUnit {
+    fn write(&mut self, _position: i32, words: &[Word]) -> Result<(), DeviceError> {
+        let block = self.current_block()?;
+        self.data[block].copy_from_slice(words);
+        self.position += 1;
+        Ok(())
+    }
+}
+
+pub struct DiskUnit {
+    pub capacity: usize,
+    pub data: Vec<[Word; DISK_BLOCK_SIZE]>,
+}
+
+impl DiskUnit {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            data: vec![[Word::ZERO; DISK_BLOCK_SIZE]; capacity],
+        }
+    }
+
+    pub fn from_words(capacity: usize, words: &[Word]) -> Self {
+        let mut disk = Self::new(capacity.max(words.len().div_ceil(DISK_BLOCK_SIZE)));
+        for (idx, word) in words.iter().enumerate() {
+            disk.data[idx / DISK_BLOCK_SIZE][idx % DISK_BLOCK_SIZE] = *word;
+        }
+        disk
+    }
+
+    pub fn from_blocks(data: Vec<[Word; DISK_BLOCK_SIZE]>) -> Self {
+        Self {
+            capacity: data.len(),
+            data,
+        }
+    }
+
+    pub fn load_image(path: impl AsRef<Path>) -> Result<(Self, ImageFormat), ImageError> {
+        let (blocks, format) = image::read_image_from_file(path)?;
+        Ok((Self::from_blocks(blocks), format))
+    }
+
+    pub fn save_image(&self, path: impl AsRef<Path>, format: ImageFormat) -> std::io::Result<()> {
+        image::write_image_to_file(&self.data, format, path)
+    }
+
+    fn block(&self, position: i32) -> Result<usize, DeviceError> {
+        match usize::try_from(position) {
+            Ok(x) if x < self.capacity => Ok(x),
+            _ => Err(DeviceError::InvalidPosition(position)),
+        }
+    }
+}
+
+impl Device for DiskUnit {
+    fn block_size(&self) -> usize {
+        DISK_BLOCK_SIZE
+    }
+
+    // seeking is instantaneous, but a bad track is still an error
+    fn control(&mut self, _operation: i32, position: i32) -> Result<(), DeviceError> {
+        self.block(position).map(|_| ())
+    }
+
+    fn as_readable(&mut self) -> Option<&mut dyn Readable> {
+        Some(self)
+    }
+
+    fn as_writeable(&mut self) -> Option<&mut dyn Writeable> {
+        Some(self)
+    }
+}
+
+impl Readable for DiskUnit {
+    fn read(&mut self, position: i32) -> Result<Vec<Word>, DeviceError> {
+        let block = self.block(position)?;
+        Ok(self.data[block].to_vec())
+    }
+}
+
+impl Writeable for DiskUnit {
+    fn write(&mut self, position: i32, words: &[Word]) -> Result<(), DeviceError> {
+        let block = self.block(position)?;
+        self.data[block].copy_from_slice(words);
+        Ok(())
+    }
+}
+
+// Character devices read and write lines of text, five characters to a word. Input lines shorter
+// than a block are padded with spaces and output lines have trailing spaces trimmed.
+pub struct TextInput {
+    block_size: usize,
+    pub lines: Vec<String>,
+    pub position: usize,
+}
+
+impl TextInput {
+    pub fn card_reader(lines: Vec<String>) -> Self {
+        Self {
+            block_size: CARD_BLOCK_SIZE,
+            lines,
+            position: 0,
+        }
+    }
+
+    pub fn paper_tape(lines: Vec<String>) -> Self {
+        Self {
+            block_size: PAPER_TAPE_BLOCK_SIZE,
+            lines,
+            position: 0,
+        }
+    }
+
+    pub fn terminal(lines: Vec<String>) -> Self {
+        Self {
+            block_size: TERMINAL_BLOCK_SIZE,
+            lines,
+            position: 0,
+        }
+    }
+
+    fn next_block(&mut self) -> Result<Vec<Word>, DeviceError> {
+        let line = self
+            .lines
+            .get(self.position)
+            .ok_or(DeviceError::EndOfInput)?;
+        let words = words_from_text(line, self.block_size)?;
+        self.position += 1;
+        Ok(words)
+    }
+}
+
+impl Device for TextInput {
+    fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    // only paper tape can be rewound, the card reader just ignores IOC
+    fn control(&mut self, operation: i32, _position: i32) -> Result<(), DeviceError> {
+        if self.block_size == PAPER_TAPE_BLOCK_SIZE && operation == 0 {
+            self.position = 0;
+        }
+        Ok(())
+    }
+
+    fn as_readable(&mut self) -> Option<&mut dyn Readable> {
+        Some(self)
+    }
+}
+
+impl Readable for TextInput {
+    fn read(&mut self, _position: i32) -> Result<Vec<Word>, DeviceError> {
+        self.next_block()
+    }
+}
+
+pub struct TextOutput {
+    block_size: usize,
+    pub lines: Vec<String>,
+    // every line is also written here as it's produced, e.g. to stdout or a file
+    pub echo: Option<Box<dyn Write + Send>>,
+}
+
+impl TextOutput {
+    pub fn card_punch() -> Self {
+        Self {
+            block_size: CARD_BLOCK_SIZE,
+            lines: Vec::new(),
+            echo: None,
+        }
+    }
+
+    pub fn line_printer() -> Self {
+        Self {
+            block_size: PRINTER_BLOCK_SIZE,
+            lines: Vec::new(),
+            echo: None,
+        }
+    }
+
+    pub fn terminal() -> Self {
+        Self {
+            block_size: TERMINAL_BLOCK_SIZE,
+            lines: Vec::new(),
+            echo: None,
+        }
+    }
+
+    pub fn with_echo(mut self, echo: Box<dyn Write + Send>) -> Self {
+        self.echo = Some(echo);
+        self
+    }
+
+    fn push_line(&mut self, line: String) -> Result<(), DeviceError> {
+        if let Some(echo) = self.echo.as_mut() {
+            writeln!(echo, "{}", line)
+                .and_then(|_| echo.flush())
+                .map_err(|e| DeviceError::Io(e.to_string()))?;
+        }
+        self.lines.push(line);
+        Ok(())
+    }
+}
+
+impl Device for TextOutput {
+    fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    // IOC 0 on the line printer skips to the top of the next page, shown as an empty line
+    fn control(&mut self, operation: i32, _position: i32) -> Result<(), DeviceError> {
+        if self.block_size == PRINTER_BLOCK_SIZE && operation == 0 {
+            self.push_line(String::new())?;
+        }
+        Ok(())
+    }
+
+    fn as_writeable(&mut self) -> Option<&mut dyn Writeable> {
+        Some(self)
+    }
+}
+
+impl Writeable for TextOutput {
+    fn write(&mut self, _position: i32, words: &[Word]) -> Result<(), DeviceError> {
+        let line = text_from_words(words)?;
+        self.push_line(line)
+    }
+}
+
+// The terminal reads typed lines and prints lines back, so it's an input and output glued together
+pub struct Terminal {
+    pub input: TextInput,
+    pub output: TextOutput,
+}
+
+impl Terminal {
+    pub fn new(lines: Vec<String>) -> Self {
+        Self {
+            input: TextInput::terminal(lines),
+            output: TextOutput::terminal(),
+        }
+    }
+}
+
+impl Device for Terminal {
+    fn block_size(&self) -> usize {
+        TERMINAL_BLOCK_SIZE
+    }
+
+    fn as_readable(&mut self) -> Option<&mut dyn Readable> {
+        Some(self)
+    }
+
+    fn as_writeable(&mut self) -> Option<&mut dyn Writeable> {
+        Some(self)
+    }
+}
+
+impl Readable for Terminal {
+    fn read(&mut self, position: i32) -> Result<Vec<Word>, DeviceError> {
+        self.input.read(position)
+    }
+}
+
+impl Writeable for Terminal {
+    fn write(&mut self, position: i32, words: &[Word]) -> Result<(), DeviceError> {
+        self.output.write(position, words)
+    }
+}
+
+pub fn words_from_text(line: &str, block_size: usize) -> Result<Vec<Word>, DeviceError> {
+    let chars: Vec<char> = line.trim_end().chars().collect();
+    if chars.len() > block_size * 5 {
+        return Err(DeviceError::LineTooLong(block_size * 5));
+    }
+    let mut values = Vec::with_capacity(block_size * 5);
+    for c in chars {
+        let byte = Byte::from_char(c).map_err(|_| DeviceError::BadCharacter(c))?;
+        values.push(byte.to_i32());
+    }
+    values.resize(block_size * 5, 0);
+    Ok(values
+        .chunks(5)
+        .map(|x| Word::from_byte_values(Sign::PLUS, x[0], x[1], x[2], x[3], x[4]).unwrap())
+        .collect())
+}
+
+pub fn text_from_words(words: &[Word]) -> Result<String, DeviceError> {
+    let mut line = String::new();
+    for word in words {
+        let bytes = [
+            word.bytes.0,
+            word.bytes.1,
+            word.bytes.2,
+            word.bytes.3,
+            word.bytes.4,
+        ];
+        for byte in bytes {
+            line.push(
+                byte.to_char()
+                    .map_err(|_| DeviceError::BadByte(byte.to_i32()))?,
+            );
+        }
+    }
+    Ok(line.trim_end().to_string())
+}
+
+pub struct Devices {
+    pub tapes: [Option<TapeUnit>; 8],
+    pub disks: [Option<DiskUnit>; 8],
+    pub card_reader: Option<TextInput>,
+    pub card_punch: Option<TextOutput>,
+    pub line_printer: Option<TextOutput>,
+    pub terminal: Option<Terminal>,
+    pub paper_tape: Option<TextInput>,
+}
+
+impl Devices {
+    // nothing attached, see Computer::new for the default set up
+    pub fn new() -> Self {
+        Self {
+            tapes: Default::default(),
+            disks: Default::default(),
+            card_reader: None,
+            card_punch: None,
+            line_printer: None,
+            terminal: None,
+            paper_tape: None,
+        }
+    }
+
+    pub fn unit(&mut self, unit: i32) -> Result<&mut dyn Device, DeviceError> {
+        let device: Option<&mut dyn Device> = match unit {
+            0..=7 => self.tapes[unit as usize]
+                .as_mut()
+                .map(|x| x as &mut dyn Device),
+            8..=15 => self.disks[unit as usize - 8]
+                .as_mut()
+                .map(|x| x as &mut dyn Device),
+            CARD_READER => self.card_reader.as_mut().map(|x| x as &mut dyn Device),
+            CARD_PUNCH => self.card_punch.as_mut().map(|x| x as &mut dyn Device),
+            LINE_PRINTER => self.line_printer.as_mut().map(|x| x as &mut dyn Device),
+            TERMINAL => self.terminal.as_mut().map(|x| x as &mut dyn Device),
+            PAPER_TAPE => self.paper_tape.as_mut().map(|x| x as &mut dyn Device),
+            _ => return Err(DeviceError::NoSuchUnit(unit)),
+        };
+        device.ok_or(DeviceError::NotAttached(unit))
+    }
+}
+
+impl Default for Devices {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[test]
+fn text_should_round_trip_through_words() {
+    let words = words_from_text("HELLO, WORLD", CARD_BLOCK_SIZE).unwrap();
+    assert_eq!(words.len(), CARD_BLOCK_SIZE);
+    assert_eq!(text_from_words(&words).unwrap(), "HELLO, WORLD");
+    assert_eq!(
+        words_from_text("hello", CARD_BLOCK_SIZE),
+        Err(DeviceError::BadCharacter('h'))
+    );
+    assert_eq!(
+        words_from_text(&"A".repeat(81), CARD_BLOCK_SIZE),
+        Err(DeviceError::LineTooLong(80))
+    );
+}
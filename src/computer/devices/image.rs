@@ -0,0 +1,304 @@
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use crate::data_types::{Byte, ByteSize, Sign, Word};
+
+// Tape and disk images hold the blocks on a reel or disk, 100 words to a block, so they can be
+// prepared ahead of a run and kept afterwards. There are two formats and readers accept either.
+//
+// Binary images start with the 7 bytes "MIXTAPE" and a version byte, then the number of blocks as
+// a little endian u32. Each word takes 6 bytes: 0 for + or 1 for -, then the five byte values.
+//
+// Byte values can go up to 99 so images work for decimal machines too. Signed numbers are always
+// split into binary bytes, and words with a byte too big for that are written as byte values.
+//
+// Text images are meant for editing by hand, with one word per line:
+//
+//   MIX-TAPE 1
+//   # comments and blank lines are ignored
+//   -42                     (a signed number, -0 is negative zero)
+//   + 0 1 2 3 4             (sign then byte values)
+//   ALF "HELLO"             (five characters)
+//   BLOCK                   (fills the rest of the block with +0 and starts the next)
+//
+// A block left unfinished at the end of the file is filled with +0 as well.
+//
+// The version number goes up whenever the meaning of an existing record changes.
+pub const IMAGE_VERSION: i32 = 1;
+
+pub const BLOCK_SIZE: usize = 100;
+
+const BINARY_MAGIC: &[u8; 7] = b"MIXTAPE";
+const TEXT_HEADER: &str = "MIX-TAPE";
+
+pub type Block = [Word; BLOCK_SIZE];
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ImageFormat {
+    BINARY,
+    // text with every word as a number
+    TEXT,
+    // text with words written as ALF where they're all characters
+    ALF,
+}
+
+#[derive(Debug)]
+pub enum ImageError {
+    Io(io::Error),
+    BadHeader(String),
+    UnsupportedVersion(i32),
+    BadLine(usize, String),
+    BadWord(usize),
+    Truncated,
+}
+
+impl From<io::Error> for ImageError {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl std::fmt::Display for ImageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "{}", e),
+            Self::BadHeader(x) => write!(f, "not a tape image, header was {:?}", x),
+            Self::UnsupportedVersion(x) => write!(f, "unsupported tape image version {}", x),
+            Self::BadLine(number, line) => write!(f, "line {}: can't read {:?}", number, line),
+            Self::BadWord(x) => write!(f, "word {} isn't a valid MIX word", x),
+            Self::Truncated => write!(f, "image ends part way through a block"),
+        }
+    }
+}
+
+// reads either format, working out which from the first bytes
+pub fn read_image(mut reader: impl BufRead) -> Result<(Vec<Block>, ImageFormat), ImageError> {
+    if reader.fill_buf()?.starts_with(BINARY_MAGIC) {
+        return Ok((read_binary(reader)?, ImageFormat::BINARY));
+    }
+    Ok((read_text(reader)?, ImageFormat::TEXT))
+}
+
+pub fn read_image_from_file(
+    path: impl AsRef<Path>,
+) -> Result<(Vec<Block>, ImageFormat), ImageError> {
+    read_image(BufReader::new(File::open(path)?))
+}
+
+pub fn write_image(
+    blocks: &[Block],
+    format: ImageFormat,
+    writer: &mut impl Write,
+) -> io::Result<()> {
+    match format {
+        ImageFormat::BINARY => write_binary(blocks, writer),
+        ImageFormat::TEXT => write_text(blocks, false, writer),
+        ImageFormat::ALF => write_text(blocks, true, writer),
+    }
+}
+
+pub fn write_image_to_file(
+    blocks: &[Block],
+    format: ImageFormat,
+    path: impl AsRef<Path>,
+) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_image(blocks, format, &mut writer)?;
+    writer.flush()
+}
+
+fn read_binary(mut reader: impl Read) -> Result<Vec<Block>, ImageError> {
+    let mut header = [0; 12];
+    reader.read_exact(&mut header).map_err(truncated)?;
+    let version = header[7] as i32;
+    if version != IMAGE_VERSION {
+        return Err(ImageError::UnsupportedVersion(version));
+    }
+    let count = u32::from_le_bytes([header[8], header[9], header[10], header[11]]) as usize;
+    let mut blocks = Vec::with_capacity(count);
+    let mut buffer = [0; 6 * BLOCK_SIZE];
+    for block_idx in 0..count {
+        reader.read_exact(&mut buffer).map_err(truncated)?;
+        let mut block = [Word::ZERO; BLOCK_SIZE];
+        for (word_idx, (word, bytes)) in block.iter_mut().zip(buffer.chunks(6)).enumerate() {
+            let sign = match bytes[0] {
+                0 => Sign::PLUS,
+                1 => Sign::MINUS,
+                _ => return Err(ImageError::BadWord(block_idx * BLOCK_SIZE + word_idx)),
+            };
+            let [b1, b2, b3, b4, b5] = [1, 2, 3, 4, 5].map(|idx| bytes[idx] as i32);
+            *word = Word::from_byte_values_in(sign, [b1, b2, b3, b4, b5], ByteSize::DECIMAL)
+                .map_err(|_| ImageError::BadWord(block_idx * BLOCK_SIZE + word_idx))?;
+        }
+        blocks.push(block);
+    }
+    Ok(blocks)
+}
+
+fn truncated(e: io::Error) -> ImageError {
+    match e.kind() {
+        io::ErrorKind::UnexpectedEof => ImageError::Truncated,
+        _ => ImageError::Io(e),
+    }
+}
+
+fn write_binary(blocks: &[Block], writer: &mut impl Write) -> io::Result<()> {
+    writer.write_all(BINARY_MAGIC)?;
+    writer.write_all(&[IMAGE_VERSION as u8])?;
+    writer.write_all(&(blocks.len() as u32).to_le_bytes())?;
+    for block in blocks {
+        for word in block {
+            let sign = match word.sign {
+                Sign::PLUS => 0,
+                Sign::MINUS => 1,
+            };
+            writer.write_all(&[sign])?;
+            writer.write_all(&byte_values(word).map(|x| x as u8))?;
+        }
+    }
+    Ok(())
+}
+
+fn read_text(reader: impl BufRead) -> Result<Vec<Block>, ImageError> {
+    let mut lines = reader.lines();
+    let header = lines.next().transpose()?.unwrap_or_default();
+    match header.split_once(' ') {
+        Some((TEXT_HEADER, version)) => match version.trim().parse::<i32>() {
+            Ok(IMAGE_VERSION) => (),
+            Ok(x) => return Err(ImageError::UnsupportedVersion(x)),
+            Err(_) => return Err(ImageError::BadHeader(header)),
+        },
+        _ => return Err(ImageError::BadHeader(header)),
+    }
+
+    let mut blocks = Vec::new();
+    let mut block = [Word::ZERO; BLOCK_SIZE];
+    let mut word_idx = 0;
+    for (idx, line) in lines.enumerate() {
+        let line = line?;
+        // the header was line 1
+        let line_number = idx + 2;
+        let record = line.trim();
+        if record.is_empty() || record.starts_with('#') {
+            continue;
+        }
+        if record == "BLOCK" {
+            blocks.push(block);
+            block = [Word::ZERO; BLOCK_SIZE];
+            word_idx = 0;
+            continue;
+        }
+        if word_idx == BLOCK_SIZE {
+            blocks.push(block);
+            block = [Word::ZERO; BLOCK_SIZE];
+            word_idx = 0;
+        }
+        block[word_idx] =
+            parse_word(record).ok_or_else(|| ImageError::BadLine(line_number, line.clone()))?;
+        word_idx += 1;
+    }
+    if word_idx > 0 {
+        blocks.push(block);
+    }
+    Ok(blocks)
+}
+
+fn write_text(blocks: &[Block], alf: bool, writer: &mut impl Write) -> io::Result<()> {
+    writeln!(writer, "{} {}", TEXT_HEADER, IMAGE_VERSION)?;
+    for (idx, block) in blocks.iter().enumerate() {
+        writeln!(writer, "# block {}", idx)?;
+        // trailing +0 words are left for BLOCK to fill in
+        let used = block
+            .iter()
+            .rposition(|x| *x != Word::ZERO)
+            .map_or(0, |x| x + 1);
+        for word in block[..used].iter() {
+            match alf.then(|| alf_text(word)).flatten() {
+                Some(text) => writeln!(writer, "ALF \"{}\"", text)?,
+                None => writeln!(writer, "{}", format_word(word))?,
+            }
+        }
+        writeln!(writer, "BLOCK")?;
+    }
+    Ok(())
+}
+
+pub fn parse_word(record: &str) -> Option<Word> {
+    if let Some(text) = record.strip_prefix("ALF") {
+        let text = text.trim().strip_prefix('"')?.strip_suffix('"')?;
+        let mut values = [0; 5];
+        let chars: Vec<char> = text.chars().collect();
+        if chars.len() > 5 {
+            return None;
+        }
+        for (value, c) in values.iter_mut().zip(chars) {
+            *value = Byte::from_char(c).ok()?.to_i32();
+        }
+        let [b1, b2, b3, b4, b5] = values;
+        return Word::from_byte_values(Sign::PLUS, b1, b2, b3, b4, b5).ok();
+    }
+    if record.contains(' ') {
+        return Word::from_byte_string_in(record, ByteSize::DECIMAL);
+    }
+    record.parse().ok()
+}
+
+pub fn format_word(word: &Word) -> String {
+    if byte_values(word)
+        .iter()
+        .any(|x| *x >= ByteSize::BINARY.values())
+    {
+        return word.to_byte_string();
+    }
+    word.to_string()
+}
+
+// the five characters in a positive word, if they all have one
+pub fn alf_text(word: &Word) -> Option<String> {
+    if word.sign == Sign::MINUS {
+        return None;
+    }
+    let bytes = [
+        word.bytes.0,
+        word.bytes.1,
+        word.bytes.2,
+        word.bytes.3,
+        word.bytes.4,
+    ];
+    bytes.iter().map(|x| x.to_char().ok()).collect()
+}
+
+fn byte_values(word: &Word) -> [i32; 5] {
+    [
+        word.bytes.0.to_i32(),
+        word.bytes.1.to_i32(),
+        word.bytes.2.to_i32(),
+        word.bytes.3.to_i32(),
+        word.bytes.4.to_i32(),
+    ]
+}
+
+#[test]
+fn words_should_parse_in_every_text_form() {
+    assert_eq!(parse_word("-42"), Word::from_i32(-42).ok());
+    assert_eq!(parse_word("-0"), Some(Word::ZERO.with_sign(Sign::MINUS)));
+    assert_eq!(
+        parse_word("+ 0 1 2 3 4"),
+        Word::from_byte_values(Sign::PLUS, 0, 1, 2, 3, 4).ok()
+    );
+    assert_eq!(
+        parse_word("ALF \"AB C\""),
+        Word::from_byte_values(Sign::PLUS, 1, 2, 0, 3, 0).ok()
+    );
+    assert_eq!(parse_word("ALF \"TOO LONG\""), None);
+    assert_eq!(parse_word("1073741824"), None);
+    assert_eq!(format_word(&Word::ZERO.with_sign(Sign::MINUS)), "-0");
+    let decimal =
+        Word::from_byte_values_in(Sign::PLUS, [0, 0, 0, 10, 99], ByteSize::DECIMAL).unwrap();
+    assert_eq!(format_word(&decimal), "+ 0 0 0 10 99");
+    assert_eq!(parse_word("+ 0 0 0 10 99"), Some(decimal));
+}
@@ -0,0 +1,258 @@
+use std::cmp::Ordering;
+
+use crate::data_types::{ByteSize, Sign, Word};
+
+// Knuth's floating point format (section 4.2.1): the first byte holds the exponent e in excess
+// q = b/2 and the other four bytes a fraction f, for a value of f * b^(e-q) with 0 <= f < 1.
+// Results are normalized, so the first byte of the fraction isn't zero unless the value is, and
+// rounded to four bytes with ties going to an even last byte. When the exponent doesn't fit in a
+// byte the overflow flag comes back set and the exponent is wrapped, as the hardware would.
+//
+// Every operation works out the exact answer first and rounds once at the end, so the result is
+// the representable number nearest the true one.
+
+const FRACTION_BYTES: i32 = 4;
+
+// how far apart two exponents can be before the smaller operand only matters for rounding
+const SIGNIFICANT_SHIFT: i32 = 2 * FRACTION_BYTES + 2;
+
+// fraction * base^exponent, exactly
+#[derive(Debug, Clone, Copy)]
+struct Exact {
+    negative: bool,
+    fraction: u128,
+    exponent: i32,
+}
+
+fn base(size: ByteSize) -> u128 {
+    size.values() as u128
+}
+
+fn excess(size: ByteSize) -> i32 {
+    size.values() / 2
+}
+
+fn unpack(word: Word, size: ByteSize) -> Exact {
+    let magnitude = word.to_i64_in(size).unsigned_abs() as u128;
+    let scale = base(size).pow(FRACTION_BYTES as u32);
+    Exact {
+        negative: word.sign == Sign::MINUS,
+        fraction: magnitude % scale,
+        exponent: (magnitude / scale) as i32 - excess(size) - FRACTION_BYTES,
+    }
+}
+
+fn digits(mut value: u128, base: u128) -> i32 {
+    let mut count = 0;
+    while value > 0 {
+        value /= base;
+        count += 1;
+    }
+    count
+}
+
+// divides by base^shift, rounding to nearest and ties to even
+fn round_shift(value: u128, shift: u32, base: u128) -> u128 {
+    let divisor = base.pow(shift);
+    let (quotient, remainder) = (value / divisor, value % divisor);
+    match (2 * remainder).cmp(&divisor) {
+        Ordering::Less => quotient,
+        Ordering::Equal => quotient + quotient % 2,
+        Ordering::Greater => quotient + 1,
+    }
+}
+
+fn make_word(negative: bool, magnitude: u128, size: ByteSize) -> Word {
+    let sign = match negative {
+        true => Sign::MINUS,
+        false => Sign::PLUS,
+    };
+    Word::from_i64_in(magnitude as i64, size)
+        .unwrap()
+        .with_sign(sign)
+}
+
+fn pack(value: Exact, size: ByteSize) -> (Word, bool) {
+    if value.fraction == 0 {
+        return (Word::ZERO, false);
+    }
+    let base = base(size);
+    let length = digits(value.fraction, base);
+    let (mut fraction, mut exponent) = match length > FRACTION_BYTES {
+        true => {
+            let shift = length - FRACTION_BYTES;
+            (
+                round_shift(value.fraction, shift as u32, base),
+                value.exponent + shift,
+            )
+        }
+        false => {
+            let shift = FRACTION_BYTES - length;
+            (
+                value.fraction * base.pow(shift as u32),
+                value.exponent - shift,
+            )
+        }
+    };
+    // rounding up can carry into a fifth byte
+    if fraction == base.pow(FRACTION_BYTES as u32) {
+        fraction /= base;
+        exponent += 1;
+    }
+    let stored_exponent = exponent + FRACTION_BYTES + excess(size);
+    let overflow = !(0..base as i32).contains(&stored_exponent);
+    let stored_exponent = stored_exponent.rem_euclid(base as i32) as u128;
+    let magnitude = stored_exponent * base.pow(FRACTION_BYTES as u32) + fraction;
+    (make_word(value.negative, magnitude, size), overflow)
+}
+
+fn sum(u: Exact, v: Exact, size: ByteSize) -> Exact {
+    if u.fraction == 0 {
+        return v;
+    }
+    if v.fraction == 0 {
+        return u;
+    }
+    let (high, low) = match u.exponent >= v.exponent {
+        true => (u, v),
+        false => (v, u),
+    };
+    let mut shift = high.exponent - low.exponent;
+    let mut low_fraction = low.fraction;
+    if shift > SIGNIFICANT_SHIFT {
+        // anything this far down rounds the same way, so keep a single unit to stand in for it
+        shift = SIGNIFICANT_SHIFT + 1;
+        low_fraction = 1;
+    }
+    let high_fraction = high.fraction * base(size).pow(shift as u32);
+    let exponent = high.exponent - shift;
+    let (negative, fraction) = if high.negative == low.negative {
+        (high.negative, high_fraction + low_fraction)
+    } else if high_fraction >= low_fraction {
+        (high.negative, high_fraction - low_fraction)
+    } else {
+        (low.negative, low_fraction - high_fraction)
+    };
+    Exact {
+        negative,
+        fraction,
+        exponent,
+    }
+}
+
+fn negate(value: Exact) -> Exact {
+    Exact {
+        negative: !value.negative,
+        ..value
+    }
+}
+
+pub fn add(u: Word, v: Word, size: ByteSize) -> (Word, bool) {
+    pack(sum(unpack(u, size), unpack(v, size), size), size)
+}
+
+pub fn subtract(u: Word, v: Word, size: ByteSize) -> (Word, bool) {
+    pack(sum(unpack(u, size), negate(unpack(v, size)), size), size)
+}
+
+pub fn multiply(u: Word, v: Word, size: ByteSize) -> (Word, bool) {
+    let (u, v) = (unpack(u, size), unpack(v, size));
+    let product = Exact {
+        negative: u.negative != v.negative,
+        fraction: u.fraction * v.fraction,
+        exponent: u.exponent + v.exponent,
+    };
+    pack(product, size)
+}
+
+// None when dividing by zero
+pub fn divide(u: Word, v: Word, size: ByteSize) -> Option<(Word, bool)> {
+    let (u, v) = (unpack(u, size), unpack(v, size));
+    if v.fraction == 0 {
+        return None;
+    }
+    let base = base(size);
+    let numerator = u.fraction * base.pow(SIGNIFICANT_SHIFT as u32);
+    let mut quotient = Exact {
+        negative: u.negative != v.negative,
+        fraction: numerator / v.fraction,
+        exponent: u.exponent - v.exponent - SIGNIFICANT_SHIFT,
+    };
+    if !numerator.is_multiple_of(v.fraction) {
+        // an extra low digit so a remainder is never mistaken for an exact tie
+        quotient.fraction = quotient.fraction * base + 1;
+        quotient.exponent -= 1;
+    }
+    Some(pack(quotient, size))
+}
+
+// FLOT, the integer in a word as a floating point number
+pub fn from_integer(word: Word, size: ByteSize) -> (Word, bool) {
+    let value = Exact {
+        negative: word.sign == Sign::MINUS,
+        fraction: word.to_i64_in(size).unsigned_abs() as u128,
+        exponent: 0,
+    };
+    pack(value, size)
+}
+
+// FIX, rounded to the nearest integer with overflow when it needs more than five bytes
+pub fn to_integer(word: Word, size: ByteSize) -> (Word, bool) {
+    let value = unpack(word, size);
+    let base = base(size);
+    let limit = base.pow(5);
+    let magnitude = if value.fraction == 0 || value.exponent < -FRACTION_BYTES - 1 {
+        0
+    } else if value.exponent < 0 {
+        round_shift(value.fraction, -value.exponent as u32, base)
+    } else if value.exponent <= 5 {
+        value.fraction * base.pow(value.exponent as u32)
+    } else {
+        // well past five bytes, all that's left after wrapping is zero
+        return (make_word(value.negative, 0, size), true);
+    };
+    (
+        make_word(value.negative, magnitude % limit, size),
+        magnitude >= limit,
+    )
+}
+
+fn compare_magnitudes(a: Exact, c: Exact, size: ByteSize) -> Ordering {
+    let base = base(size);
+    match (a.fraction == 0, c.fraction == 0) {
+        (true, true) => return Ordering::Equal,
+        (true, false) => return Ordering::Less,
+        (false, true) => return Ordering::Greater,
+        _ => (),
+    }
+    let a_top = digits(a.fraction, base) + a.exponent;
+    let c_top = digits(c.fraction, base) + c.exponent;
+    if a_top != c_top {
+        return a_top.cmp(&c_top);
+    }
+    // the leading digits line up, so scaling one fraction to the other's exponent stays small
+    if a.exponent >= c.exponent {
+        (a.fraction * base.pow((a.exponent - c.exponent) as u32)).cmp(&c.fraction)
+    } else {
+        a.fraction
+            .cmp(&(c.fraction * base.pow((c.exponent - a.exponent) as u32)))
+    }
+}
+
+// FCMP, Knuth's approximate comparison from section 4.2.2: u and v are equal when they're within
+// epsilon * b^(e-q) of each other, where e is the larger of their exponents
+pub fn compare(u: Word, v: Word, epsilon: Word, size: ByteSize) -> Ordering {
+    let (u, v) = (unpack(u, size), unpack(v, size));
+    let difference = sum(v, negate(u), size);
+    let epsilon = unpack(epsilon, size);
+    let tolerance = Exact {
+        negative: false,
+        fraction: epsilon.fraction,
+        exponent: epsilon.exponent + u.exponent.max(v.exponent) + FRACTION_BYTES,
+    };
+    match compare_magnitudes(difference, tolerance, size) {
+        Ordering::Greater if difference.negative => Ordering::Greater,
+        Ordering::Greater => Ordering::Less,
+        _ => Ordering::Equal,
+    }
+}
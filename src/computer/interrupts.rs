@@ -0,0 +1,214 @@
+use crate::data_types::{Byte, FieldSpecification, Index, JumpAddress, Sign, Word};
+
+use super::{ComparisonIndicatorState, Computer};
+
+// The interrupt facility Knuth sketches in exercise 1.4.4-18, which is off unless
+// Computer::enable_interrupts is called. The machine is then in one of two states. Programs run
+// in normal state, as on the plain machine. An interrupt saves the registers and where the program
+// was in the locations below, switches to control state and jumps to the interrupt's location.
+// Control state can also use locations -1 to -3999, which is where the saved registers, the timer
+// and the interrupt locations are, and where an operating system would keep its own code and data.
+// Interrupts wait while the machine is in control state.
+//
+// INT (C = 5, F = 9) interrupts to -12 in normal state, so a program can call on the operating
+// system. In control state it goes back to normal state, loading the registers and location from
+// the saved ones, which the operating system may have changed to switch to another program.
+//
+// Each IN, OUT and IOC interrupts to -(20 + unit) when it finishes, which is io_time u after it
+// starts, and the unit is busy until then. The timer at -10 counts down by the time each
+// instruction takes, in either state, and interrupts to -11 when it reaches zero.
+
+// where rA, rI1 to rI6 and rX are saved, in that order, then the word holding the location to go
+// back to in (1:2), the overflow toggle and comparison indicator in (3:3), see machine_state, and
+// rJ in (4:5)
+pub const SAVED_REGISTERS: i32 = -9;
+pub const SAVED_STATE: i32 = -1;
+pub const TIMER: i32 = -10;
+pub const TIMER_INTERRUPT: i32 = -11;
+pub const INT_INTERRUPT: i32 = -12;
+
+pub fn device_interrupt(unit: i32) -> i32 {
+    -20 - unit
+}
+
+#[derive(Debug, Default)]
+pub struct Interrupts {
+    // how long in u an I/O operation takes, transfers still happen as soon as the instruction runs
+    pub io_time: u64,
+    // when each interrupt is due, in u since the machine started, and where it goes
+    pending: Vec<(u64, i32)>,
+}
+
+impl Interrupts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn schedule(&mut self, time: u64, location: i32) {
+        self.pending.push((time, location));
+    }
+
+    pub fn pending(&self) -> &[(u64, i32)] {
+        &self.pending
+    }
+
+    // the earliest interrupt due by now, first come first served when they're due together
+    fn take_due(&mut self, now: u64) -> Option<i32> {
+        let (idx, _) = self
+            .pending
+            .iter()
+            .enumerate()
+            .filter(|(_, (time, _))| *time <= now)
+            .min_by_key(|(_, (time, _))| *time)?;
+        Some(self.pending.remove(idx).1)
+    }
+
+    // an I/O operation on the unit that hasn't finished yet
+    pub fn is_busy(&self, unit: i32, now: u64) -> bool {
+        self.pending
+            .iter()
+            .any(|(time, location)| *location == device_interrupt(unit) && *time > now)
+    }
+}
+
+// the comparison indicator as 0 for none, then 1, 2 and 3 for less, equal and greater, plus 4 when
+// overflow is on, so the value is at most 7 and any byte size can hold it
+fn machine_state(overflow: bool, comparison: &Option<ComparisonIndicatorState>) -> i32 {
+    let comparison = match comparison {
+        None => 0,
+        Some(ComparisonIndicatorState::LESS) => 1,
+        Some(ComparisonIndicatorState::EQUAL) => 2,
+        Some(ComparisonIndicatorState::GREATER) => 3,
+    };
+    i32::from(overflow) * 4 + comparison
+}
+
+fn from_machine_state(value: i32) -> (bool, Option<ComparisonIndicatorState>) {
+    let comparison = match value % 4 {
+        1 => Some(ComparisonIndicatorState::LESS),
+        2 => Some(ComparisonIndicatorState::EQUAL),
+        3 => Some(ComparisonIndicatorState::GREATER),
+        _ => None,
+    };
+    (value & 4 != 0, comparison)
+}
+
+impl Computer {
+    // adds the control state locations and starts taking interrupts, the machine stays in normal
+    // state until the first one unless a program loaded afterwards starts at a negative location
+    pub fn enable_interrupts(&mut self) {
+        self.memory.add_negative_locations();
+        self.interrupts = Some(Interrupts::new());
+    }
+
+    pub fn in_control_state(&self) -> bool {
+        self.memory.control_state
+    }
+
+    // before each instruction, takes an interrupt that's due if the machine is in normal state. After
+    // a jump outside memory it waits, and fetching the next instruction faults as it would without
+    // interrupts, rather than saving a location the program could never go back to.
+    pub(super) fn check_interrupts(&mut self) {
+        if self.memory.control_state || self.memory.get(self.current_instruction_address).is_err() {
+            return;
+        }
+        let time = self.time;
+        if let Some(location) = self.interrupts.as_mut().and_then(|x| x.take_due(time)) {
+            self.interrupt(location);
+        }
+    }
+
+    fn interrupt(&mut self, location: i32) {
+        let registers = [
+            self.registers.a,
+            Self::index_to_word(&self.registers.i1),
+            Self::index_to_word(&self.registers.i2),
+            Self::index_to_word(&self.registers.i3),
+            Self::index_to_word(&self.registers.i4),
+            Self::index_to_word(&self.registers.i5),
+            Self::index_to_word(&self.registers.i6),
+            self.registers.x,
+        ];
+        for (address, word) in (SAVED_REGISTERS..).zip(registers) {
+            self.memory.set_in_control_state(address, word).unwrap();
+        }
+        // a location in memory or one past the end after INT at 3999, either fits
+        let (l1, l2) = JumpAddress::from_i32_in(self.current_instruction_address, self.byte_size)
+            .expect("checked by check_interrupts")
+            .bytes();
+        let state = machine_state(self.overflow, &self.comparison_indicator);
+        let (j1, j2) = self.registers.j.bytes();
+        let saved = Word::from_byte_array(
+            Sign::PLUS,
+            [
+                l1,
+                l2,
+                Byte::from_i32_in(state, self.byte_size).unwrap(),
+                j1,
+                j2,
+            ],
+        );
+        self.memory
+            .set_in_control_state(SAVED_STATE, saved)
+            .unwrap();
+        self.memory.control_state = true;
+        self.current_instruction_address = location;
+    }
+
+    // INT
+    pub(super) fn int(&mut self) {
+        if !self.memory.control_state {
+            return self.interrupt(INT_INTERRUPT);
+        }
+        let saved: Vec<Word> = (SAVED_REGISTERS..=SAVED_STATE)
+            .map(|x| self.memory.get(x).unwrap())
+            .collect();
+        let index = |word: Word| Index {
+            sign: word.sign,
+            bytes: (word.bytes.3, word.bytes.4),
+        };
+        self.registers.a = saved[0];
+        self.registers.i1 = index(saved[1]);
+        self.registers.i2 = index(saved[2]);
+        self.registers.i3 = index(saved[3]);
+        self.registers.i4 = index(saved[4]);
+        self.registers.i5 = index(saved[5]);
+        self.registers.i6 = index(saved[6]);
+        self.registers.x = saved[7];
+        let state = saved[8];
+        (self.overflow, self.comparison_indicator) = from_machine_state(state.bytes.2.to_i32());
+        let field = |left, right| {
+            let value = state.get_field(FieldSpecification { left, right });
+            value.to_i64_in(self.byte_size) as i32
+        };
+        self.registers.j = JumpAddress::from_i32_in(field(4, 5), self.byte_size).unwrap();
+        self.current_instruction_address = field(1, 2);
+        self.memory.control_state = false;
+    }
+
+    // after each instruction, counts the timer down by the time it took
+    pub(super) fn count_down_timer(&mut self, elapsed: u64) {
+        let timer = self.memory.get_in_control_state(TIMER).unwrap();
+        let value = timer.to_i64_in(self.byte_size);
+        if value <= 0 || elapsed == 0 {
+            return;
+        }
+        let left = value.saturating_sub(elapsed as i64).max(0);
+        let word = Word::from_i64_in(left, self.byte_size).unwrap();
+        self.memory.set_in_control_state(TIMER, word).unwrap();
+        if left == 0 {
+            let time = self.time;
+            if let Some(interrupts) = &mut self.interrupts {
+                interrupts.schedule(time, TIMER_INTERRUPT);
+            }
+        }
+    }
+
+    // after an IN, OUT or IOC
+    pub(super) fn start_io(&mut self, unit: i32) {
+        let time = self.time;
+        if let Some(interrupts) = &mut self.interrupts {
+            interrupts.schedule(time + interrupts.io_time, device_interrupt(unit));
+        }
+    }
+}
@@ -4,6 +4,10 @@ pub const SIZE: usize = 4000;
 
 pub struct Memory {
     content: [Word; SIZE],
+    // locations -1 to -3999, indexed by the negated address, which only a machine with interrupts
+    // has and which can only be used in control state
+    negative: Vec<Word>,
+    pub control_state: bool,
 }
 
 #[derive(Debug)]
@@ -19,9 +23,40 @@ pub enum SetError {
 impl Memory {
     pub const ZERO: Self = Self {
         content: [Word::ZERO; SIZE],
+        negative: Vec::new(),
+        control_state: false,
     };
 
+    pub fn add_negative_locations(&mut self) {
+        self.negative = vec![Word::ZERO; SIZE];
+    }
+
+    fn negative_index(&self, address: i32, control_state: bool) -> Option<usize> {
+        let index = address.unsigned_abs() as usize;
+        (address < 0 && control_state && index < self.negative.len()).then_some(index)
+    }
+
     pub fn get(&self, address: i32) -> Result<Word, AccessError> {
+        self.get_in_state(address, self.control_state)
+    }
+
+    // as if in control state, for the interrupt hardware which uses the negative locations in
+    // either state
+    pub fn get_in_control_state(&self, address: i32) -> Result<Word, AccessError> {
+        self.get_in_state(address, true)
+    }
+
+    pub fn set_in_control_state(&mut self, address: i32, value: Word) -> Result<(), SetError> {
+        let control_state = std::mem::replace(&mut self.control_state, true);
+        let result = self.set(address, value);
+        self.control_state = control_state;
+        result
+    }
+
+    fn get_in_state(&self, address: i32, control_state: bool) -> Result<Word, AccessError> {
+        if let Some(x) = self.negative_index(address, control_state) {
+            return Ok(self.negative[x]);
+        }
         match address.try_into() {
             Err(_) => Err(AccessError::InvalidAddress(address)),
             Ok(x) => match x {
@@ -32,11 +67,18 @@ impl Memory {
     }
 
     pub fn set(&mut self, address: i32, value: Word) -> Result<(), SetError> {
+        if let Some(x) = self.negative_index(address, self.control_state) {
+            self.negative[x] = value;
+            return Ok(());
+        }
         match address.try_into() {
             Err(_) => Err(SetError::InvalidAddress(address)),
             Ok(x) => match x {
                 SIZE.. => Err(SetError::InvalidAddress(address)),
-                _ => Ok(self.content[x] = value),
+                _ => {
+                    self.content[x] = value;
+                    Ok(())
+                }
             },
         }
     }
@@ -0,0 +1,128 @@
+use std::io::{self, Write};
+
+use crate::object::Program;
+
+use super::memory;
+
+// How often each address was executed and how much time it took, the numbers Knuth puts next to
+// each line of a program when analysing it. Attach one to Computer::profile before running.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Profile {
+    entries: Vec<Entry>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Entry {
+    pub count: u64,
+    // in units of u
+    pub time: u64,
+}
+
+// how many addresses the summary after a listing shows
+const HOT_SPOTS: usize = 10;
+
+impl Profile {
+    pub fn new() -> Self {
+        Self {
+            entries: vec![Entry::default(); memory::SIZE],
+        }
+    }
+
+    pub fn record(&mut self, address: i32, time: u64) {
+        if let Some(entry) = self.entries.get_mut(address as usize) {
+            entry.count += 1;
+            entry.time += time;
+        }
+    }
+
+    pub fn get(&self, address: i32) -> Entry {
+        self.entries
+            .get(address as usize)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn total(&self) -> Entry {
+        self.entries
+            .iter()
+            .fold(Entry::default(), |total, x| Entry {
+                count: total.count + x.count,
+                time: total.time + x.time,
+            })
+    }
+
+    // the addresses that took the most time, most first, leaving out ones never executed
+    pub fn hot_spots(&self, count: usize) -> Vec<(i32, Entry)> {
+        let mut executed: Vec<(i32, Entry)> = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, x)| x.count > 0)
+            .map(|(address, x)| (address as i32, *x))
+            .collect();
+        executed.sort_by(|a, b| b.1.time.cmp(&a.1.time).then(a.0.cmp(&b.0)));
+        executed.truncate(count);
+        executed
+    }
+
+    // The source with the address, count and time for each line that assembled to a word, then
+    // the hot spots. Lines that didn't assemble to anything get blank columns.
+    pub fn write_listing(
+        &self,
+        source: &str,
+        program: &Program,
+        writer: &mut impl Write,
+    ) -> io::Result<()> {
+        writeln!(writer, " LINE  LOC     COUNT      TIME  SOURCE")?;
+        for (idx, text) in source.lines().enumerate() {
+            let line = idx + 1;
+            // the listing is of the main file, includes aren't shown
+            match program.source_map.address_of_line(0, line) {
+                Some(address) => {
+                    let entry = self.get(address);
+                    writeln!(
+                        writer,
+                        "{:>5} {:>4} {:>9} {:>9}  {}",
+                        line, address, entry.count, entry.time, text
+                    )?;
+                }
+                None => writeln!(
+                    writer,
+                    "{:>5} {:>4} {:>9} {:>9}  {}",
+                    line, "", "", "", text
+                )?,
+            }
+        }
+
+        let total = self.total();
+        writeln!(writer)?;
+        writeln!(
+            writer,
+            "{} instructions in {}u, hot spots:",
+            total.count, total.time
+        )?;
+        writeln!(writer, "  LOC     COUNT      TIME  SHARE  SOURCE")?;
+        for (address, entry) in self.hot_spots(HOT_SPOTS) {
+            let text = match program.source_map.line_of_address(address) {
+                Some((0, line)) => source.lines().nth(line - 1).unwrap_or(""),
+                _ => "",
+            };
+            writeln!(
+                writer,
+                " {:>4} {:>9} {:>9} {:>5.1}%  {}",
+                address,
+                entry.count,
+                entry.time,
+                100.0 * entry.time as f64 / total.time as f64,
+                text.trim()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
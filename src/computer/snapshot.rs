@@ -0,0 +1,461 @@
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::Path,
+};
+
+use crate::data_types::{Byte, ByteSize, Index, JumpAddress, Sign, Word};
+
+use super::{
+    devices::{self, Devices, DiskUnit, Terminal, TextInput, TextOutput},
+    memory, ComparisonIndicatorState, Computer, TapeUnit,
+};
+
+// Snapshot files are plain text, one item per line, so they can be diffed and handed around:
+//
+//   MIX-SNAPSHOT 1
+//   BYTES 100               (values per byte, left out for binary machines and before any words)
+//   PC 3000
+//   RUNNING 1
+//   OVERFLOW 0
+//   COMPARISON LESS         (or EQUAL, GREATER, NONE)
+//   A + 0 0 1 2 3           (sign then byte values, likewise X)
+//   I1 - 0 5                (likewise I2 to I6)
+//   J 0 17
+//   INTERRUPTS 100          (I/O time in u, only for a machine with the interrupt facility)
+//   CONTROL 1               (1 in control state, likewise only with interrupts)
+//   MEM 3000 + 0 1 2 3 4    (only words other than +0 are written, with interrupts that includes
+//                            the negative locations and so the timer at -10)
+//   COUNT 1234              (instructions executed so far)
+//   TIME 5678               (time taken so far in units of u)
+//   PENDING 5700 -21        (when an interrupt is due and its location, one per interrupt waiting)
+//   TAPE 0 1 1000 3         (unit, ready flag, capacity in blocks and current block)
+//   TAPEWORD 0 12 99 + 0 0 0 0 1
+//   DISK 8 4000             (unit and capacity in blocks)
+//   DISKWORD 8 12 99 + 0 0 0 0 1
+//   INPUT 16 2              (unit and number of lines already read, for 16, 19 and 20)
+//   INLINE 16 SOME TEXT     (one per input line, the text runs to the end of the line)
+//   OUTPUT 18               (unit, for 17, 18 and 19)
+//   OUTLINE 18 SOME TEXT    (one per line written so far)
+//
+// Only attached devices are saved. Echoes to stdout or files can't be saved, so restored output
+// devices just collect their lines.
+//
+// The version number goes up whenever the meaning of an existing line changes.
+pub const SNAPSHOT_VERSION: i32 = 1;
+
+const HEADER: &str = "MIX-SNAPSHOT";
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    Io(io::Error),
+    BadHeader(String),
+    UnsupportedVersion(i32),
+    BadLine(usize, String),
+}
+
+impl From<io::Error> for SnapshotError {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl Computer {
+    pub fn save_snapshot(&self, writer: &mut impl Write) -> io::Result<()> {
+        writeln!(writer, "{} {}", HEADER, SNAPSHOT_VERSION)?;
+        if self.byte_size != ByteSize::BINARY {
+            writeln!(writer, "BYTES {}", self.byte_size.values())?;
+        }
+        writeln!(writer, "PC {}", self.current_instruction_address)?;
+        writeln!(writer, "RUNNING {}", self.running as i32)?;
+        writeln!(writer, "OVERFLOW {}", self.overflow as i32)?;
+        let comparison = match self.comparison_indicator {
+            None => "NONE",
+            Some(ComparisonIndicatorState::LESS) => "LESS",
+            Some(ComparisonIndicatorState::EQUAL) => "EQUAL",
+            Some(ComparisonIndicatorState::GREATER) => "GREATER",
+        };
+        writeln!(writer, "COMPARISON {}", comparison)?;
+        writeln!(writer, "A {}", self.registers.a.to_byte_string())?;
+        writeln!(writer, "X {}", self.registers.x.to_byte_string())?;
+        let index_registers = [
+            &self.registers.i1,
+            &self.registers.i2,
+            &self.registers.i3,
+            &self.registers.i4,
+            &self.registers.i5,
+            &self.registers.i6,
+        ];
+        for (idx, register) in index_registers.iter().enumerate() {
+            writeln!(writer, "I{} {}", idx + 1, format_index(register))?;
+        }
+        writeln!(writer, "J {}", format_jump_address(&self.registers.j))?;
+        let mut first_address = 0;
+        if let Some(interrupts) = &self.interrupts {
+            writeln!(writer, "INTERRUPTS {}", interrupts.io_time)?;
+            writeln!(writer, "CONTROL {}", self.memory.control_state as i32)?;
+            first_address = 1 - memory::SIZE as i32;
+        }
+        for address in first_address..memory::SIZE as i32 {
+            let word = self.memory.get_in_control_state(address).unwrap();
+            if word != Word::ZERO {
+                writeln!(writer, "MEM {} {}", address, word.to_byte_string())?;
+            }
+        }
+        writeln!(writer, "COUNT {}", self.instruction_count)?;
+        writeln!(writer, "TIME {}", self.time)?;
+        if let Some(interrupts) = &self.interrupts {
+            for (time, location) in interrupts.pending() {
+                writeln!(writer, "PENDING {} {}", time, location)?;
+            }
+        }
+        save_devices(&self.devices, writer)?;
+        Ok(())
+    }
+
+    pub fn save_snapshot_to_file(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.save_snapshot(&mut writer)?;
+        writer.flush()
+    }
+
+    pub fn restore_snapshot(reader: impl BufRead) -> Result<Computer, SnapshotError> {
+        let mut lines = reader.lines();
+        let header = lines.next().transpose()?.unwrap_or_default();
+        match header.split_once(' ') {
+            Some((HEADER, version)) => match version.trim().parse::<i32>() {
+                Ok(SNAPSHOT_VERSION) => (),
+                Ok(x) => return Err(SnapshotError::UnsupportedVersion(x)),
+                Err(_) => return Err(SnapshotError::BadHeader(header)),
+            },
+            _ => return Err(SnapshotError::BadHeader(header)),
+        }
+
+        let mut computer = Computer::new();
+        computer.devices = Devices::new();
+        for (idx, line) in lines.enumerate() {
+            let line = line?;
+            // the header was line 1
+            let line_number = idx + 2;
+            restore_line(&mut computer, &line)
+                .ok_or_else(|| SnapshotError::BadLine(line_number, line.clone()))?;
+        }
+        Ok(computer)
+    }
+
+    pub fn restore_snapshot_from_file(path: impl AsRef<Path>) -> Result<Computer, SnapshotError> {
+        Self::restore_snapshot(BufReader::new(File::open(path)?))
+    }
+}
+
+fn restore_line(computer: &mut Computer, line: &str) -> Option<()> {
+    let mut parts = line.split_whitespace();
+    let key = match parts.next() {
+        Some(x) => x,
+        None => return Some(()),
+    };
+    let values: Vec<&str> = parts.collect();
+    let size = computer.byte_size;
+    match key {
+        "BYTES" => computer.byte_size = ByteSize::new(parse_single(&values)?)?,
+        "PC" => computer.current_instruction_address = parse_single(&values)?,
+        "RUNNING" => computer.running = parse_flag(&values)?,
+        "OVERFLOW" => computer.overflow = parse_flag(&values)?,
+        "COMPARISON" => {
+            computer.comparison_indicator = match values.as_slice() {
+                ["NONE"] => None,
+                ["LESS"] => Some(ComparisonIndicatorState::LESS),
+                ["EQUAL"] => Some(ComparisonIndicatorState::EQUAL),
+                ["GREATER"] => Some(ComparisonIndicatorState::GREATER),
+                _ => return None,
+            }
+        }
+        "A" => computer.registers.a = parse_word(&values, size)?,
+        "X" => computer.registers.x = parse_word(&values, size)?,
+        "I1" => computer.registers.i1 = parse_index(&values, size)?,
+        "I2" => computer.registers.i2 = parse_index(&values, size)?,
+        "I3" => computer.registers.i3 = parse_index(&values, size)?,
+        "I4" => computer.registers.i4 = parse_index(&values, size)?,
+        "I5" => computer.registers.i5 = parse_index(&values, size)?,
+        "I6" => computer.registers.i6 = parse_index(&values, size)?,
+        "J" => computer.registers.j = parse_jump_address(&values, size)?,
+        "INTERRUPTS" => {
+            computer.enable_interrupts();
+            computer.interrupts.as_mut()?.io_time = parse_single(&values)?;
+        }
+        "CONTROL" if computer.interrupts.is_some() => {
+            computer.memory.control_state = parse_flag(&values)?
+        }
+        "MEM" => {
+            let address = values.first()?.parse().ok()?;
+            let word = parse_word(values.get(1..)?, size)?;
+            computer.memory.set_in_control_state(address, word).ok()?;
+        }
+        "COUNT" => computer.instruction_count = parse_single(&values)?,
+        "TIME" => computer.time = parse_single(&values)?,
+        "PENDING" => {
+            let time = values.first()?.parse().ok()?;
+            let location = parse_single(values.get(1..)?)?;
+            computer.interrupts.as_mut()?.schedule(time, location);
+        }
+        "TAPE" => {
+            let [unit, ready, capacity, position] = parse_numbers(&values)?;
+            let mut tape = TapeUnit::new(capacity);
+            tape.ready = flag(ready)?;
+            tape.position = position;
+            *computer.devices.tapes.get_mut(unit)? = Some(tape);
+        }
+        "TAPEWORD" => {
+            let [unit, block, word_idx] = parse_numbers(values.get(..3)?)?;
+            let tape = computer.devices.tapes.get_mut(unit)?.as_mut()?;
+            *tape.data.get_mut(block)?.get_mut(word_idx)? = parse_word(values.get(3..)?, size)?;
+        }
+        "DISK" => {
+            let [unit, capacity] = parse_numbers(&values)?;
+            *computer.devices.disks.get_mut(unit.checked_sub(8)?)? = Some(DiskUnit::new(capacity));
+        }
+        "DISKWORD" => {
+            let [unit, block, word_idx] = parse_numbers(values.get(..3)?)?;
+            let disk = computer
+                .devices
+                .disks
+                .get_mut(unit.checked_sub(8)?)?
+                .as_mut()?;
+            *disk.data.get_mut(block)?.get_mut(word_idx)? = parse_word(values.get(3..)?, size)?;
+        }
+        "INPUT" => {
+            let [unit, position] = parse_numbers(&values)?;
+            let devices = &mut computer.devices;
+            match unit as i32 {
+                devices::CARD_READER => devices.card_reader = Some(TextInput::card_reader(vec![])),
+                devices::PAPER_TAPE => devices.paper_tape = Some(TextInput::paper_tape(vec![])),
+                devices::TERMINAL => {
+                    devices
+                        .terminal
+                        .get_or_insert_with(|| Terminal::new(vec![]));
+                }
+                _ => return None,
+            }
+            text_input(devices, unit as i32)?.position = position;
+        }
+        "INLINE" => {
+            let unit = values.first()?.parse().ok()?;
+            let text = rest_of_line(line, 2);
+            text_input(&mut computer.devices, unit)?.lines.push(text);
+        }
+        "OUTPUT" => {
+            let devices = &mut computer.devices;
+            match parse_single(&values)? {
+                devices::CARD_PUNCH => devices.card_punch = Some(TextOutput::card_punch()),
+                devices::LINE_PRINTER => devices.line_printer = Some(TextOutput::line_printer()),
+                devices::TERMINAL => {
+                    devices
+                        .terminal
+                        .get_or_insert_with(|| Terminal::new(vec![]));
+                }
+                _ => return None,
+            }
+        }
+        "OUTLINE" => {
+            let unit = values.first()?.parse().ok()?;
+            let text = rest_of_line(line, 2);
+            text_output(&mut computer.devices, unit)?.lines.push(text);
+        }
+        _ => return None,
+    }
+    Some(())
+}
+
+fn save_devices(devices: &Devices, writer: &mut impl Write) -> io::Result<()> {
+    for (unit, tape) in devices.tapes.iter().enumerate() {
+        let tape = match tape {
+            Some(x) => x,
+            None => continue,
+        };
+        writeln!(
+            writer,
+            "TAPE {} {} {} {}",
+            unit, tape.ready as i32, tape.capacity, tape.position
+        )?;
+        save_blocks(writer, "TAPEWORD", unit, &tape.data)?;
+    }
+    for (idx, disk) in devices.disks.iter().enumerate() {
+        let disk = match disk {
+            Some(x) => x,
+            None => continue,
+        };
+        writeln!(writer, "DISK {} {}", idx + 8, disk.capacity)?;
+        save_blocks(writer, "DISKWORD", idx + 8, &disk.data)?;
+    }
+    let inputs = [
+        (devices::CARD_READER, devices.card_reader.as_ref()),
+        (
+            devices::TERMINAL,
+            devices.terminal.as_ref().map(|x| &x.input),
+        ),
+        (devices::PAPER_TAPE, devices.paper_tape.as_ref()),
+    ];
+    for (unit, input) in inputs {
+        if let Some(input) = input {
+            writeln!(writer, "INPUT {} {}", unit, input.position)?;
+            for line in input.lines.iter() {
+                writeln!(writer, "INLINE {} {}", unit, line)?;
+            }
+        }
+    }
+    let outputs = [
+        (devices::CARD_PUNCH, devices.card_punch.as_ref()),
+        (devices::LINE_PRINTER, devices.line_printer.as_ref()),
+        (
+            devices::TERMINAL,
+            devices.terminal.as_ref().map(|x| &x.output),
+        ),
+    ];
+    for (unit, output) in outputs {
+        if let Some(output) = output {
+            writeln!(writer, "OUTPUT {}", unit)?;
+            for line in output.lines.iter() {
+                writeln!(writer, "OUTLINE {} {}", unit, line)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn save_blocks<const N: usize>(
+    writer: &mut impl Write,
+    key: &str,
+    unit: usize,
+    blocks: &[[Word; N]],
+) -> io::Result<()> {
+    for (block_idx, block) in blocks.iter().enumerate() {
+        for (word_idx, word) in block.iter().enumerate() {
+            if *word != Word::ZERO {
+                writeln!(
+                    writer,
+                    "{} {} {} {} {}",
+                    key,
+                    unit,
+                    block_idx,
+                    word_idx,
+                    word.to_byte_string()
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn text_input(devices: &mut Devices, unit: i32) -> Option<&mut TextInput> {
+    match unit {
+        devices::CARD_READER => devices.card_reader.as_mut(),
+        devices::TERMINAL => devices.terminal.as_mut().map(|x| &mut x.input),
+        devices::PAPER_TAPE => devices.paper_tape.as_mut(),
+        _ => None,
+    }
+}
+
+fn text_output(devices: &mut Devices, unit: i32) -> Option<&mut TextOutput> {
+    match unit {
+        devices::CARD_PUNCH => devices.card_punch.as_mut(),
+        devices::LINE_PRINTER => devices.line_printer.as_mut(),
+        devices::TERMINAL => devices.terminal.as_mut().map(|x| &mut x.output),
+        _ => None,
+    }
+}
+
+// text after the first count space separated items, keeping any spaces inside it
+fn rest_of_line(line: &str, count: usize) -> String {
+    line.splitn(count + 1, ' ')
+        .nth(count)
+        .unwrap_or("")
+        .to_string()
+}
+
+fn format_sign(sign: Sign) -> &'static str {
+    match sign {
+        Sign::PLUS => "+",
+        Sign::MINUS => "-",
+    }
+}
+
+fn format_index(index: &Index) -> String {
+    format!(
+        "{} {} {}",
+        format_sign(index.sign),
+        index.bytes.0.to_i32(),
+        index.bytes.1.to_i32()
+    )
+}
+
+fn format_jump_address(jump_address: &JumpAddress) -> String {
+    let (b1, b2) = jump_address.bytes();
+    format!("{} {}", b1.to_i32(), b2.to_i32())
+}
+
+fn parse_single<T: std::str::FromStr>(values: &[&str]) -> Option<T> {
+    match values {
+        [x] => x.parse().ok(),
+        _ => None,
+    }
+}
+
+fn parse_flag(values: &[&str]) -> Option<bool> {
+    flag(parse_single(values)?)
+}
+
+fn flag(value: usize) -> Option<bool> {
+    match value {
+        0 => Some(false),
+        1 => Some(true),
+        _ => None,
+    }
+}
+
+fn parse_numbers<const N: usize>(values: &[&str]) -> Option<[usize; N]> {
+    if values.len() != N {
+        return None;
+    }
+    let mut result = [0; N];
+    for (value, number) in values.iter().zip(result.iter_mut()) {
+        *number = value.parse().ok()?;
+    }
+    Some(result)
+}
+
+fn parse_sign(value: &str) -> Option<Sign> {
+    match value {
+        "+" => Some(Sign::PLUS),
+        "-" => Some(Sign::MINUS),
+        _ => None,
+    }
+}
+
+fn parse_bytes<const N: usize>(values: &[&str], size: ByteSize) -> Option<[Byte; N]> {
+    if values.len() != N {
+        return None;
+    }
+    let mut result = [Byte::ZERO; N];
+    for (value, byte) in values.iter().zip(result.iter_mut()) {
+        *byte = Byte::from_i32_in(value.parse().ok()?, size).ok()?;
+    }
+    Some(result)
+}
+
+fn parse_word(values: &[&str], size: ByteSize) -> Option<Word> {
+    Word::from_byte_string_in(&values.join(" "), size)
+}
+
+fn parse_index(values: &[&str], size: ByteSize) -> Option<Index> {
+    let sign = parse_sign(values.first()?)?;
+    let [b1, b2] = parse_bytes(values.get(1..)?, size)?;
+    Some(Index {
+        sign,
+        bytes: (b1, b2),
+    })
+}
+
+fn parse_jump_address(values: &[&str], size: ByteSize) -> Option<JumpAddress> {
+    let [b1, b2] = parse_bytes(values, size)?;
+    JumpAddress::from_i32_in(b1.to_i32() * size.values() + b2.to_i32(), size).ok()
+}
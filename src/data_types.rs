@@ -1,11 +1,16 @@
-use std::ops::RangeInclusive;
+use std::fmt;
+use std::ops::{Add, Neg, RangeInclusive, Sub};
+use std::str::FromStr;
 
+// Bytes used to be six of these. Nothing uses them now a byte can hold up to 100 values.
+#[deprecated(note = "bytes aren't made of bits any more, use Byte::to_i32 for a byte's value")]
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum Bit {
     ZERO,
     ONE,
 }
 
+#[allow(deprecated)]
 impl Bit {
     pub fn value(&self) -> i32 {
         match self {
@@ -16,14 +21,44 @@ impl Bit {
 
     pub fn from_bool(value: bool) -> Self {
         match value {
-            true => Bit::ONE,
-            false => Bit::ZERO,
+            true => Self::ONE,
+            false => Self::ZERO,
         }
     }
 }
 
+// How many values a byte holds. Knuth only promises somewhere from 64 to 100, so a MIX program
+// that works on one size and not another is depending on something it shouldn't.
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-pub struct Byte(Bit, Bit, Bit, Bit, Bit, Bit);
+pub struct ByteSize(i32);
+
+impl ByteSize {
+    pub const BINARY: Self = Self(64);
+    pub const DECIMAL: Self = Self(100);
+
+    pub fn new(values: i32) -> Option<Self> {
+        (64..=100).contains(&values).then_some(Self(values))
+    }
+
+    pub fn values(&self) -> i32 {
+        self.0
+    }
+
+    // the number of different values that fit in count bytes, e.g. 64^5 for a binary word
+    pub fn capacity(&self, count: u32) -> i64 {
+        (self.0 as i64).pow(count)
+    }
+}
+
+impl Default for ByteSize {
+    fn default() -> Self {
+        Self::BINARY
+    }
+}
+
+// room for a byte of any size, though which values are valid depends on the machine's ByteSize
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Byte(u8);
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum ByteValueError {
@@ -38,48 +73,31 @@ pub enum CharConversionError {
 }
 
 impl Byte {
-    pub const ZERO: Self = Self(
-        Bit::ZERO,
-        Bit::ZERO,
-        Bit::ZERO,
-        Bit::ZERO,
-        Bit::ZERO,
-        Bit::ZERO,
-    );
+    pub const ZERO: Self = Self(0);
 
-    pub const MAX: Self = Self(Bit::ONE, Bit::ONE, Bit::ONE, Bit::ONE, Bit::ONE, Bit::ONE);
+    // the largest binary byte
+    pub const MAX: Self = Self(63);
+
+    pub fn max_in(size: ByteSize) -> Self {
+        Self((size.0 - 1) as u8)
+    }
 
     pub fn to_i32(&self) -> i32 {
-        self.0.value() * 32
-            + self.1.value() * 16
-            + self.2.value() * 8
-            + self.3.value() * 4
-            + self.4.value() * 2
-            + self.5.value()
+        self.0 as i32
     }
 
     pub fn from_i32(value: i32) -> Result<Self, ByteValueError> {
-        if value > 63 {
+        Self::from_i32_in(value, ByteSize::BINARY)
+    }
+
+    pub fn from_i32_in(value: i32, size: ByteSize) -> Result<Self, ByteValueError> {
+        if value >= size.0 {
             return Err(ByteValueError::Overflow(value));
         }
         if value < 0 {
             return Err(ByteValueError::Underflow(value));
         }
-        let mut x = value;
-        let first_bit = Bit::from_bool(x >= 32);
-        x = x % 32;
-        let second_bit = Bit::from_bool(x >= 16);
-        x = x % 16;
-        let third_bit = Bit::from_bool(x >= 8);
-        x = x % 8;
-        let fourth_bit = Bit::from_bool(x >= 4);
-        x = x % 4;
-        let fifth_bit = Bit::from_bool(x >= 2);
-        x = x % 2;
-        let sixth_bit = Bit::from_bool(x == 1);
-        Ok(Self(
-            first_bit, second_bit, third_bit, fourth_bit, fifth_bit, sixth_bit,
-        ))
+        Ok(Self(value as u8))
     }
 
     pub fn to_char(&self) -> Result<char, CharConversionError> {
@@ -211,76 +229,37 @@ impl Byte {
 
 #[test]
 fn should_return_byte_value_as_i32() {
-    assert_eq!(
-        Byte(
-            Bit::ZERO,
-            Bit::ZERO,
-            Bit::ZERO,
-            Bit::ZERO,
-            Bit::ZERO,
-            Bit::ZERO
-        )
-        .to_i32(),
-        0
-    );
-    assert_eq!(
-        Byte(Bit::ONE, Bit::ONE, Bit::ONE, Bit::ONE, Bit::ONE, Bit::ONE).to_i32(),
-        63
-    );
-    assert_eq!(
-        Byte(
-            Bit::ONE,
-            Bit::ZERO,
-            Bit::ONE,
-            Bit::ZERO,
-            Bit::ONE,
-            Bit::ZERO
-        )
-        .to_i32(),
-        42
-    );
-    assert_eq!(
-        Byte(
-            Bit::ZERO,
-            Bit::ONE,
-            Bit::ZERO,
-            Bit::ONE,
-            Bit::ZERO,
-            Bit::ONE
-        )
-        .to_i32(),
-        21
-    );
+    assert_eq!(Byte(0b000000).to_i32(), 0);
+    assert_eq!(Byte(0b111111).to_i32(), 63);
+    assert_eq!(Byte(0b101010).to_i32(), 42);
+    assert_eq!(Byte(0b010101).to_i32(), 21);
+    assert_eq!(Byte(99).to_i32(), 99);
 }
 
 #[test]
 fn should_make_correct_byte_for_i32_value() {
     assert_eq!(Byte::from_i32(0), Ok(Byte::ZERO));
     assert_eq!(Byte::from_i32(63), Ok(Byte::MAX));
+    assert_eq!(Byte::from_i32(42), Ok(Byte(0b101010)));
+    assert_eq!(Byte::from_i32(21), Ok(Byte(0b010101)));
+    assert_eq!(Byte::from_i32(64), Err(ByteValueError::Overflow(64)));
+    assert_eq!(Byte::from_i32(-1), Err(ByteValueError::Underflow(-1)));
+}
+
+#[test]
+fn should_check_bytes_against_the_byte_size() {
+    assert_eq!(Byte::from_i32_in(64, ByteSize::DECIMAL), Ok(Byte(64)));
     assert_eq!(
-        Byte::from_i32(42),
-        Ok(Byte(
-            Bit::ONE,
-            Bit::ZERO,
-            Bit::ONE,
-            Bit::ZERO,
-            Bit::ONE,
-            Bit::ZERO
-        ))
+        Byte::from_i32_in(99, ByteSize::DECIMAL),
+        Ok(Byte::max_in(ByteSize::DECIMAL))
     );
     assert_eq!(
-        Byte::from_i32(21),
-        Ok(Byte(
-            Bit::ZERO,
-            Bit::ONE,
-            Bit::ZERO,
-            Bit::ONE,
-            Bit::ZERO,
-            Bit::ONE
-        ))
+        Byte::from_i32_in(100, ByteSize::DECIMAL),
+        Err(ByteValueError::Overflow(100))
     );
-    assert_eq!(Byte::from_i32(64), Err(ByteValueError::Overflow(64)));
-    assert_eq!(Byte::from_i32(-1), Err(ByteValueError::Underflow(-1)));
+    assert_eq!(ByteSize::new(63), None);
+    assert_eq!(ByteSize::new(101), None);
+    assert_eq!(ByteSize::new(100), Some(ByteSize::DECIMAL));
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -312,7 +291,16 @@ impl Sign {
     }
 }
 
-#[derive(Debug)]
+impl fmt::Display for Sign {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(match self {
+            Self::PLUS => "+",
+            Self::MINUS => "-",
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub struct FieldSpecification {
     pub left: i32,
     pub right: i32,
@@ -333,6 +321,11 @@ impl FieldSpecification {
     pub fn value(&self) -> i32 {
         self.left * 8 + self.right
     }
+
+    // indexes into a word's byte array, the sign isn't one of them
+    fn byte_range(&self) -> std::ops::Range<usize> {
+        self.left.max(1) as usize - 1..self.right as usize
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -343,8 +336,8 @@ pub struct Word {
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum WordValueError {
-    Overflow(i32),
-    Underflow(i32),
+    Overflow(i64),
+    Underflow(i64),
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -355,6 +348,125 @@ pub enum InstructionValueError {
     Code(i32),
 }
 
+impl fmt::Display for WordValueError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Overflow(x) | Self::Underflow(x) => write!(f, "{} doesn't fit in a word", x),
+        }
+    }
+}
+
+impl std::error::Error for WordValueError {}
+
+impl fmt::Display for InstructionValueError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Address(x) => write!(f, "address {} doesn't fit in two bytes", x),
+            Self::Field(x) => write!(f, "field {} doesn't fit in a byte", x),
+            Self::Index(x) => write!(f, "index {} isn't a register from 0 to 6", x),
+            Self::Code(x) => write!(f, "operation code {} doesn't fit in a byte", x),
+        }
+    }
+}
+
+impl std::error::Error for InstructionValueError {}
+
+// what's wrong with text given to one of the FromStr impls below
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseValueError {
+    Invalid(String),
+    Overflow(i64),
+    Underflow(i64),
+}
+
+impl fmt::Display for ParseValueError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Invalid(x) => write!(f, "'{}' isn't a number or a sign and byte values", x),
+            Self::Overflow(x) | Self::Underflow(x) => write!(f, "{} doesn't fit", x),
+        }
+    }
+}
+
+impl std::error::Error for ParseValueError {}
+
+// a + b in a register that holds magnitudes below limit: the low part of the magnitude is kept, the
+// flag says whether anything was lost and a zero result keeps the sign of a, as in section 1.3.1
+fn register_sum(a_sign: Sign, a: i64, b: i64, limit: i64) -> (Sign, i64, bool) {
+    let sum = a + b;
+    let sign = match sum.cmp(&0) {
+        std::cmp::Ordering::Less => Sign::MINUS,
+        std::cmp::Ordering::Greater => Sign::PLUS,
+        std::cmp::Ordering::Equal => a_sign,
+    };
+    (sign, sum.abs() % limit, sum.abs() >= limit)
+}
+
+// "-12", "+12" or "12", with the sign kept apart so "-0" is minus zero
+fn parse_decimal(value: &str) -> Result<(Sign, i64), ParseValueError> {
+    let (sign, digits) = match value.strip_prefix('-') {
+        Some(rest) => (Sign::MINUS, rest),
+        None => (Sign::PLUS, value.strip_prefix('+').unwrap_or(value)),
+    };
+    match !digits.is_empty() && digits.bytes().all(|x| x.is_ascii_digit()) {
+        true => match digits.parse() {
+            Ok(x) => Ok((sign, x)),
+            Err(_) => Err(ParseValueError::Invalid(value.to_string())),
+        },
+        false => Err(ParseValueError::Invalid(value.to_string())),
+    }
+}
+
+// the error for a magnitude too big for what it's being parsed into
+fn out_of_range(sign: Sign, magnitude: i64) -> ParseValueError {
+    match sign {
+        Sign::PLUS => ParseValueError::Overflow(magnitude),
+        Sign::MINUS => ParseValueError::Underflow(-magnitude),
+    }
+}
+
+// "+ 1 2 3" for a sign and N bytes, each of which has to fit the byte size
+fn parse_byte_form<const N: usize>(value: &str, size: ByteSize) -> Option<(Sign, [Byte; N])> {
+    let parts: Vec<&str> = value.split_whitespace().collect();
+    let sign = match parts.first() {
+        Some(&"+") => Sign::PLUS,
+        Some(&"-") => Sign::MINUS,
+        _ => return None,
+    };
+    let mut bytes = [Byte::ZERO; N];
+    if parts.len() != N + 1 {
+        return None;
+    }
+    for (part, byte) in parts[1..].iter().zip(bytes.iter_mut()) {
+        *byte = Byte::from_i32_in(part.parse().ok()?, size).ok()?;
+    }
+    Some((sign, bytes))
+}
+
+// A Word, Index or JumpAddress shown as its value on a machine with the given byte size, which
+// Display alone can't know. {:#} is the byte form, the same for any size.
+#[derive(Debug, Clone, Copy)]
+pub struct DisplayIn<T> {
+    value: T,
+    size: ByteSize,
+}
+
+// the value with -0 kept, padded as a whole
+fn pad_value(f: &mut fmt::Formatter, sign: Sign, magnitude: i64) -> fmt::Result {
+    match sign {
+        Sign::PLUS => f.pad(&magnitude.to_string()),
+        Sign::MINUS => f.pad(&format!("-{}", magnitude)),
+    }
+}
+
+fn format_byte_form(sign: Sign, bytes: &[Byte]) -> String {
+    let mut text = sign.to_string();
+    for byte in bytes {
+        text += &format!(" {}", byte.to_i32());
+    }
+    text
+}
+
 impl Word {
     pub const ZERO: Self = Self {
         sign: Sign::PLUS,
@@ -372,36 +484,62 @@ impl Word {
     };
 
     pub fn to_i32(&self) -> i32 {
-        self.sign.value()
-            * (64_i32.pow(4) * self.bytes.0.to_i32()
-                + 64_i32.pow(3) * self.bytes.1.to_i32()
-                + 64_i32.pow(2) * self.bytes.2.to_i32()
-                + 64 * self.bytes.3.to_i32()
-                + self.bytes.4.to_i32())
+        self.to_i64_in(ByteSize::BINARY) as i32
+    }
+
+    pub fn to_i64_in(&self, size: ByteSize) -> i64 {
+        let base = size.0 as i64;
+        let magnitude = match size == ByteSize::BINARY {
+            // binary bytes are just six bits each
+            true => self
+                .byte_array()
+                .iter()
+                .fold(0, |value, byte| (value << 6) | byte.0 as i64),
+            false => self
+                .byte_array()
+                .iter()
+                .fold(0, |value, byte| value * base + byte.0 as i64),
+        };
+        self.sign.value() as i64 * magnitude
+    }
+
+    pub fn display_in(&self, size: ByteSize) -> DisplayIn<Self> {
+        DisplayIn { value: *self, size }
     }
 
     pub fn from_i32(value: i32) -> Result<Self, WordValueError> {
-        if value > 1_073_741_823 {
+        Self::from_i64_in(value as i64, ByteSize::BINARY)
+    }
+
+    pub fn from_i64_in(value: i64, size: ByteSize) -> Result<Self, WordValueError> {
+        if value >= size.capacity(5) {
             return Err(WordValueError::Overflow(value));
         }
-        if value < -1_073_741_823 {
+        if value <= -size.capacity(5) {
             return Err(WordValueError::Underflow(value));
         }
-        let sign = Sign::from_i32(value);
-        let mut x = value.abs();
-        let first_byte = Byte::from_i32(x / 64_i32.pow(4)).unwrap();
-        x = x % 64_i32.pow(4);
-        let second_byte = Byte::from_i32(x / 64_i32.pow(3)).unwrap();
-        x = x % 64_i32.pow(3);
-        let third_byte = Byte::from_i32(x / 64_i32.pow(2)).unwrap();
-        x = x % 64_i32.pow(2);
-        let fourth_byte = Byte::from_i32(x / 64).unwrap();
-        x = x % 64;
-        let fifth_byte = Byte::from_i32(x).unwrap();
-        Ok(Word {
-            sign,
-            bytes: (first_byte, second_byte, third_byte, fourth_byte, fifth_byte),
-        })
+        let magnitude = value.abs();
+        let mut bytes = [Byte::ZERO; 5];
+        for (idx, byte) in bytes.iter_mut().enumerate() {
+            let place = 4 - idx as u32;
+            *byte = match size == ByteSize::BINARY {
+                true => Byte((magnitude >> (6 * place) & 63) as u8),
+                false => Byte((magnitude / size.capacity(place) % size.0 as i64) as u8),
+            };
+        }
+        Ok(Word::from_byte_array(
+            Sign::from_i32(value.signum() as i32),
+            bytes,
+        ))
+    }
+
+    // the largest word on a machine with bytes of the given size
+    pub fn max_in(size: ByteSize) -> Self {
+        let byte = Byte::max_in(size);
+        Self {
+            sign: Sign::PLUS,
+            bytes: (byte, byte, byte, byte, byte),
+        }
     }
 
     pub fn from_instruction_parts(
@@ -411,26 +549,37 @@ impl Word {
         field: i32,
         code: i32,
     ) -> Result<Self, InstructionValueError> {
-        if address.abs() > 4095 {
+        Self::from_instruction_parts_in(sign, address, index, field, code, ByteSize::BINARY)
+    }
+
+    pub fn from_instruction_parts_in(
+        sign: Sign,
+        address: i32,
+        index: i32,
+        field: i32,
+        code: i32,
+        size: ByteSize,
+    ) -> Result<Self, InstructionValueError> {
+        if address.abs() as i64 >= size.capacity(2) {
             return Err(InstructionValueError::Address(address));
         }
-        if index < 0 || index > 6 {
+        if !(0..=6).contains(&index) {
             return Err(InstructionValueError::Index(index));
         }
-        if field < 0 || field > 63 {
+        if !(0..size.0).contains(&field) {
             return Err(InstructionValueError::Field(field));
         }
-        if code < 0 || code > 63 {
+        if !(0..size.0).contains(&code) {
             return Err(InstructionValueError::Code(code));
         }
         Ok(Word {
             sign,
             bytes: (
-                Byte::from_i32(address / 64).unwrap(),
-                Byte::from_i32(address % 64).unwrap(),
-                Byte::from_i32(index).unwrap(),
-                Byte::from_i32(field).unwrap(),
-                Byte::from_i32(code).unwrap(),
+                Byte((address.abs() / size.0) as u8),
+                Byte((address.abs() % size.0) as u8),
+                Byte(index as u8),
+                Byte(field as u8),
+                Byte(code as u8),
             ),
         })
     }
@@ -443,30 +592,67 @@ impl Word {
         value4: i32,
         value5: i32,
     ) -> Result<Word, WordValueError> {
-        let byte1 = match Byte::from_i32(value1) {
-            Ok(x) => x,
-            Err(_) => return Err(WordValueError::Overflow(value1)),
-        };
-        let byte2 = match Byte::from_i32(value2) {
-            Ok(x) => x,
-            Err(_) => return Err(WordValueError::Overflow(value2)),
-        };
-        let byte3 = match Byte::from_i32(value3) {
-            Ok(x) => x,
-            Err(_) => return Err(WordValueError::Overflow(value3)),
-        };
-        let byte4 = match Byte::from_i32(value4) {
-            Ok(x) => x,
-            Err(_) => return Err(WordValueError::Overflow(value4)),
+        Self::from_byte_values_in(
+            sign,
+            [value1, value2, value3, value4, value5],
+            ByteSize::BINARY,
+        )
+    }
+
+    pub fn from_byte_values_in(
+        sign: Sign,
+        values: [i32; 5],
+        size: ByteSize,
+    ) -> Result<Word, WordValueError> {
+        let mut bytes = [Byte::ZERO; 5];
+        for (byte, value) in bytes.iter_mut().zip(values) {
+            *byte = match Byte::from_i32_in(value, size) {
+                Ok(x) => x,
+                Err(_) => return Err(WordValueError::Overflow(value as i64)),
+            };
+        }
+        Ok(Word::from_byte_array(sign, bytes))
+    }
+
+    // the bytes as an array, which is easier to slice than the tuple
+    pub fn byte_array(&self) -> [Byte; 5] {
+        let (b1, b2, b3, b4, b5) = self.bytes;
+        [b1, b2, b3, b4, b5]
+    }
+
+    pub fn from_byte_array(sign: Sign, bytes: [Byte; 5]) -> Self {
+        let [b1, b2, b3, b4, b5] = bytes;
+        Self {
+            sign,
+            bytes: (b1, b2, b3, b4, b5),
+        }
+    }
+
+    // A field as a load sees it (section 1.3.1): the bytes are shifted over to the right hand end
+    // and the sign is + unless the field includes it. The field has to be valid.
+    pub fn get_field(&self, field: FieldSpecification) -> Word {
+        let range = field.byte_range();
+        let mut bytes = [Byte::ZERO; 5];
+        bytes[5 - range.len()..].copy_from_slice(&self.byte_array()[range]);
+        let sign = match field.left {
+            0 => self.sign,
+            _ => Sign::PLUS,
         };
-        let byte5 = match Byte::from_i32(value5) {
-            Ok(x) => x,
-            Err(_) => return Err(WordValueError::Overflow(value5)),
+        Word::from_byte_array(sign, bytes)
+    }
+
+    // This word after a store of value into a field: the field takes the rightmost bytes of value,
+    // and its sign too if the field includes it. Everything else is left alone.
+    pub fn with_field(&self, field: FieldSpecification, value: Word) -> Word {
+        let range = field.byte_range();
+        let mut bytes = self.byte_array();
+        let length = range.len();
+        bytes[range].copy_from_slice(&value.byte_array()[5 - length..]);
+        let sign = match field.left {
+            0 => value.sign,
+            _ => self.sign,
         };
-        Ok(Word {
-            sign,
-            bytes: (byte1, byte2, byte3, byte4, byte5),
-        })
+        Word::from_byte_array(sign, bytes)
     }
 
     pub fn with_sign(&self, sign: Sign) -> Self {
@@ -484,7 +670,11 @@ impl Word {
     }
 
     pub fn address(&self) -> i32 {
-        self.sign.value() * (self.bytes.0.to_i32() * 64 + self.bytes.1.to_i32())
+        self.address_in(ByteSize::BINARY)
+    }
+
+    pub fn address_in(&self, size: ByteSize) -> i32 {
+        self.sign.value() * (self.bytes.0.to_i32() * size.0 + self.bytes.1.to_i32())
     }
 
     pub fn index(&self) -> i32 {
@@ -502,6 +692,125 @@ impl Word {
     pub fn code(&self) -> i32 {
         self.bytes.4.to_i32()
     }
+
+    // the sign followed by each byte value, e.g. "- 0 1 2 3 4", as used in the text file formats
+    pub fn to_byte_string(&self) -> String {
+        format_byte_form(self.sign, &self.byte_array())
+    }
+
+    pub fn from_byte_string(value: &str) -> Option<Self> {
+        Self::from_byte_string_in(value, ByteSize::BINARY)
+    }
+
+    pub fn from_byte_string_in(value: &str, size: ByteSize) -> Option<Self> {
+        let (sign, bytes) = parse_byte_form(value, size)?;
+        Some(Self::from_byte_array(sign, bytes))
+    }
+
+    // a decimal number, "-0" included, or the byte form
+    pub fn parse_in(value: &str, size: ByteSize) -> Result<Self, ParseValueError> {
+        let value = value.trim();
+        if value.contains(char::is_whitespace) {
+            return Self::from_byte_string_in(value, size)
+                .ok_or_else(|| ParseValueError::Invalid(value.to_string()));
+        }
+        let (sign, magnitude) = parse_decimal(value)?;
+        if magnitude >= size.capacity(5) {
+            return Err(out_of_range(sign, magnitude));
+        }
+        Ok(Self::from_i64_in(magnitude, size).unwrap().with_sign(sign))
+    }
+
+    // ADD and SUB as the hardware does them: the result keeps its low five bytes and the flag is
+    // the overflow toggle. A zero result has the sign of self.
+    pub fn overflowing_add_in(&self, other: Word, size: ByteSize) -> (Word, bool) {
+        let (sign, magnitude, overflow) = register_sum(
+            self.sign,
+            self.to_i64_in(size),
+            other.to_i64_in(size),
+            size.capacity(5),
+        );
+        let word = Self::from_i64_in(magnitude, size).unwrap().with_sign(sign);
+        (word, overflow)
+    }
+
+    pub fn overflowing_sub_in(&self, other: Word, size: ByteSize) -> (Word, bool) {
+        self.overflowing_add_in(-other, size)
+    }
+
+    pub fn overflowing_add(&self, other: Word) -> (Word, bool) {
+        self.overflowing_add_in(other, ByteSize::BINARY)
+    }
+
+    pub fn overflowing_sub(&self, other: Word) -> (Word, bool) {
+        self.overflowing_sub_in(other, ByteSize::BINARY)
+    }
+
+    pub fn checked_add(&self, other: Word) -> Option<Word> {
+        match self.overflowing_add(other) {
+            (word, false) => Some(word),
+            (_, true) => None,
+        }
+    }
+
+    pub fn checked_sub(&self, other: Word) -> Option<Word> {
+        self.checked_add(-other)
+    }
+}
+
+impl Neg for Word {
+    type Output = Word;
+
+    fn neg(self) -> Word {
+        self.with_opposite_sign()
+    }
+}
+
+// + and - wrap like the hardware on a binary machine, overflowing_add says when they did
+impl Add for Word {
+    type Output = Word;
+
+    fn add(self, other: Word) -> Word {
+        self.overflowing_add(other).0
+    }
+}
+
+impl Sub for Word {
+    type Output = Word;
+
+    fn sub(self, other: Word) -> Word {
+        self.overflowing_sub(other).0
+    }
+}
+
+impl fmt::Display for DisplayIn<Word> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let word = self.value;
+        match f.alternate() {
+            true => f.pad(&word.to_byte_string()),
+            false => pad_value(
+                f,
+                word.sign,
+                word.with_sign(Sign::PLUS).to_i64_in(self.size),
+            ),
+        }
+    }
+}
+
+// {} is the value on a binary machine, with -0 kept, and {:#} the byte form. Use display_in for
+// the value on a decimal machine.
+impl fmt::Display for Word {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.display_in(ByteSize::BINARY).fmt(f)
+    }
+}
+
+impl FromStr for Word {
+    type Err = ParseValueError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::parse_in(value, ByteSize::BINARY)
+    }
 }
 
 #[test]
@@ -553,7 +862,121 @@ fn should_make_correct_word_for_i32_value() {
     );
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[test]
+fn should_convert_word_to_and_from_byte_string() {
+    let word = Word::from_byte_values(Sign::MINUS, 0, 1, 2, 3, 63).unwrap();
+    assert_eq!(word.to_byte_string(), "- 0 1 2 3 63");
+    assert_eq!(Word::from_byte_string("- 0 1 2 3 63"), Some(word));
+    assert_eq!(Word::from_byte_string("+ 0 1 2 3"), None);
+    assert_eq!(Word::from_byte_string("+ 0 1 2 3 64"), None);
+    assert_eq!(Word::from_byte_string("0 1 2 3 4 5"), None);
+}
+
+#[test]
+fn should_convert_decimal_words() {
+    let size = ByteSize::DECIMAL;
+    let word = Word::from_i64_in(-1_020_304_050, size).unwrap();
+    assert_eq!(word.to_byte_string(), "- 10 20 30 40 50");
+    assert_eq!(word.to_i64_in(size), -1_020_304_050);
+    assert_eq!(Word::max_in(size).to_i64_in(size), 9_999_999_999);
+    assert_eq!(
+        Word::from_i64_in(10_000_000_000, size),
+        Err(WordValueError::Overflow(10_000_000_000))
+    );
+    let instruction = Word::from_instruction_parts_in(Sign::PLUS, 3999, 1, 5, 8, size).unwrap();
+    assert_eq!(instruction.to_byte_string(), "+ 39 99 1 5 8");
+    assert_eq!(instruction.address_in(size), 3999);
+    assert_eq!(
+        Word::from_byte_string_in("+ 0 0 0 0 99", size)
+            .unwrap()
+            .to_i64_in(size),
+        99
+    );
+    assert_eq!(
+        Index::from_i32_in(9999, size).unwrap().to_i32_in(size),
+        9999
+    );
+    assert_eq!(
+        JumpAddress::from_i32_in(3999, size)
+            .unwrap()
+            .to_i32_in(size),
+        3999
+    );
+}
+
+#[test]
+fn should_add_and_subtract_words_like_the_hardware() {
+    let minus_zero = -Word::ZERO;
+    assert_eq!(minus_zero.sign, Sign::MINUS);
+    assert_eq!(-minus_zero, Word::ZERO);
+    let five = Word::from_i32(5).unwrap();
+    assert_eq!((five - Word::from_i32(7).unwrap()).to_i32(), -2);
+    // a zero result keeps the sign of the left hand side
+    assert_eq!(minus_zero + Word::ZERO, minus_zero);
+    assert_eq!(-five + five, minus_zero);
+    assert_eq!(five - five, Word::ZERO);
+    assert_eq!(
+        Word::MAX.overflowing_add(five),
+        (Word::from_i32(4).unwrap(), true)
+    );
+    assert_eq!(
+        Word::MIN.overflowing_sub(five),
+        (Word::from_i32(-4).unwrap(), true)
+    );
+    assert_eq!(Word::MAX.checked_add(five), None);
+    assert_eq!(
+        Word::MAX.checked_sub(five),
+        Word::from_i32(1_073_741_818).ok()
+    );
+    let size = ByteSize::DECIMAL;
+    let (sum, overflow) = Word::max_in(size).overflowing_add_in(five, size);
+    assert_eq!((sum.to_i64_in(size), overflow), (4, true));
+}
+
+#[test]
+fn should_display_and_parse_words_both_ways() {
+    let word = Word::from_byte_values(Sign::MINUS, 0, 0, 0, 1, 2).unwrap();
+    assert_eq!(word.to_string(), "-66");
+    assert_eq!(format!("{:#}", word), "- 0 0 0 1 2");
+    assert_eq!(format!("{:>5}", word), "  -66");
+    assert_eq!((-Word::ZERO).to_string(), "-0");
+    assert_eq!("-66".parse(), Ok(word));
+    assert_eq!("- 0 0 0 1 2".parse(), Ok(word));
+    assert_eq!("+66".parse(), Ok(-word));
+    assert_eq!("-0".parse(), Ok(-Word::ZERO));
+    assert_eq!(" - 0 0 0 0 0 ".parse(), Ok(-Word::ZERO));
+    assert_eq!(
+        "1073741824".parse::<Word>(),
+        Err(ParseValueError::Overflow(1_073_741_824))
+    );
+    assert_eq!(
+        "-1073741824".parse::<Word>(),
+        Err(ParseValueError::Underflow(-1_073_741_824))
+    );
+    assert_eq!(
+        "+ 0 0 0 0 64".parse::<Word>(),
+        Err(ParseValueError::Invalid("+ 0 0 0 0 64".to_string()))
+    );
+    assert!("12a".parse::<Word>().is_err());
+    assert!("--1".parse::<Word>().is_err());
+    assert!("".parse::<Word>().is_err());
+    assert_eq!(
+        Word::parse_in("+ 0 0 0 0 99", ByteSize::DECIMAL).map(|x| x.to_i64_in(ByteSize::DECIMAL)),
+        Ok(99)
+    );
+    assert_eq!(
+        Word::parse_in("9999999999", ByteSize::DECIMAL),
+        Ok(Word::max_in(ByteSize::DECIMAL))
+    );
+    let word = Word::from_byte_values(Sign::MINUS, 0, 0, 0, 1, 1).unwrap();
+    assert_eq!(word.display_in(ByteSize::DECIMAL).to_string(), "-101");
+    assert_eq!(
+        format!("{:#}", word.display_in(ByteSize::DECIMAL)),
+        "- 0 0 0 1 1"
+    );
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub struct Index {
     pub sign: Sign,
     pub bytes: (Byte, Byte),
@@ -582,19 +1005,31 @@ impl Index {
     };
 
     pub fn to_i32(&self) -> i32 {
-        self.sign.value() * (self.bytes.0.to_i32() * 64 + self.bytes.1.to_i32())
+        self.to_i32_in(ByteSize::BINARY)
+    }
+
+    pub fn to_i32_in(&self, size: ByteSize) -> i32 {
+        self.sign.value() * (self.bytes.0.to_i32() * size.0 + self.bytes.1.to_i32())
+    }
+
+    pub fn display_in(&self, size: ByteSize) -> DisplayIn<Self> {
+        DisplayIn { value: *self, size }
     }
 
     pub fn from_i32(value: i32) -> Result<Self, IndexValueError> {
-        if value > 4095 {
+        Self::from_i32_in(value, ByteSize::BINARY)
+    }
+
+    pub fn from_i32_in(value: i32, size: ByteSize) -> Result<Self, IndexValueError> {
+        if value as i64 >= size.capacity(2) {
             return Err(IndexValueError::Overflow(value));
         }
-        if value < -4095 {
+        if value as i64 <= -size.capacity(2) {
             return Err(IndexValueError::Underflow(value));
         }
         let sign = Sign::from_i32(value);
-        let first_byte = Byte::from_i32(value.abs() / 64).unwrap();
-        let second_byte = Byte::from_i32(value.abs() % 64).unwrap();
+        let first_byte = Byte((value.abs() / size.0) as u8);
+        let second_byte = Byte((value.abs() % size.0) as u8);
         Ok(Self {
             sign,
             bytes: (first_byte, second_byte),
@@ -640,6 +1075,119 @@ impl Index {
             bytes: self.bytes,
         }
     }
+
+    // a decimal number, "-0" included, or the byte form "+ 1 2"
+    pub fn parse_in(value: &str, size: ByteSize) -> Result<Self, ParseValueError> {
+        let value = value.trim();
+        if value.contains(char::is_whitespace) {
+            let (sign, [b1, b2]) = parse_byte_form(value, size)
+                .ok_or_else(|| ParseValueError::Invalid(value.to_string()))?;
+            return Ok(Self {
+                sign,
+                bytes: (b1, b2),
+            });
+        }
+        let (sign, magnitude) = parse_decimal(value)?;
+        if magnitude >= size.capacity(2) {
+            return Err(out_of_range(sign, magnitude));
+        }
+        Ok(Self::from_i32_in(magnitude as i32, size)
+            .unwrap()
+            .with_sign(sign))
+    }
+
+    // the flag is set when the sum doesn't fit in two bytes, which Knuth leaves undefined for the
+    // index registers. A zero result has the sign of self.
+    pub fn overflowing_add_in(&self, other: Index, size: ByteSize) -> (Index, bool) {
+        let (sign, magnitude, overflow) = register_sum(
+            self.sign,
+            self.to_i32_in(size) as i64,
+            other.to_i32_in(size) as i64,
+            size.capacity(2),
+        );
+        let index = Self::from_i32_in(magnitude as i32, size)
+            .unwrap()
+            .with_sign(sign);
+        (index, overflow)
+    }
+
+    pub fn overflowing_sub_in(&self, other: Index, size: ByteSize) -> (Index, bool) {
+        self.overflowing_add_in(-other, size)
+    }
+
+    pub fn overflowing_add(&self, other: Index) -> (Index, bool) {
+        self.overflowing_add_in(other, ByteSize::BINARY)
+    }
+
+    pub fn overflowing_sub(&self, other: Index) -> (Index, bool) {
+        self.overflowing_sub_in(other, ByteSize::BINARY)
+    }
+
+    pub fn checked_add(&self, other: Index) -> Option<Index> {
+        match self.overflowing_add(other) {
+            (index, false) => Some(index),
+            (_, true) => None,
+        }
+    }
+
+    pub fn checked_sub(&self, other: Index) -> Option<Index> {
+        self.checked_add(-other)
+    }
+}
+
+impl Neg for Index {
+    type Output = Index;
+
+    fn neg(self) -> Index {
+        self.with_opposite_sign()
+    }
+}
+
+impl Add for Index {
+    type Output = Index;
+
+    fn add(self, other: Index) -> Index {
+        self.overflowing_add(other).0
+    }
+}
+
+impl Sub for Index {
+    type Output = Index;
+
+    fn sub(self, other: Index) -> Index {
+        self.overflowing_sub(other).0
+    }
+}
+
+impl fmt::Display for DisplayIn<Index> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let index = self.value;
+        match f.alternate() {
+            true => f.pad(&format_byte_form(
+                index.sign,
+                &[index.bytes.0, index.bytes.1],
+            )),
+            false => {
+                let magnitude = index.with_sign(Sign::PLUS).to_i32_in(self.size);
+                pad_value(f, index.sign, magnitude.into())
+            }
+        }
+    }
+}
+
+// the value on a binary machine like Word
+impl fmt::Display for Index {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.display_in(ByteSize::BINARY).fmt(f)
+    }
+}
+
+impl FromStr for Index {
+    type Err = ParseValueError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::parse_in(value, ByteSize::BINARY)
+    }
 }
 
 #[test]
@@ -712,7 +1260,34 @@ fn should_make_correct_index_for_i32_value() {
     );
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[test]
+fn should_do_index_arithmetic_and_text() {
+    let index = Index::from_i32(-131).unwrap();
+    assert_eq!(index.to_string(), "-131");
+    assert_eq!(format!("{:#}", index), "- 2 3");
+    assert_eq!("-131".parse(), Ok(index));
+    assert_eq!("- 2 3".parse(), Ok(index));
+    assert_eq!("-0".parse(), Ok(-Index::ZERO));
+    assert_eq!((-Index::ZERO).to_string(), "-0");
+    assert_eq!(
+        "4096".parse::<Index>(),
+        Err(ParseValueError::Overflow(4096))
+    );
+    assert_eq!(index + -index, index - index);
+    assert_eq!((index - index).sign, Sign::MINUS);
+    assert_eq!(
+        Index::MAX.overflowing_add(Index::from_i32(1).unwrap()),
+        (Index::ZERO, true)
+    );
+    assert_eq!(Index::MIN.checked_sub(Index::from_i32(1).unwrap()), None);
+    let index = Index::from_i32_in(-101, ByteSize::DECIMAL).unwrap();
+    assert_eq!(index.display_in(ByteSize::DECIMAL).to_string(), "-101");
+    let address = JumpAddress::from_i32_in(101, ByteSize::DECIMAL).unwrap();
+    assert_eq!(address.to_string(), "65");
+    assert_eq!(address.display_in(ByteSize::DECIMAL).to_string(), "101");
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub struct JumpAddress(Byte, Byte);
 
 #[derive(Debug, PartialEq, Eq)]
@@ -727,21 +1302,37 @@ impl JumpAddress {
     pub const MAX: Self = Self(Byte::MAX, Byte::MAX);
 
     pub fn to_i32(&self) -> i32 {
-        64 * self.0.to_i32() + self.1.to_i32()
+        self.to_i32_in(ByteSize::BINARY)
+    }
+
+    pub fn to_i32_in(&self, size: ByteSize) -> i32 {
+        size.0 * self.0.to_i32() + self.1.to_i32()
+    }
+
+    pub fn display_in(&self, size: ByteSize) -> DisplayIn<Self> {
+        DisplayIn { value: *self, size }
     }
 
     pub fn from_i32(value: i32) -> Result<Self, JumpAddressValueError> {
-        if value > 4095 {
+        Self::from_i32_in(value, ByteSize::BINARY)
+    }
+
+    pub fn from_i32_in(value: i32, size: ByteSize) -> Result<Self, JumpAddressValueError> {
+        if value as i64 >= size.capacity(2) {
             return Err(JumpAddressValueError::Overflow(value));
         }
         if value < 0 {
             return Err(JumpAddressValueError::Underflow(value));
         }
-        let first_byte = Byte::from_i32(value / 64).unwrap();
-        let second_byte = Byte::from_i32(value % 64).unwrap();
+        let first_byte = Byte((value / size.0) as u8);
+        let second_byte = Byte((value % size.0) as u8);
         Ok(Self(first_byte, second_byte))
     }
 
+    pub fn bytes(&self) -> (Byte, Byte) {
+        (self.0, self.1)
+    }
+
     pub fn from_byte_values(value1: i32, value2: i32) -> Result<Self, JumpAddressValueError> {
         let first_byte = match Byte::from_i32(value1) {
             Ok(x) => x,
@@ -761,6 +1352,48 @@ impl JumpAddress {
 
         Ok(Self(first_byte, second_byte))
     }
+
+    // a decimal number or the byte form "+ 1 2", rJ is never negative
+    pub fn parse_in(value: &str, size: ByteSize) -> Result<Self, ParseValueError> {
+        let value = value.trim();
+        if value.contains(char::is_whitespace) {
+            return match parse_byte_form(value, size) {
+                Some((Sign::PLUS, [b1, b2])) => Ok(Self(b1, b2)),
+                _ => Err(ParseValueError::Invalid(value.to_string())),
+            };
+        }
+        match parse_decimal(value)? {
+            (Sign::PLUS, magnitude) if magnitude < size.capacity(2) => {
+                Ok(Self::from_i32_in(magnitude as i32, size).unwrap())
+            }
+            (sign, magnitude) => Err(out_of_range(sign, magnitude)),
+        }
+    }
+}
+
+impl fmt::Display for DisplayIn<JumpAddress> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let address = self.value;
+        match f.alternate() {
+            true => f.pad(&format_byte_form(Sign::PLUS, &[address.0, address.1])),
+            false => f.pad(&address.to_i32_in(self.size).to_string()),
+        }
+    }
+}
+
+// the value on a binary machine like Word
+impl fmt::Display for JumpAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.display_in(ByteSize::BINARY).fmt(f)
+    }
+}
+
+impl FromStr for JumpAddress {
+    type Err = ParseValueError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::parse_in(value, ByteSize::BINARY)
+    }
 }
 
 #[test]
@@ -798,3 +1431,21 @@ fn should_make_correct_jump_address_for_i32_value() {
         Err(JumpAddressValueError::Underflow(-1))
     );
 }
+
+#[test]
+fn should_display_and_parse_jump_addresses() {
+    let jump_address = JumpAddress::from_i32(131).unwrap();
+    assert_eq!(jump_address.to_string(), "131");
+    assert_eq!(format!("{:#}", jump_address), "+ 2 3");
+    assert_eq!("131".parse(), Ok(jump_address));
+    assert_eq!("+ 2 3".parse(), Ok(jump_address));
+    assert_eq!(
+        "-1".parse::<JumpAddress>(),
+        Err(ParseValueError::Underflow(-1))
+    );
+    assert!("- 2 3".parse::<JumpAddress>().is_err());
+    assert_eq!(
+        JumpAddress::parse_in("3999", ByteSize::DECIMAL).map(|x| x.bytes()),
+        Ok((Byte(39), Byte(99)))
+    );
+}
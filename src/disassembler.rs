@@ -0,0 +1,58 @@
+use crate::{
+    data_types::{ByteSize, Sign, Word},
+    opcodes::{self, OperandKind},
+};
+
+// Renders an instruction word back into MIXAL, leaving out the index and field parts when they
+// are the defaults the assembler would fill in. Words that aren't valid instructions come out as
+// CON so the result can always be assembled again.
+pub fn disassemble(instruction: Word) -> String {
+    disassemble_in(instruction, ByteSize::BINARY)
+}
+
+pub fn disassemble_in(instruction: Word, size: ByteSize) -> String {
+    let opcode = match opcodes::decode(instruction) {
+        Some(x) => x,
+        None => return format!("CON {}", instruction.to_i64_in(size)),
+    };
+    let field = instruction.field().value();
+    let field_part = match opcode.operand {
+        OperandKind::FIXED => String::new(),
+        _ if field == opcode.field => String::new(),
+        OperandKind::FIELD => format!("({}:{})", field / 8, field % 8),
+        OperandKind::UNIT | OperandKind::COUNT => format!("({})", field),
+    };
+    let index_part = match instruction.index() {
+        0 => String::new(),
+        i => format!(",{}", i),
+    };
+    let address = instruction.address_in(size);
+    let address_part = match instruction.sign {
+        Sign::MINUS if address == 0 => "-0".to_string(),
+        _ if address == 0 && index_part.is_empty() && field_part.is_empty() => String::new(),
+        _ => address.to_string(),
+    };
+    if address_part.is_empty() {
+        return opcode.mnemonic.to_string();
+    }
+    format!(
+        "{} {}{}{}",
+        opcode.mnemonic, address_part, index_part, field_part
+    )
+}
+
+#[test]
+fn should_disassemble_instructions() {
+    let lda = Word::from_instruction_parts(Sign::PLUS, 2000, 1, 3, 8).unwrap();
+    assert_eq!(disassemble(lda), "LDA 2000,1(0:3)");
+    let lda_default_field = Word::from_instruction_parts(Sign::MINUS, 10, 0, 5, 8).unwrap();
+    assert_eq!(disassemble(lda_default_field), "LDA -10");
+    let hlt = Word::from_instruction_parts(Sign::PLUS, 0, 0, 2, 5).unwrap();
+    assert_eq!(disassemble(hlt), "HLT");
+    let out = Word::from_instruction_parts(Sign::PLUS, 1000, 0, 18, 37).unwrap();
+    assert_eq!(disassemble(out), "OUT 1000(18)");
+    let enta = Word::from_instruction_parts(Sign::MINUS, 0, 0, 2, 48).unwrap();
+    assert_eq!(disassemble(enta), "ENTA -0");
+    let bad = Word::from_instruction_parts(Sign::PLUS, 0, 0, 12, 39).unwrap();
+    assert_eq!(disassemble(bad), format!("CON {}", bad.to_i32()));
+}
@@ -0,0 +1,142 @@
+use crate::{
+    computer::{
+        devices::{Devices, DiskUnit, TapeUnit, TextInput, TextOutput},
+        interrupts::TIMER,
+        memory, Computer, Fault, StopReason,
+    },
+    data_types::{Byte, ByteSize, Index, Sign, Word},
+};
+
+// For the targets in fuzz/ and tests/fuzzing.rs, which is why it's public, but not meant for
+// anything else and it can change whenever they do.
+//
+// The first byte picks the byte size and whether interrupts are on, with the rest of it starting
+// the timer at -10 so that interrupts actually happen, and every six bytes after it are a word for
+// memory starting at 0, a sign and five bytes taken modulo the byte size. The machine has cards,
+// tapes, a disk, a punch and a printer attached and runs from 0 for at most limit instructions.
+// Whatever the program does it should fault or stop rather than panic, and this panics if the
+// machine is left in a state it should never be in, otherwise it gives back the machine and how
+// the run ended.
+pub fn run_arbitrary(data: &[u8], limit: u64) -> (Computer, Result<StopReason, Fault>) {
+    let (&first, rest) = data.split_first().unwrap_or((&0, &[]));
+    let byte_size = match first & 1 {
+        0 => ByteSize::BINARY,
+        _ => ByteSize::DECIMAL,
+    };
+    let mut computer = Computer::with_byte_size(byte_size);
+    if first & 2 != 0 {
+        computer.enable_interrupts();
+        let timer = Word::from_i64_in(i64::from(first >> 2), byte_size).unwrap();
+        computer.memory.set_in_control_state(TIMER, timer).unwrap();
+    }
+    computer.devices = Devices::new();
+    computer.devices.card_reader = Some(TextInput::card_reader(vec![
+        "0000000001".to_string(),
+        "HELLO".to_string(),
+    ]));
+    computer.devices.card_punch = Some(TextOutput::card_punch());
+    computer.devices.line_printer = Some(TextOutput::line_printer());
+    computer.devices.tapes[0] = Some(TapeUnit::new(4));
+    computer.devices.disks[0] = Some(DiskUnit::new(4));
+    let words = rest.chunks_exact(6).take(memory::SIZE);
+    for (address, chunk) in words.enumerate() {
+        let byte = |x: u8| Byte::from_i32_in(i32::from(x) % byte_size.values(), byte_size).unwrap();
+        let word = Word {
+            sign: match chunk[0] & 1 {
+                0 => Sign::PLUS,
+                _ => Sign::MINUS,
+            },
+            bytes: (
+                byte(chunk[1]),
+                byte(chunk[2]),
+                byte(chunk[3]),
+                byte(chunk[4]),
+                byte(chunk[5]),
+            ),
+        };
+        computer.memory.set(address as i32, word).unwrap();
+    }
+
+    let result = computer.run(Some(limit));
+    if let Err(e) = check_state(&computer, &result, limit) {
+        panic!("inconsistent state after {:?}: {}", result, e);
+    }
+    (computer, result)
+}
+
+fn check_state(
+    computer: &Computer,
+    result: &Result<StopReason, Fault>,
+    limit: u64,
+) -> Result<(), String> {
+    match result {
+        Ok(StopReason::HALTED) if computer.running => return Err("halted but running".into()),
+        Ok(StopReason::LIMIT) if computer.instruction_count < limit => {
+            return Err(format!("stopped after {}", computer.instruction_count))
+        }
+        Err(_) if computer.running => return Err("faulted but running".into()),
+        _ => (),
+    }
+    if computer.instruction_count > limit {
+        return Err(format!("ran {} instructions", computer.instruction_count));
+    }
+    // every instruction takes at least 1u
+    if computer.time < computer.instruction_count {
+        return Err(format!("took {}u", computer.time));
+    }
+    let size = computer.byte_size;
+    let registers = &computer.registers;
+    let indexes = [
+        ("rI1", registers.i1),
+        ("rI2", registers.i2),
+        ("rI3", registers.i3),
+        ("rI4", registers.i4),
+        ("rI5", registers.i5),
+        ("rI6", registers.i6),
+    ];
+    let mut words = vec![
+        ("rA".to_string(), registers.a),
+        ("rX".to_string(), registers.x),
+    ];
+    for (name, index) in indexes {
+        words.push((name.to_string(), index_word(index)));
+    }
+    let first_address = match computer.interrupts {
+        Some(_) => 1 - memory::SIZE as i32,
+        None => 0,
+    };
+    for address in first_address..memory::SIZE as i32 {
+        let word = computer.memory.get_in_control_state(address).unwrap();
+        words.push((address.to_string(), word));
+    }
+    for (name, word) in words {
+        let bytes = [
+            word.bytes.0,
+            word.bytes.1,
+            word.bytes.2,
+            word.bytes.3,
+            word.bytes.4,
+        ];
+        if bytes.iter().any(|x| x.to_i32() >= size.values()) {
+            return Err(format!("{} holds {}", name, word.to_byte_string()));
+        }
+    }
+    let j = registers.j.to_i32_in(size);
+    if !(0..size.values() * size.values()).contains(&j) {
+        return Err(format!("rJ holds {}", j));
+    }
+    Ok(())
+}
+
+fn index_word(index: Index) -> Word {
+    Word {
+        sign: index.sign,
+        bytes: (
+            Byte::ZERO,
+            Byte::ZERO,
+            Byte::ZERO,
+            index.bytes.0,
+            index.bytes.1,
+        ),
+    }
+}
@@ -0,0 +1,349 @@
+use std::{
+    fmt::Write as _,
+    fs, io,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex, PoisonError,
+    },
+    thread,
+};
+
+use crate::{
+    assembler::{self, AssemblerOptions},
+    computer::{
+        devices::{Devices, TapeUnit, TextInput, TextOutput, TAPE_BLOCK_SIZE},
+        Computer, StopReason,
+    },
+    data_types::{ByteSize, Word},
+    object::Program,
+};
+
+// Runs a batch of submissions for the same exercise, each on a fresh machine with the same
+// inputs, and checks what they print and punch. Every submission gets the same budget of
+// instructions and time, so a program that never halts only costs its own run.
+#[derive(Default)]
+pub struct Assignment {
+    pub byte_size: ByteSize,
+    pub cards: Vec<String>,
+    // tape units and what's on them, each run gets its own copy
+    pub tapes: Vec<(usize, Vec<[Word; TAPE_BLOCK_SIZE]>)>,
+    // the lines the printer and punch should end up with, not checked when None
+    pub expected_printer: Option<Vec<String>>,
+    pub expected_punch: Option<Vec<String>>,
+    pub instruction_limit: Option<u64>,
+    // in units of u
+    pub time_limit: Option<u64>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Outcome {
+    PASSED,
+    INCORRECT,
+    UNASSEMBLED,
+    FAULTED,
+    // ran out of instructions or time before halting
+    LIMIT,
+    TIMEOUT,
+}
+
+impl Outcome {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::PASSED => "passed",
+            Self::INCORRECT => "incorrect",
+            Self::UNASSEMBLED => "unassembled",
+            Self::FAULTED => "faulted",
+            Self::LIMIT => "limit",
+            Self::TIMEOUT => "timeout",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Report {
+    pub name: String,
+    pub outcome: Outcome,
+    // the assembler error, the fault, or the first line of output that was wrong
+    pub details: Option<String>,
+    pub instructions: u64,
+    pub time: u64,
+}
+
+impl Report {
+    fn new(name: &str, outcome: Outcome, details: Option<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            outcome,
+            details,
+            instructions: 0,
+            time: 0,
+        }
+    }
+}
+
+// A submission is either a single .mixal file or a directory of them, which are assembled as one
+// program in name order. Submissions are named by the file or directory and come back sorted.
+pub fn find_submissions(directory: impl AsRef<Path>) -> io::Result<Vec<(String, Vec<PathBuf>)>> {
+    let mut submissions = Vec::new();
+    for entry in fs::read_dir(directory)? {
+        let path = entry?.path();
+        let name = match path.file_stem() {
+            Some(x) => x.to_string_lossy().to_string(),
+            None => continue,
+        };
+        if path.is_dir() {
+            let mut files = mixal_files(&path)?;
+            if !files.is_empty() {
+                files.sort();
+                submissions.push((name, files));
+            }
+        } else if is_mixal(&path) {
+            submissions.push((name, vec![path]));
+        }
+    }
+    submissions.sort();
+    Ok(submissions)
+}
+
+fn mixal_files(directory: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(directory)? {
+        let path = entry?.path();
+        if is_mixal(&path) {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+fn is_mixal(path: &Path) -> bool {
+    path.is_file() && path.extension().is_some_and(|x| x == "mixal")
+}
+
+pub fn grade_files(assignment: &Assignment, name: &str, paths: &[PathBuf]) -> Report {
+    let options = AssemblerOptions {
+        byte_size: assignment.byte_size,
+        ..Default::default()
+    };
+    match assembler::assemble_files(paths, &options) {
+        Ok(program) => grade_program(assignment, name, &program),
+        Err(e) => Report::new(name, Outcome::UNASSEMBLED, Some(e.to_string())),
+    }
+}
+
+pub fn grade_source(assignment: &Assignment, name: &str, source: &str) -> Report {
+    let options = AssemblerOptions {
+        byte_size: assignment.byte_size,
+        file_name: Some(format!("{}.mixal", name)),
+        ..Default::default()
+    };
+    match assembler::assemble_with(source, &options) {
+        Ok(program) => grade_program(assignment, name, &program),
+        Err(e) => Report::new(name, Outcome::UNASSEMBLED, Some(e.to_string())),
+    }
+}
+
+pub fn grade_program(assignment: &Assignment, name: &str, program: &Program) -> Report {
+    let mut computer = Computer::with_byte_size(assignment.byte_size);
+    computer.devices = Devices::new();
+    computer.devices.card_reader = Some(TextInput::card_reader(assignment.cards.clone()));
+    computer.devices.card_punch = Some(TextOutput::card_punch());
+    computer.devices.line_printer = Some(TextOutput::line_printer());
+    for (unit, blocks) in assignment.tapes.iter() {
+        match computer.devices.tapes.get_mut(*unit) {
+            Some(x) => *x = Some(TapeUnit::from_blocks(blocks.clone())),
+            None => {
+                let details = format!("tape unit {} doesn't exist", unit);
+                return Report::new(name, Outcome::FAULTED, Some(details));
+            }
+        }
+    }
+    if let Err(e) = computer.load_program(program) {
+        let details = format!("the program didn't load: {}", e);
+        return Report::new(name, Outcome::UNASSEMBLED, Some(details));
+    }
+
+    let (outcome, details) = run(&mut computer, assignment);
+    let (outcome, details) = match outcome {
+        Outcome::PASSED => check_output(&computer, assignment),
+        _ => (outcome, details),
+    };
+    Report {
+        name: name.to_string(),
+        outcome,
+        details,
+        instructions: computer.instruction_count,
+        time: computer.time,
+    }
+}
+
+// one instruction at a time when there's a time limit, so no run goes over it by more than the
+// instruction that crossed it
+fn run(computer: &mut Computer, assignment: &Assignment) -> (Outcome, Option<String>) {
+    let step = match assignment.time_limit {
+        Some(_) => Some(1),
+        None => assignment.instruction_limit,
+    };
+    loop {
+        if assignment
+            .instruction_limit
+            .is_some_and(|x| computer.instruction_count >= x)
+        {
+            return (Outcome::LIMIT, None);
+        }
+        if assignment.time_limit.is_some_and(|x| computer.time >= x) {
+            return (Outcome::TIMEOUT, None);
+        }
+        match computer.run(step) {
+            Ok(StopReason::HALTED) => return (Outcome::PASSED, None),
+            Ok(StopReason::LIMIT) => (),
+            Err(fault) => return (Outcome::FAULTED, Some(fault.to_string())),
+        }
+    }
+}
+
+fn check_output(computer: &Computer, assignment: &Assignment) -> (Outcome, Option<String>) {
+    let devices = &computer.devices;
+    let outputs = [
+        (
+            "printer",
+            &assignment.expected_printer,
+            &devices.line_printer,
+        ),
+        ("punch", &assignment.expected_punch, &devices.card_punch),
+    ];
+    for (device, expected, output) in outputs {
+        let (expected, lines) = match (expected, output) {
+            (Some(x), Some(y)) => (x, &y.lines),
+            _ => continue,
+        };
+        if let Some(difference) = compare_lines(expected, lines) {
+            return (
+                Outcome::INCORRECT,
+                Some(format!("{} {}", device, difference)),
+            );
+        }
+    }
+    (Outcome::PASSED, None)
+}
+
+// where the output first differs from what was expected, if it does
+fn compare_lines(expected: &[String], lines: &[String]) -> Option<String> {
+    for (idx, (x, y)) in expected.iter().zip(lines.iter()).enumerate() {
+        if x != y {
+            return Some(format!("line {}: expected {:?}, got {:?}", idx + 1, x, y));
+        }
+    }
+    if expected.len() != lines.len() {
+        return Some(format!(
+            "expected {} lines, got {}",
+            expected.len(),
+            lines.len()
+        ));
+    }
+    None
+}
+
+// Grades the submissions on up to threads threads and returns the reports in the same order. A
+// submission that makes the grader panic is reported as faulted and the others carry on.
+pub fn grade_all(
+    assignment: &Assignment,
+    submissions: &[(String, Vec<PathBuf>)],
+    threads: usize,
+) -> Vec<Report> {
+    let next = AtomicUsize::new(0);
+    let reports: Mutex<Vec<Option<Report>>> =
+        Mutex::new(submissions.iter().map(|_| None).collect());
+    thread::scope(|scope| {
+        for _ in 0..threads.clamp(1, submissions.len().max(1)) {
+            scope.spawn(|| loop {
+                let idx = next.fetch_add(1, Ordering::Relaxed);
+                let (name, paths) = match submissions.get(idx) {
+                    Some(x) => x,
+                    None => break,
+                };
+                let report =
+                    panic::catch_unwind(AssertUnwindSafe(|| grade_files(assignment, name, paths)))
+                        .unwrap_or_else(|payload| {
+                            let details =
+                                format!("the grader crashed: {}", panic_message(&*payload));
+                            Report::new(name, Outcome::FAULTED, Some(details))
+                        });
+                reports.lock().unwrap_or_else(PoisonError::into_inner)[idx] = Some(report);
+            });
+        }
+    });
+    let reports = reports.into_inner().unwrap_or_else(PoisonError::into_inner);
+    reports
+        .into_iter()
+        .zip(submissions)
+        .map(|(report, (name, _))| {
+            report.unwrap_or_else(|| {
+                Report::new(name, Outcome::FAULTED, Some("not graded".to_string()))
+            })
+        })
+        .collect()
+}
+
+// panic! with a literal or with format arguments, which is all the grader can run into
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> &str {
+    match payload.downcast_ref::<&str>() {
+        Some(x) => x,
+        None => payload
+            .downcast_ref::<String>()
+            .map_or("unknown panic", |x| x.as_str()),
+    }
+}
+
+// {"total": 2, "passed": 1, "submissions": [{"name": "alice", "outcome": "passed", ...}, ...]}
+pub fn json_report(reports: &[Report]) -> String {
+    let passed = reports
+        .iter()
+        .filter(|x| x.outcome == Outcome::PASSED)
+        .count();
+    let mut json = format!(
+        "{{\n  \"total\": {},\n  \"passed\": {},\n  \"submissions\": [",
+        reports.len(),
+        passed
+    );
+    for (idx, report) in reports.iter().enumerate() {
+        let details = match &report.details {
+            Some(x) => json_string(x),
+            None => "null".to_string(),
+        };
+        let separator = if idx == 0 { "" } else { "," };
+        write!(
+            json,
+            "{}\n    {{\"name\": {}, \"outcome\": {}, \"passed\": {}, \"instructions\": {}, \
+             \"time\": {}, \"details\": {}}}",
+            separator,
+            json_string(&report.name),
+            json_string(report.outcome.name()),
+            report.outcome == Outcome::PASSED,
+            report.instructions,
+            report.time,
+            details
+        )
+        .unwrap();
+    }
+    json += "\n  ]\n}\n";
+    json
+}
+
+fn json_string(text: &str) -> String {
+    let mut result = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => result += "\\\"",
+            '\\' => result += "\\\\",
+            '\n' => result += "\\n",
+            '\t' => result += "\\t",
+            c if (c as u32) < 0x20 => write!(result, "\\u{:04x}", c as u32).unwrap(),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
@@ -0,0 +1,529 @@
+pub mod json;
+
+use std::{
+    collections::HashMap,
+    io::{self, BufRead, Write},
+};
+
+use crate::{
+    assembler::{
+        self, AssemblerError, AssemblerOptions, SourceField, SymbolKind, SymbolUse, DIRECTIVES,
+        PSEUDO_OPS,
+    },
+    opcodes::{Op, OPCODES},
+};
+use json::Json;
+
+// A language server for MIXAL, which editors start and talk to over stdin and stdout with
+// JSON-RPC as the Language Server Protocol describes. It keeps the text of each open file and
+// answers from that:
+//
+// - diagnostics, the first error the assembler finds, sent whenever a file is opened or changed
+// - go to definition and find references for symbols, with dB and dF going to the right dH, and
+//   for macros from where they're used
+// - hover, which shows the C, F and time of an instruction, what a pseudo-op does, or the line
+//   defining a symbol
+// - completion of mnemonics, pseudo-ops, directives and macros in the op field and of symbols in
+//   the address field
+//
+// Files are read a line at a time with the assembler's tokeniser, so navigation works while a
+// file doesn't assemble, but symbols in files it INCLUDEs aren't known.
+#[derive(Default)]
+pub struct Server {
+    // the text of each open file by URI
+    documents: HashMap<String, String>,
+    shut_down: bool,
+    // set by the exit notification to the code the process should exit with
+    pub exit: Option<i32>,
+}
+
+// error codes from JSON-RPC and the protocol
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+// the protocol's CompletionItemKind
+const FUNCTION: i64 = 3;
+const VARIABLE: i64 = 6;
+const KEYWORD: i64 = 14;
+
+const ERROR: i64 = 1;
+
+// the longest message read, far more than any source file, so a bad Content-Length is an error
+// rather than an allocation that aborts the server
+const MAX_MESSAGE_LENGTH: usize = 64 * 1024 * 1024;
+
+impl Server {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // the responses and notifications to send for a message from the editor, which may be none
+    pub fn handle(&mut self, message: &str) -> Vec<Json> {
+        let message = match Json::parse(message) {
+            Ok(x) => x,
+            Err(e) => return vec![error(Json::Null, PARSE_ERROR, &e)],
+        };
+        let method = message.get("method").as_str().unwrap_or("");
+        let params = message.get("params");
+        let id = message.get("id").clone();
+        // a notification, which never gets a response
+        if id == Json::Null {
+            return self.notification(method, params);
+        }
+        if self.shut_down {
+            return vec![error(id, INVALID_REQUEST, "the server has shut down")];
+        }
+        let result = match method {
+            "initialize" => Ok(capabilities()),
+            "shutdown" => {
+                self.shut_down = true;
+                Ok(Json::Null)
+            }
+            "textDocument/definition" => self.definition(params),
+            "textDocument/references" => self.references(params),
+            "textDocument/hover" => self.hover(params),
+            "textDocument/completion" => self.completion(params),
+            _ => Err((METHOD_NOT_FOUND, format!("unknown method {}", method))),
+        };
+        match result {
+            Ok(result) => vec![Json::object(vec![
+                ("jsonrpc", "2.0".into()),
+                ("id", id),
+                ("result", result),
+            ])],
+            Err((code, message)) => vec![error(id, code, &message)],
+        }
+    }
+
+    fn notification(&mut self, method: &str, params: &Json) -> Vec<Json> {
+        let uri = params.get("textDocument").get("uri").as_str();
+        match (method, uri) {
+            ("exit", _) => {
+                self.exit = Some(if self.shut_down { 0 } else { 1 });
+                Vec::new()
+            }
+            ("textDocument/didOpen", Some(uri)) => {
+                let text = params.get("textDocument").get("text").as_str();
+                self.update(uri, text.unwrap_or(""))
+            }
+            // the whole text each time, which is what initialize asked for
+            ("textDocument/didChange", Some(uri)) => {
+                let changes = params.get("contentChanges").as_array().unwrap_or(&[]);
+                match changes.last().and_then(|x| x.get("text").as_str()) {
+                    Some(text) => self.update(uri, text),
+                    None => Vec::new(),
+                }
+            }
+            // a file it includes may have changed too
+            ("textDocument/didSave", Some(uri)) => match self.documents.get(uri) {
+                Some(text) => vec![diagnostics(uri, text)],
+                None => Vec::new(),
+            },
+            ("textDocument/didClose", Some(uri)) => {
+                self.documents.remove(uri);
+                vec![publish(uri, Vec::new())]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn update(&mut self, uri: &str, text: &str) -> Vec<Json> {
+        self.documents.insert(uri.to_string(), text.to_string());
+        vec![diagnostics(uri, text)]
+    }
+
+    // the text of the file a request is about and the position in it, as a line from 1 and a
+    // column in characters like SymbolUse
+    fn document<'a>(&'a self, params: &'a Json) -> Result<(&'a str, &'a str, Position), Failure> {
+        let uri = params.get("textDocument").get("uri").as_str();
+        let position = params.get("position");
+        let line = position.get("line").as_i64();
+        let character = position.get("character").as_i64();
+        let (uri, line, character) = match (uri, line, character) {
+            (Some(uri), Some(line), Some(character)) if line >= 0 && character >= 0 => {
+                (uri, line as usize, character as usize)
+            }
+            _ => {
+                return Err((
+                    INVALID_PARAMS,
+                    "expected a document and position".to_string(),
+                ))
+            }
+        };
+        let text = match self.documents.get(uri) {
+            Some(x) => x.as_str(),
+            None => return Err((INVALID_PARAMS, format!("{} isn't open", uri))),
+        };
+        let line_text = text.lines().nth(line).unwrap_or("");
+        let position = Position {
+            line: line + 1,
+            column: char_column(line_text, character),
+        };
+        Ok((uri, text, position))
+    }
+
+    fn definition(&self, params: &Json) -> Result<Json, Failure> {
+        let (uri, text, position) = self.document(params)?;
+        let uses = assembler::symbol_uses(text);
+        let definition = symbol_at(&uses, &position)
+            .and_then(|x| assembler::definition_of(&uses, x))
+            .map_or(Json::Null, |x| location(uri, text, x));
+        Ok(definition)
+    }
+
+    fn references(&self, params: &Json) -> Result<Json, Failure> {
+        let (uri, text, position) = self.document(params)?;
+        let declaration = params.get("context").get("includeDeclaration").as_bool();
+        let uses = assembler::symbol_uses(text);
+        let definition = match symbol_at(&uses, &position) {
+            Some(x) => assembler::definition_of(&uses, x),
+            None => return Ok(Json::Null),
+        };
+        let definition = match definition {
+            Some(x) => x,
+            None => return Ok(Json::Array(Vec::new())),
+        };
+        let references = uses
+            .iter()
+            .filter(|x| declaration != Some(false) || x.kind != SymbolKind::DEFINITION)
+            .filter(|x| assembler::definition_of(&uses, x) == Some(definition))
+            .map(|x| location(uri, text, x))
+            .collect();
+        Ok(Json::Array(references))
+    }
+
+    fn hover(&self, params: &Json) -> Result<Json, Failure> {
+        let (_, text, position) = self.document(params)?;
+        let uses = assembler::symbol_uses(text);
+        let symbol = match symbol_at(&uses, &position) {
+            Some(x) => x,
+            None => return Ok(Json::Null),
+        };
+        let description = match symbol.kind {
+            SymbolKind::OP => describe_op(&symbol.name),
+            _ => None,
+        };
+        // a macro or symbol is shown by the line that defines it
+        let description = description.or_else(|| {
+            let definition = assembler::definition_of(&uses, symbol)?;
+            let line = text.lines().nth(definition.line - 1)?;
+            Some(format!(
+                "```mixal\n{}\n```\nline {}",
+                line.trim_end(),
+                definition.line
+            ))
+        });
+        let description = match description {
+            Some(x) => x,
+            None => return Ok(Json::Null),
+        };
+        let line = text.lines().nth(symbol.line - 1).unwrap_or("");
+        Ok(Json::object(vec![
+            (
+                "contents",
+                Json::object(vec![
+                    ("kind", "markdown".into()),
+                    ("value", description.into()),
+                ]),
+            ),
+            ("range", range(line, symbol)),
+        ]))
+    }
+
+    fn completion(&self, params: &Json) -> Result<Json, Failure> {
+        let (_, text, position) = self.document(params)?;
+        let line = text.lines().nth(position.line - 1).unwrap_or("");
+        let uses = assembler::symbol_uses(text);
+        let mut items = Vec::new();
+        match assembler::field_at(line, position.column) {
+            SourceField::OP => {
+                for opcode in OPCODES.iter() {
+                    let detail = describe_opcode(opcode.op);
+                    items.push(completion_item(opcode.mnemonic, KEYWORD, &detail));
+                }
+                for name in PSEUDO_OPS.iter().chain(DIRECTIVES.iter()) {
+                    let detail = describe_pseudo_op(name).unwrap_or("");
+                    items.push(completion_item(name, KEYWORD, detail));
+                }
+                for symbol in definitions(&uses, |x| x == "MACRO") {
+                    items.push(completion_item(&symbol.name, FUNCTION, "macro"));
+                }
+            }
+            // each symbol with the line defining it
+            SourceField::ADDRESS => {
+                let symbols = definitions(&uses, |x| x != "MACRO");
+                for symbol in symbols.filter(|x| !assembler::is_local_symbol(&x.name)) {
+                    let line = text.lines().nth(symbol.line - 1).unwrap_or("");
+                    items.push(completion_item(&symbol.name, VARIABLE, line.trim()));
+                }
+            }
+            _ => (),
+        }
+        Ok(Json::Array(items))
+    }
+}
+
+// an error code and message to send back for a request
+type Failure = (i64, String);
+
+struct Position {
+    line: usize,
+    column: usize,
+}
+
+fn error(id: Json, code: i64, message: &str) -> Json {
+    Json::object(vec![
+        ("jsonrpc", "2.0".into()),
+        ("id", id),
+        (
+            "error",
+            Json::object(vec![("code", code.into()), ("message", message.into())]),
+        ),
+    ])
+}
+
+fn capabilities() -> Json {
+    Json::object(vec![
+        (
+            "capabilities",
+            Json::object(vec![
+                // the whole file is sent on every change
+                ("textDocumentSync", Json::from(1i64)),
+                ("definitionProvider", true.into()),
+                ("referencesProvider", true.into()),
+                ("hoverProvider", true.into()),
+                ("completionProvider", Json::object(Vec::new())),
+            ]),
+        ),
+        (
+            "serverInfo",
+            Json::object(vec![
+                ("name", "mixal-lsp".into()),
+                ("version", env!("CARGO_PKG_VERSION").into()),
+            ]),
+        ),
+    ])
+}
+
+fn publish(uri: &str, diagnostics: Vec<Json>) -> Json {
+    Json::object(vec![
+        ("jsonrpc", "2.0".into()),
+        ("method", "textDocument/publishDiagnostics".into()),
+        (
+            "params",
+            Json::object(vec![
+                ("uri", uri.into()),
+                ("diagnostics", diagnostics.into()),
+            ]),
+        ),
+    ])
+}
+
+// The assembler stops at the first error, so there's at most one, on the line it's in or on the
+// line that included the file it's in. The file name lets INCLUDE find files next to it.
+fn diagnostics(uri: &str, text: &str) -> Json {
+    let options = AssemblerOptions {
+        file_name: file_path(uri),
+        ..Default::default()
+    };
+    let error = match assembler::assemble_with(text, &options) {
+        Ok(_) => return publish(uri, Vec::new()),
+        Err(e) => e,
+    };
+    let (number, message) = match error {
+        AssemblerError::InLine(number, e) => (number, e.to_string()),
+        e => (1, e.to_string()),
+    };
+    let line = text.lines().nth(number.saturating_sub(1)).unwrap_or("");
+    let whole_line = Json::object(vec![
+        ("start", position(number, 0)),
+        (
+            "end",
+            position(number, utf16_column(line, line.chars().count())),
+        ),
+    ]);
+    let diagnostic = Json::object(vec![
+        ("range", whole_line),
+        ("severity", ERROR.into()),
+        ("source", "mixal".into()),
+        ("message", message.into()),
+    ]);
+    publish(uri, vec![diagnostic])
+}
+
+// file:///home/a%20b/x.mixal is /home/a b/x.mixal
+fn file_path(uri: &str) -> Option<String> {
+    let path = uri.strip_prefix("file://")?;
+    let mut bytes = Vec::new();
+    let mut iter = path.bytes();
+    while let Some(x) = iter.next() {
+        match x {
+            b'%' => {
+                let digits = [iter.next()?, iter.next()?];
+                let digits = std::str::from_utf8(&digits).ok()?;
+                bytes.push(u8::from_str_radix(digits, 16).ok()?);
+            }
+            x => bytes.push(x),
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+// the symbol the cursor is on, or just after
+fn symbol_at<'a>(uses: &'a [SymbolUse], position: &Position) -> Option<&'a SymbolUse> {
+    uses.iter().find(|x| {
+        let end = x.column + x.name.chars().count();
+        x.line == position.line && (x.column..=end).contains(&position.column)
+    })
+}
+
+// symbols defined by the LOC field of lines whose op passes the test
+fn definitions<'a>(
+    uses: &'a [SymbolUse],
+    op: impl Fn(&str) -> bool + 'a,
+) -> impl Iterator<Item = &'a SymbolUse> + 'a {
+    let ops: HashMap<usize, &str> = uses
+        .iter()
+        .filter(|x| x.kind == SymbolKind::OP)
+        .map(|x| (x.line, x.name.as_str()))
+        .collect();
+    uses.iter().filter(move |x| {
+        x.kind == SymbolKind::DEFINITION && ops.get(&x.line).is_some_and(|x| op(x))
+    })
+}
+
+fn completion_item(label: &str, kind: i64, detail: &str) -> Json {
+    let mut fields = vec![("label", label.into()), ("kind", kind.into())];
+    if !detail.is_empty() {
+        fields.push(("detail", detail.into()));
+    }
+    Json::object(fields)
+}
+
+// LDA: C = 8, F = (0:5), 2u
+fn describe_opcode(op: Op) -> String {
+    let opcode = op.opcode();
+    let mut description = format!(
+        "C = {}, F = {}, {}u",
+        opcode.code,
+        opcode.field_text(),
+        opcode.time_text()
+    );
+    if op.is_binary_only() {
+        description += ", binary MIX only";
+    }
+    description
+}
+
+fn describe_op(name: &str) -> Option<String> {
+    match Op::from_mnemonic(name) {
+        Some(op) => Some(format!("**{}** {}", name, describe_opcode(op))),
+        None => describe_pseudo_op(name).map(|x| format!("**{}** {}", name, x)),
+    }
+}
+
+fn describe_pseudo_op(name: &str) -> Option<&'static str> {
+    let description = match name {
+        "EQU" => "gives the LOC the value of the address",
+        "ORIG" => "sets the location of the next word",
+        "CON" => "a word with the value of the address",
+        "ALF" => "a word holding the five characters in quotes",
+        "END" => "ends the program, which starts at the address",
+        "EXTERN" => "symbols defined in another module",
+        "EXPORT" => "symbols other modules can use",
+        "MACRO" => "defines a macro named by the LOC, taking the parameters in the address",
+        "ENDM" => "ends a macro",
+        "INCLUDE" => "reads the file in quotes in place of the line",
+        "IF" => "keeps the lines up to ELSE or ENDIF if the address isn't zero",
+        "IFDEF" => "keeps the lines up to ELSE or ENDIF if the symbol is defined",
+        "IFNDEF" => "keeps the lines up to ELSE or ENDIF if the symbol isn't defined",
+        "ELSE" => "keeps the lines up to ENDIF if the IF left out the ones before",
+        "ENDIF" => "ends an IF",
+        _ => return None,
+    };
+    Some(description)
+}
+
+fn position(line: usize, character: usize) -> Json {
+    Json::object(vec![
+        ("line", (line - 1).into()),
+        ("character", character.into()),
+    ])
+}
+
+fn range(line: &str, symbol: &SymbolUse) -> Json {
+    let start = utf16_column(line, symbol.column);
+    let end = utf16_column(line, symbol.column + symbol.name.chars().count());
+    Json::object(vec![
+        ("start", position(symbol.line, start)),
+        ("end", position(symbol.line, end)),
+    ])
+}
+
+fn location(uri: &str, text: &str, symbol: &SymbolUse) -> Json {
+    let line = text.lines().nth(symbol.line - 1).unwrap_or("");
+    Json::object(vec![("uri", uri.into()), ("range", range(line, symbol))])
+}
+
+// Positions in the protocol count UTF-16 code units and ours count characters, which are the
+// same for anything MIX can print.
+fn utf16_column(line: &str, column: usize) -> usize {
+    line.chars().take(column).map(char::len_utf16).sum()
+}
+
+fn char_column(line: &str, character: usize) -> usize {
+    let mut units = 0;
+    for (idx, c) in line.chars().enumerate() {
+        if units >= character {
+            return idx;
+        }
+        units += c.len_utf16();
+    }
+    line.chars().count()
+}
+
+// A message is a Content-Length header, any others, a blank line and then that many bytes of
+// JSON. None at the end of the input.
+pub fn read_message(input: &mut impl BufRead) -> io::Result<Option<String>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                length = value.trim().parse().ok();
+            }
+        }
+    }
+    let length = length.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            "message without a Content-Length",
+        )
+    })?;
+    if length > MAX_MESSAGE_LENGTH {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("message of {} bytes is too long", length),
+        ));
+    }
+    let mut body = vec![0; length];
+    input.read_exact(&mut body)?;
+    String::from_utf8(body)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+pub fn write_message(output: &mut impl Write, message: &Json) -> io::Result<()> {
+    let body = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    output.flush()
+}
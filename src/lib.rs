@@ -1,3 +1,13 @@
 pub mod assembler;
 pub mod computer;
 pub mod data_types;
+pub mod disassembler;
+#[doc(hidden)]
+pub mod fuzzing;
+pub mod grader;
+pub mod language_server;
+pub mod linker;
+pub mod object;
+pub mod opcodes;
+pub mod source_map;
+pub mod testing;
@@ -0,0 +1,210 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+use crate::{
+    computer::memory,
+    data_types::{ByteSize, FieldSpecification, Word},
+    object::{Linkage, Program},
+    source_map::SourceMap,
+};
+
+// Modules are named in errors by the first file they were assembled from.
+#[derive(Debug, PartialEq, Eq)]
+pub enum LinkError {
+    NotAModule(String),
+    MixedByteSizes(String),
+    DuplicateExport(String),
+    Undefined(String, String),
+    MultipleStarts(String),
+    OutsideMemory(String, i32),
+    Overlap(String, i32),
+    // a relocation or external for an address the module has no word at
+    BadRelocation(String, i32),
+    // an address the linker moved no longer fits in its field
+    FieldOverflow(String, i32),
+}
+
+impl fmt::Display for LinkError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NotAModule(x) => write!(f, "{} is not a module, assemble it with --module", x),
+            Self::MixedByteSizes(x) => {
+                write!(f, "{} is for a different byte size to the others", x)
+            }
+            Self::DuplicateExport(x) => write!(f, "{} is exported by more than one module", x),
+            Self::Undefined(module, x) => {
+                write!(f, "{}: {} isn't exported by any module", module, x)
+            }
+            Self::MultipleStarts(x) => {
+                write!(f, "{} has an END address but so does an earlier module", x)
+            }
+            Self::OutsideMemory(module, x) => {
+                write!(f, "{}: address {} is outside memory", module, x)
+            }
+            Self::Overlap(module, x) => {
+                write!(
+                    f,
+                    "{}: address {} is already used by another module",
+                    module, x
+                )
+            }
+            Self::BadRelocation(module, x) => {
+                write!(f, "{}: there's no word at {} to relocate", module, x)
+            }
+            Self::FieldOverflow(module, x) => {
+                write!(f, "{}: the address linked into {} doesn't fit", module, x)
+            }
+        }
+    }
+}
+
+struct Placed<'a> {
+    program: &'a Program,
+    linkage: &'a Linkage,
+    name: String,
+    origin: i32,
+}
+
+// Combines modules into one program that can be loaded. Each goes at its origin, or straight after
+// the one before it when it doesn't have one (the first then goes at 0), and every address in it
+// is moved there. Only exported symbols end up in the program's symbols, although the source maps
+// are kept, so faults still say which file and line they happened on.
+pub fn link(modules: &[(&Program, Option<i32>)]) -> Result<Program, LinkError> {
+    let mut placed = Vec::new();
+    let mut next = 0;
+    for (idx, (program, origin)) in modules.iter().enumerate() {
+        let name = match program.source_map.files.first() {
+            Some(x) => x.clone(),
+            None => format!("module {}", idx + 1),
+        };
+        let linkage = match &program.linkage {
+            Some(x) => x,
+            None => return Err(LinkError::NotAModule(name)),
+        };
+        if program.byte_size != modules[0].0.byte_size {
+            return Err(LinkError::MixedByteSizes(name));
+        }
+        let origin = origin.unwrap_or(next);
+        let size = program.words.iter().map(|(x, _)| x + 1).max().unwrap_or(0);
+        next = origin + size;
+        placed.push(Placed {
+            program,
+            linkage,
+            name,
+            origin,
+        });
+    }
+
+    let mut exports = HashMap::new();
+    let mut symbols = Vec::new();
+    for module in placed.iter() {
+        for (name, value, relative) in module.linkage.exports.iter() {
+            let value = match relative {
+                true => value + module.origin,
+                false => *value,
+            };
+            if exports.insert(name.as_str(), value).is_some() {
+                return Err(LinkError::DuplicateExport(name.clone()));
+            }
+            symbols.push((name.clone(), value));
+        }
+    }
+
+    let byte_size = modules.first().map(|x| x.0.byte_size).unwrap_or_default();
+    let mut program = Program {
+        byte_size,
+        symbols,
+        ..Default::default()
+    };
+    let mut used = HashSet::new();
+    for module in placed.iter() {
+        let mut words: HashMap<i32, Word> = HashMap::new();
+        for (address, word) in module.program.words.iter() {
+            words.insert(*address, *word);
+        }
+        for (address, field) in module.linkage.relocations.iter() {
+            relocate(
+                &mut words,
+                *address,
+                *field,
+                module.origin.into(),
+                module,
+                byte_size,
+            )?;
+        }
+        for (address, field, name) in module.linkage.externals.iter() {
+            let value = match exports.get(name.as_str()) {
+                Some(x) => *x,
+                None => return Err(LinkError::Undefined(module.name.clone(), name.clone())),
+            };
+            relocate(
+                &mut words,
+                *address,
+                *field,
+                value.into(),
+                module,
+                byte_size,
+            )?;
+        }
+        for (address, _) in module.program.words.iter() {
+            let linked = address + module.origin;
+            if !(0..memory::SIZE as i32).contains(&linked) {
+                return Err(LinkError::OutsideMemory(module.name.clone(), linked));
+            }
+            if !used.insert(linked) {
+                return Err(LinkError::Overlap(module.name.clone(), linked));
+            }
+            program.words.push((linked, words[address]));
+        }
+        if let Some(start) = module.program.start {
+            if program.start.is_some() {
+                return Err(LinkError::MultipleStarts(module.name.clone()));
+            }
+            program.start = Some(start + module.origin);
+        }
+        add_source_map(
+            &mut program.source_map,
+            &module.program.source_map,
+            module.origin,
+        );
+    }
+    Ok(program)
+}
+
+// adds amount to the value in a field of the word at address
+fn relocate(
+    words: &mut HashMap<i32, Word>,
+    address: i32,
+    field: FieldSpecification,
+    amount: i64,
+    module: &Placed,
+    byte_size: ByteSize,
+) -> Result<(), LinkError> {
+    let word = match words.get_mut(&address) {
+        Some(x) => x,
+        None => return Err(LinkError::BadRelocation(module.name.clone(), address)),
+    };
+    let value = word.get_field(field).to_i64_in(byte_size) + amount;
+    let bytes = (field.right - field.left.max(1) + 1) as u32;
+    if value.abs() >= byte_size.capacity(bytes) || (field.left > 0 && value < 0) {
+        return Err(LinkError::FieldOverflow(
+            module.name.clone(),
+            address + module.origin,
+        ));
+    }
+    let value = Word::from_i64_in(value, byte_size).unwrap();
+    *word = word.with_field(field, value);
+    Ok(())
+}
+
+fn add_source_map(linked: &mut SourceMap, module: &SourceMap, origin: i32) {
+    let files: Vec<usize> = module.files.iter().map(|x| linked.file(x)).collect();
+    for (address, file, line) in module.lines.iter() {
+        linked.lines.push((address + origin, files[*file], *line));
+    }
+    for (name, address) in module.labels.iter() {
+        linked.labels.push((name.clone(), address + origin));
+    }
+}
@@ -0,0 +1,223 @@
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::Path,
+};
+
+use crate::{
+    data_types::{ByteSize, FieldSpecification, Word},
+    source_map::SourceMap,
+};
+
+// Object files hold an assembled program ready to be loaded into memory. Like snapshots they are
+// plain text with one record per line:
+//
+//   MIX-OBJECT 1
+//   BYTES 100               (the byte size it was assembled for, left out for binary)
+//   START 3000              (from END, left out when the source had no END)
+//   SYMBOL PRIME 3000       (one per symbol defined in the source)
+//   WORD 3000 + 0 1 2 3 4   (address then sign and byte values of the word stored there)
+//   FILE primes.mixal       (a source file, the LINE records after it are for lines in it)
+//   LINE 3000 12            (address then the source line its word was assembled from)
+//   LABEL PRIME 3000        (one per symbol that names a location rather than a number)
+//
+// Modules, which need linking before they can be loaded, have a few more:
+//
+//   MODULE                  (addresses in the file are counted from wherever the module goes)
+//   EXPORT PRINT 12 RELATIVE    (a symbol other modules can use, RELATIVE if it's an address in
+//   EXPORT SIZE 100 ABSOLUTE     the module that moves with it, ABSOLUTE if it's just a number)
+//   RELOCATE 12 2           (a field, here (0:2) of the word at 12, holding an address in the module)
+//   EXTERNAL 14 2 PRINT     (a field holding an address relative to a symbol another module exports)
+//
+// The version number goes up whenever the meaning of an existing record changes.
+pub const OBJECT_VERSION: i32 = 1;
+
+const HEADER: &str = "MIX-OBJECT";
+
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct Program {
+    pub words: Vec<(i32, Word)>,
+    pub start: Option<i32>,
+    pub symbols: Vec<(String, i32)>,
+    // the words only mean the same thing on a machine with this byte size
+    pub byte_size: ByteSize,
+    pub source_map: SourceMap,
+    // what the linker needs, for a module
+    pub linkage: Option<Linkage>,
+}
+
+// A module is assembled as if it started at 0, so it can be put anywhere by the linker, which adds
+// where it went to every field holding an address in it.
+#[derive(Debug, PartialEq, Eq, Default, Clone)]
+pub struct Linkage {
+    // name, value and whether the value is an address in the module rather than just a number
+    pub exports: Vec<(String, i32, bool)>,
+    // the address of each word with a field holding an address in the module, and the field
+    pub relocations: Vec<(i32, FieldSpecification)>,
+    // the same for fields holding an address relative to a symbol another module exports
+    pub externals: Vec<(i32, FieldSpecification, String)>,
+}
+
+#[derive(Debug)]
+pub enum ObjectError {
+    Io(io::Error),
+    BadHeader(String),
+    UnsupportedVersion(i32),
+    BadLine(usize, String),
+}
+
+impl From<io::Error> for ObjectError {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl Program {
+    pub fn symbol(&self, name: &str) -> Option<i32> {
+        self.symbols
+            .iter()
+            .find(|(symbol, _)| symbol == name)
+            .map(|(_, value)| *value)
+    }
+
+    pub fn write_object(&self, writer: &mut impl Write) -> io::Result<()> {
+        writeln!(writer, "{} {}", HEADER, OBJECT_VERSION)?;
+        if self.byte_size != ByteSize::BINARY {
+            writeln!(writer, "BYTES {}", self.byte_size.values())?;
+        }
+        if let Some(start) = self.start {
+            writeln!(writer, "START {}", start)?;
+        }
+        for (name, value) in self.symbols.iter() {
+            writeln!(writer, "SYMBOL {} {}", name, value)?;
+        }
+        for (address, word) in self.words.iter() {
+            writeln!(writer, "WORD {} {}", address, word.to_byte_string())?;
+        }
+        let map = &self.source_map;
+        for (idx, file) in map.files.iter().enumerate() {
+            writeln!(writer, "FILE {}", file)?;
+            for (address, _, line) in map.lines.iter().filter(|(_, x, _)| *x == idx) {
+                writeln!(writer, "LINE {} {}", address, line)?;
+            }
+        }
+        for (name, address) in map.labels.iter() {
+            writeln!(writer, "LABEL {} {}", name, address)?;
+        }
+        if let Some(linkage) = &self.linkage {
+            writeln!(writer, "MODULE")?;
+            for (name, value, relative) in linkage.exports.iter() {
+                let kind = if *relative { "RELATIVE" } else { "ABSOLUTE" };
+                writeln!(writer, "EXPORT {} {} {}", name, value, kind)?;
+            }
+            for (address, field) in linkage.relocations.iter() {
+                writeln!(writer, "RELOCATE {} {}", address, field.value())?;
+            }
+            for (address, field, name) in linkage.externals.iter() {
+                writeln!(writer, "EXTERNAL {} {} {}", address, field.value(), name)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn write_object_to_file(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_object(&mut writer)?;
+        writer.flush()
+    }
+
+    pub fn read_object(reader: impl BufRead) -> Result<Program, ObjectError> {
+        let mut lines = reader.lines();
+        let header = lines.next().transpose()?.unwrap_or_default();
+        match header.split_once(' ') {
+            Some((HEADER, version)) => match version.trim().parse::<i32>() {
+                Ok(OBJECT_VERSION) => (),
+                Ok(x) => return Err(ObjectError::UnsupportedVersion(x)),
+                Err(_) => return Err(ObjectError::BadHeader(header)),
+            },
+            _ => return Err(ObjectError::BadHeader(header)),
+        }
+
+        let mut program = Program::default();
+        for (idx, line) in lines.enumerate() {
+            let line = line?;
+            // the header was line 1
+            let line_number = idx + 2;
+            read_record(&mut program, &line)
+                .ok_or_else(|| ObjectError::BadLine(line_number, line.clone()))?;
+        }
+        Ok(program)
+    }
+
+    pub fn read_object_from_file(path: impl AsRef<Path>) -> Result<Program, ObjectError> {
+        Self::read_object(BufReader::new(File::open(path)?))
+    }
+}
+
+fn read_field(value: &str) -> Option<FieldSpecification> {
+    let value: i32 = value.parse().ok()?;
+    let field = FieldSpecification {
+        left: value / 8,
+        right: value % 8,
+    };
+    field.is_valid().then_some(field)
+}
+
+fn read_record(program: &mut Program, line: &str) -> Option<()> {
+    let mut parts = line.split_whitespace();
+    let key = match parts.next() {
+        Some(x) => x,
+        None => return Some(()),
+    };
+    let values: Vec<&str> = parts.collect();
+    match (key, values.as_slice()) {
+        ("BYTES", [size]) => program.byte_size = ByteSize::new(size.parse().ok()?)?,
+        ("START", [start]) => program.start = Some(start.parse().ok()?),
+        ("SYMBOL", [name, value]) => program
+            .symbols
+            .push((name.to_string(), value.parse().ok()?)),
+        ("WORD", [address, word @ ..]) => {
+            let word = Word::from_byte_string_in(&word.join(" "), program.byte_size)?;
+            program.words.push((address.parse().ok()?, word));
+        }
+        // names can have spaces in, so the name is the rest of the line
+        ("FILE", [_, ..]) => program
+            .source_map
+            .files
+            .push(line.trim()["FILE".len()..].trim().to_string()),
+        ("LINE", [address, line]) => {
+            let map = &mut program.source_map;
+            let file = map.files.len().checked_sub(1)?;
+            map.lines
+                .push((address.parse().ok()?, file, line.parse().ok()?));
+        }
+        ("LABEL", [name, address]) => program
+            .source_map
+            .labels
+            .push((name.to_string(), address.parse().ok()?)),
+        ("MODULE", []) => program.linkage = Some(Linkage::default()),
+        ("EXPORT", [name, value, kind]) => {
+            let relative = match *kind {
+                "RELATIVE" => true,
+                "ABSOLUTE" => false,
+                _ => return None,
+            };
+            let linkage = program.linkage.as_mut()?;
+            let value = value.parse().ok()?;
+            linkage.exports.push((name.to_string(), value, relative));
+        }
+        ("RELOCATE", [address, field]) => {
+            let field = read_field(field)?;
+            let linkage = program.linkage.as_mut()?;
+            linkage.relocations.push((address.parse().ok()?, field));
+        }
+        ("EXTERNAL", [address, field, name]) => {
+            let field = read_field(field)?;
+            let linkage = program.linkage.as_mut()?;
+            let address = address.parse().ok()?;
+            linkage.externals.push((address, field, name.to_string()));
+        }
+        _ => return None,
+    }
+    Some(())
+}
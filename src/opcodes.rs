@@ -0,0 +1,481 @@
+use std::sync::OnceLock;
+
+use crate::data_types::Word;
+
+// The one place that knows how MIX op codes are laid out. The emulator decodes instructions with
+// this table, the assembler looks mnemonics up in it and the disassembler and OPCODES.md are
+// generated from it, so they can't drift apart.
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Op {
+    NOP,
+    ADD,
+    FADD,
+    SUB,
+    FSUB,
+    MUL,
+    FMUL,
+    DIV,
+    FDIV,
+    NUM,
+    CHAR,
+    HLT,
+    FLOT,
+    FIX,
+    INT,
+    SLA,
+    SRA,
+    SLAX,
+    SRAX,
+    SLC,
+    SRC,
+    SLB,
+    SRB,
+    MOVE,
+    LDA,
+    LD1,
+    LD2,
+    LD3,
+    LD4,
+    LD5,
+    LD6,
+    LDX,
+    LDAN,
+    LD1N,
+    LD2N,
+    LD3N,
+    LD4N,
+    LD5N,
+    LD6N,
+    LDXN,
+    STA,
+    ST1,
+    ST2,
+    ST3,
+    ST4,
+    ST5,
+    ST6,
+    STX,
+    STJ,
+    STZ,
+    JBUS,
+    IOC,
+    IN,
+    OUT,
+    JRED,
+    JMP,
+    JSJ,
+    JOV,
+    JNOV,
+    JL,
+    JE,
+    JG,
+    JGE,
+    JNE,
+    JLE,
+    JAN,
+    JAZ,
+    JAP,
+    JANN,
+    JANZ,
+    JANP,
+    JAE,
+    JAO,
+    J1N,
+    J1Z,
+    J1P,
+    J1NN,
+    J1NZ,
+    J1NP,
+    J2N,
+    J2Z,
+    J2P,
+    J2NN,
+    J2NZ,
+    J2NP,
+    J3N,
+    J3Z,
+    J3P,
+    J3NN,
+    J3NZ,
+    J3NP,
+    J4N,
+    J4Z,
+    J4P,
+    J4NN,
+    J4NZ,
+    J4NP,
+    J5N,
+    J5Z,
+    J5P,
+    J5NN,
+    J5NZ,
+    J5NP,
+    J6N,
+    J6Z,
+    J6P,
+    J6NN,
+    J6NZ,
+    J6NP,
+    JXN,
+    JXZ,
+    JXP,
+    JXNN,
+    JXNZ,
+    JXNP,
+    JXE,
+    JXO,
+    INCA,
+    DECA,
+    ENTA,
+    ENNA,
+    INC1,
+    DEC1,
+    ENT1,
+    ENN1,
+    INC2,
+    DEC2,
+    ENT2,
+    ENN2,
+    INC3,
+    DEC3,
+    ENT3,
+    ENN3,
+    INC4,
+    DEC4,
+    ENT4,
+    ENN4,
+    INC5,
+    DEC5,
+    ENT5,
+    ENN5,
+    INC6,
+    DEC6,
+    ENT6,
+    ENN6,
+    INCX,
+    DECX,
+    ENTX,
+    ENNX,
+    CMPA,
+    FCMP,
+    CMP1,
+    CMP2,
+    CMP3,
+    CMP4,
+    CMP5,
+    CMP6,
+    CMPX,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum OperandKind {
+    // F is a field specification (L:R), the default is given in the table
+    FIELD,
+    // F is part of the op code and selects the operation
+    FIXED,
+    // F is an I/O unit number
+    UNIT,
+    // F is a number of words (MOVE)
+    COUNT,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct OpCode {
+    pub op: Op,
+    pub mnemonic: &'static str,
+    pub code: i32,
+    // for FIXED operands this is the only valid F, otherwise it's the default used by the assembler
+    pub field: i32,
+    // execution time in units of u, MOVE takes an extra 2u per word and I/O may wait on the device
+    pub time: i32,
+    pub operand: OperandKind,
+}
+
+impl OpCode {
+    const fn new(
+        op: Op,
+        mnemonic: &'static str,
+        code: i32,
+        field: i32,
+        time: i32,
+        operand: OperandKind,
+    ) -> Self {
+        Self {
+            op,
+            mnemonic,
+            code,
+            field,
+            time,
+            operand,
+        }
+    }
+
+    pub fn matches(&self, code: i32, field: i32) -> bool {
+        self.code == code && (self.operand != OperandKind::FIXED || self.field == field)
+    }
+
+    // F as it's written in MIXAL, (0:5) for a field
+    pub fn field_text(&self) -> String {
+        match self.operand {
+            OperandKind::FIELD => format!("({}:{})", self.field / 8, self.field % 8),
+            _ => self.field.to_string(),
+        }
+    }
+
+    // the time in u, with F the number of words moved and T the time waiting on the device
+    pub fn time_text(&self) -> String {
+        match self.op {
+            Op::MOVE => "1+2F".to_string(),
+            Op::IOC | Op::IN | Op::OUT => format!("{}+T", self.time),
+            _ => self.time.to_string(),
+        }
+    }
+
+    pub fn operand_text(&self) -> &'static str {
+        match self.operand {
+            OperandKind::FIELD => "field",
+            OperandKind::FIXED => "fixed",
+            OperandKind::UNIT => "unit",
+            OperandKind::COUNT => "count",
+        }
+    }
+}
+
+impl Op {
+    pub fn opcode(&self) -> &'static OpCode {
+        // the table is declared in the same order as the enum
+        &OPCODES[*self as usize]
+    }
+
+    // the binary shifts and the even and odd jumps, which a decimal machine doesn't have
+    pub fn is_binary_only(&self) -> bool {
+        matches!(
+            self,
+            Op::SLB | Op::SRB | Op::JAE | Op::JAO | Op::JXE | Op::JXO
+        )
+    }
+
+    pub fn from_mnemonic(mnemonic: &str) -> Option<Self> {
+        OPCODES
+            .iter()
+            .find(|opcode| opcode.mnemonic == mnemonic)
+            .map(|opcode| opcode.op)
+    }
+}
+
+pub fn decode(instruction: Word) -> Option<&'static OpCode> {
+    static TABLE: OnceLock<Vec<Option<&'static OpCode>>> = OnceLock::new();
+    let table = TABLE.get_or_init(build_decode_table);
+    let index: usize = (instruction.code() * DECODE_STRIDE + instruction.field().value())
+        .try_into()
+        .ok()?;
+    *table.get(index)?
+}
+
+// room for every C and F a byte can hold on any machine, so the two never run into each other
+const DECODE_STRIDE: i32 = 100;
+
+fn build_decode_table() -> Vec<Option<&'static OpCode>> {
+    let mut table = vec![None; (DECODE_STRIDE * DECODE_STRIDE) as usize];
+    for code in 0..DECODE_STRIDE {
+        for field in 0..DECODE_STRIDE {
+            // an exact FIXED match wins over an instruction that takes any F for the same C
+            let exact = OPCODES
+                .iter()
+                .find(|x| x.operand == OperandKind::FIXED && x.matches(code, field));
+            let any = OPCODES
+                .iter()
+                .find(|x| x.operand != OperandKind::FIXED && x.matches(code, field));
+            table[(code * DECODE_STRIDE + field) as usize] = exact.or(any);
+        }
+    }
+    table
+}
+
+pub fn markdown_table() -> String {
+    let mut result = String::from("| Mnemonic | C | F | Time | Operand |\n|---|---|---|---|---|\n");
+    for opcode in OPCODES.iter() {
+        result.push_str(&format!(
+            "| {} | {} | {} | {} | {} |\n",
+            opcode.mnemonic,
+            opcode.code,
+            opcode.field_text(),
+            opcode.time_text(),
+            opcode.operand_text()
+        ));
+    }
+    result
+}
+
+pub const OPCODES: [OpCode; 158] = [
+    OpCode::new(Op::NOP, "NOP", 0, 0, 1, OperandKind::FIELD),
+    OpCode::new(Op::ADD, "ADD", 1, 5, 2, OperandKind::FIELD),
+    OpCode::new(Op::FADD, "FADD", 1, 6, 4, OperandKind::FIXED),
+    OpCode::new(Op::SUB, "SUB", 2, 5, 2, OperandKind::FIELD),
+    OpCode::new(Op::FSUB, "FSUB", 2, 6, 4, OperandKind::FIXED),
+    OpCode::new(Op::MUL, "MUL", 3, 5, 10, OperandKind::FIELD),
+    OpCode::new(Op::FMUL, "FMUL", 3, 6, 9, OperandKind::FIXED),
+    OpCode::new(Op::DIV, "DIV", 4, 5, 12, OperandKind::FIELD),
+    OpCode::new(Op::FDIV, "FDIV", 4, 6, 11, OperandKind::FIXED),
+    OpCode::new(Op::NUM, "NUM", 5, 0, 10, OperandKind::FIXED),
+    OpCode::new(Op::CHAR, "CHAR", 5, 1, 10, OperandKind::FIXED),
+    OpCode::new(Op::HLT, "HLT", 5, 2, 10, OperandKind::FIXED),
+    OpCode::new(Op::FLOT, "FLOT", 5, 6, 3, OperandKind::FIXED),
+    OpCode::new(Op::FIX, "FIX", 5, 7, 3, OperandKind::FIXED),
+    // only on a machine with interrupts, see computer::interrupts
+    OpCode::new(Op::INT, "INT", 5, 9, 2, OperandKind::FIXED),
+    OpCode::new(Op::SLA, "SLA", 6, 0, 2, OperandKind::FIXED),
+    OpCode::new(Op::SRA, "SRA", 6, 1, 2, OperandKind::FIXED),
+    OpCode::new(Op::SLAX, "SLAX", 6, 2, 2, OperandKind::FIXED),
+    OpCode::new(Op::SRAX, "SRAX", 6, 3, 2, OperandKind::FIXED),
+    OpCode::new(Op::SLC, "SLC", 6, 4, 2, OperandKind::FIXED),
+    OpCode::new(Op::SRC, "SRC", 6, 5, 2, OperandKind::FIXED),
+    // only on binary MIX, added in later printings of volume 1
+    OpCode::new(Op::SLB, "SLB", 6, 6, 2, OperandKind::FIXED),
+    OpCode::new(Op::SRB, "SRB", 6, 7, 2, OperandKind::FIXED),
+    OpCode::new(Op::MOVE, "MOVE", 7, 1, 1, OperandKind::COUNT),
+    OpCode::new(Op::LDA, "LDA", 8, 5, 2, OperandKind::FIELD),
+    OpCode::new(Op::LD1, "LD1", 9, 5, 2, OperandKind::FIELD),
+    OpCode::new(Op::LD2, "LD2", 10, 5, 2, OperandKind::FIELD),
+    OpCode::new(Op::LD3, "LD3", 11, 5, 2, OperandKind::FIELD),
+    OpCode::new(Op::LD4, "LD4", 12, 5, 2, OperandKind::FIELD),
+    OpCode::new(Op::LD5, "LD5", 13, 5, 2, OperandKind::FIELD),
+    OpCode::new(Op::LD6, "LD6", 14, 5, 2, OperandKind::FIELD),
+    OpCode::new(Op::LDX, "LDX", 15, 5, 2, OperandKind::FIELD),
+    OpCode::new(Op::LDAN, "LDAN", 16, 5, 2, OperandKind::FIELD),
+    OpCode::new(Op::LD1N, "LD1N", 17, 5, 2, OperandKind::FIELD),
+    OpCode::new(Op::LD2N, "LD2N", 18, 5, 2, OperandKind::FIELD),
+    OpCode::new(Op::LD3N, "LD3N", 19, 5, 2, OperandKind::FIELD),
+    OpCode::new(Op::LD4N, "LD4N", 20, 5, 2, OperandKind::FIELD),
+    OpCode::new(Op::LD5N, "LD5N", 21, 5, 2, OperandKind::FIELD),
+    OpCode::new(Op::LD6N, "LD6N", 22, 5, 2, OperandKind::FIELD),
+    OpCode::new(Op::LDXN, "LDXN", 23, 5, 2, OperandKind::FIELD),
+    OpCode::new(Op::STA, "STA", 24, 5, 2, OperandKind::FIELD),
+    OpCode::new(Op::ST1, "ST1", 25, 5, 2, OperandKind::FIELD),
+    OpCode::new(Op::ST2, "ST2", 26, 5, 2, OperandKind::FIELD),
+    OpCode::new(Op::ST3, "ST3", 27, 5, 2, OperandKind::FIELD),
+    OpCode::new(Op::ST4, "ST4", 28, 5, 2, OperandKind::FIELD),
+    OpCode::new(Op::ST5, "ST5", 29, 5, 2, OperandKind::FIELD),
+    OpCode::new(Op::ST6, "ST6", 30, 5, 2, OperandKind::FIELD),
+    OpCode::new(Op::STX, "STX", 31, 5, 2, OperandKind::FIELD),
+    OpCode::new(Op::STJ, "STJ", 32, 2, 2, OperandKind::FIELD),
+    OpCode::new(Op::STZ, "STZ", 33, 5, 2, OperandKind::FIELD),
+    OpCode::new(Op::JBUS, "JBUS", 34, 0, 1, OperandKind::UNIT),
+    OpCode::new(Op::IOC, "IOC", 35, 0, 1, OperandKind::UNIT),
+    OpCode::new(Op::IN, "IN", 36, 0, 1, OperandKind::UNIT),
+    OpCode::new(Op::OUT, "OUT", 37, 0, 1, OperandKind::UNIT),
+    OpCode::new(Op::JRED, "JRED", 38, 0, 1, OperandKind::UNIT),
+    OpCode::new(Op::JMP, "JMP", 39, 0, 1, OperandKind::FIXED),
+    OpCode::new(Op::JSJ, "JSJ", 39, 1, 1, OperandKind::FIXED),
+    OpCode::new(Op::JOV, "JOV", 39, 2, 1, OperandKind::FIXED),
+    OpCode::new(Op::JNOV, "JNOV", 39, 3, 1, OperandKind::FIXED),
+    OpCode::new(Op::JL, "JL", 39, 4, 1, OperandKind::FIXED),
+    OpCode::new(Op::JE, "JE", 39, 5, 1, OperandKind::FIXED),
+    OpCode::new(Op::JG, "JG", 39, 6, 1, OperandKind::FIXED),
+    OpCode::new(Op::JGE, "JGE", 39, 7, 1, OperandKind::FIXED),
+    OpCode::new(Op::JNE, "JNE", 39, 8, 1, OperandKind::FIXED),
+    OpCode::new(Op::JLE, "JLE", 39, 9, 1, OperandKind::FIXED),
+    OpCode::new(Op::JAN, "JAN", 40, 0, 1, OperandKind::FIXED),
+    OpCode::new(Op::JAZ, "JAZ", 40, 1, 1, OperandKind::FIXED),
+    OpCode::new(Op::JAP, "JAP", 40, 2, 1, OperandKind::FIXED),
+    OpCode::new(Op::JANN, "JANN", 40, 3, 1, OperandKind::FIXED),
+    OpCode::new(Op::JANZ, "JANZ", 40, 4, 1, OperandKind::FIXED),
+    OpCode::new(Op::JANP, "JANP", 40, 5, 1, OperandKind::FIXED),
+    OpCode::new(Op::JAE, "JAE", 40, 6, 1, OperandKind::FIXED),
+    OpCode::new(Op::JAO, "JAO", 40, 7, 1, OperandKind::FIXED),
+    OpCode::new(Op::J1N, "J1N", 41, 0, 1, OperandKind::FIXED),
+    OpCode::new(Op::J1Z, "J1Z", 41, 1, 1, OperandKind::FIXED),
+    OpCode::new(Op::J1P, "J1P", 41, 2, 1, OperandKind::FIXED),
+    OpCode::new(Op::J1NN, "J1NN", 41, 3, 1, OperandKind::FIXED),
+    OpCode::new(Op::J1NZ, "J1NZ", 41, 4, 1, OperandKind::FIXED),
+    OpCode::new(Op::J1NP, "J1NP", 41, 5, 1, OperandKind::FIXED),
+    OpCode::new(Op::J2N, "J2N", 42, 0, 1, OperandKind::FIXED),
+    OpCode::new(Op::J2Z, "J2Z", 42, 1, 1, OperandKind::FIXED),
+    OpCode::new(Op::J2P, "J2P", 42, 2, 1, OperandKind::FIXED),
+    OpCode::new(Op::J2NN, "J2NN", 42, 3, 1, OperandKind::FIXED),
+    OpCode::new(Op::J2NZ, "J2NZ", 42, 4, 1, OperandKind::FIXED),
+    OpCode::new(Op::J2NP, "J2NP", 42, 5, 1, OperandKind::FIXED),
+    OpCode::new(Op::J3N, "J3N", 43, 0, 1, OperandKind::FIXED),
+    OpCode::new(Op::J3Z, "J3Z", 43, 1, 1, OperandKind::FIXED),
+    OpCode::new(Op::J3P, "J3P", 43, 2, 1, OperandKind::FIXED),
+    OpCode::new(Op::J3NN, "J3NN", 43, 3, 1, OperandKind::FIXED),
+    OpCode::new(Op::J3NZ, "J3NZ", 43, 4, 1, OperandKind::FIXED),
+    OpCode::new(Op::J3NP, "J3NP", 43, 5, 1, OperandKind::FIXED),
+    OpCode::new(Op::J4N, "J4N", 44, 0, 1, OperandKind::FIXED),
+    OpCode::new(Op::J4Z, "J4Z", 44, 1, 1, OperandKind::FIXED),
+    OpCode::new(Op::J4P, "J4P", 44, 2, 1, OperandKind::FIXED),
+    OpCode::new(Op::J4NN, "J4NN", 44, 3, 1, OperandKind::FIXED),
+    OpCode::new(Op::J4NZ, "J4NZ", 44, 4, 1, OperandKind::FIXED),
+    OpCode::new(Op::J4NP, "J4NP", 44, 5, 1, OperandKind::FIXED),
+    OpCode::new(Op::J5N, "J5N", 45, 0, 1, OperandKind::FIXED),
+    OpCode::new(Op::J5Z, "J5Z", 45, 1, 1, OperandKind::FIXED),
+    OpCode::new(Op::J5P, "J5P", 45, 2, 1, OperandKind::FIXED),
+    OpCode::new(Op::J5NN, "J5NN", 45, 3, 1, OperandKind::FIXED),
+    OpCode::new(Op::J5NZ, "J5NZ", 45, 4, 1, OperandKind::FIXED),
+    OpCode::new(Op::J5NP, "J5NP", 45, 5, 1, OperandKind::FIXED),
+    OpCode::new(Op::J6N, "J6N", 46, 0, 1, OperandKind::FIXED),
+    OpCode::new(Op::J6Z, "J6Z", 46, 1, 1, OperandKind::FIXED),
+    OpCode::new(Op::J6P, "J6P", 46, 2, 1, OperandKind::FIXED),
+    OpCode::new(Op::J6NN, "J6NN", 46, 3, 1, OperandKind::FIXED),
+    OpCode::new(Op::J6NZ, "J6NZ", 46, 4, 1, OperandKind::FIXED),
+    OpCode::new(Op::J6NP, "J6NP", 46, 5, 1, OperandKind::FIXED),
+    OpCode::new(Op::JXN, "JXN", 47, 0, 1, OperandKind::FIXED),
+    OpCode::new(Op::JXZ, "JXZ", 47, 1, 1, OperandKind::FIXED),
+    OpCode::new(Op::JXP, "JXP", 47, 2, 1, OperandKind::FIXED),
+    OpCode::new(Op::JXNN, "JXNN", 47, 3, 1, OperandKind::FIXED),
+    OpCode::new(Op::JXNZ, "JXNZ", 47, 4, 1, OperandKind::FIXED),
+    OpCode::new(Op::JXNP, "JXNP", 47, 5, 1, OperandKind::FIXED),
+    OpCode::new(Op::JXE, "JXE", 47, 6, 1, OperandKind::FIXED),
+    OpCode::new(Op::JXO, "JXO", 47, 7, 1, OperandKind::FIXED),
+    OpCode::new(Op::INCA, "INCA", 48, 0, 1, OperandKind::FIXED),
+    OpCode::new(Op::DECA, "DECA", 48, 1, 1, OperandKind::FIXED),
+    OpCode::new(Op::ENTA, "ENTA", 48, 2, 1, OperandKind::FIXED),
+    OpCode::new(Op::ENNA, "ENNA", 48, 3, 1, OperandKind::FIXED),
+    OpCode::new(Op::INC1, "INC1", 49, 0, 1, OperandKind::FIXED),
+    OpCode::new(Op::DEC1, "DEC1", 49, 1, 1, OperandKind::FIXED),
+    OpCode::new(Op::ENT1, "ENT1", 49, 2, 1, OperandKind::FIXED),
+    OpCode::new(Op::ENN1, "ENN1", 49, 3, 1, OperandKind::FIXED),
+    OpCode::new(Op::INC2, "INC2", 50, 0, 1, OperandKind::FIXED),
+    OpCode::new(Op::DEC2, "DEC2", 50, 1, 1, OperandKind::FIXED),
+    OpCode::new(Op::ENT2, "ENT2", 50, 2, 1, OperandKind::FIXED),
+    OpCode::new(Op::ENN2, "ENN2", 50, 3, 1, OperandKind::FIXED),
+    OpCode::new(Op::INC3, "INC3", 51, 0, 1, OperandKind::FIXED),
+    OpCode::new(Op::DEC3, "DEC3", 51, 1, 1, OperandKind::FIXED),
+    OpCode::new(Op::ENT3, "ENT3", 51, 2, 1, OperandKind::FIXED),
+    OpCode::new(Op::ENN3, "ENN3", 51, 3, 1, OperandKind::FIXED),
+    OpCode::new(Op::INC4, "INC4", 52, 0, 1, OperandKind::FIXED),
+    OpCode::new(Op::DEC4, "DEC4", 52, 1, 1, OperandKind::FIXED),
+    OpCode::new(Op::ENT4, "ENT4", 52, 2, 1, OperandKind::FIXED),
+    OpCode::new(Op::ENN4, "ENN4", 52, 3, 1, OperandKind::FIXED),
+    OpCode::new(Op::INC5, "INC5", 53, 0, 1, OperandKind::FIXED),
+    OpCode::new(Op::DEC5, "DEC5", 53, 1, 1, OperandKind::FIXED),
+    OpCode::new(Op::ENT5, "ENT5", 53, 2, 1, OperandKind::FIXED),
+    OpCode::new(Op::ENN5, "ENN5", 53, 3, 1, OperandKind::FIXED),
+    OpCode::new(Op::INC6, "INC6", 54, 0, 1, OperandKind::FIXED),
+    OpCode::new(Op::DEC6, "DEC6", 54, 1, 1, OperandKind::FIXED),
+    OpCode::new(Op::ENT6, "ENT6", 54, 2, 1, OperandKind::FIXED),
+    OpCode::new(Op::ENN6, "ENN6", 54, 3, 1, OperandKind::FIXED),
+    OpCode::new(Op::INCX, "INCX", 55, 0, 1, OperandKind::FIXED),
+    OpCode::new(Op::DECX, "DECX", 55, 1, 1, OperandKind::FIXED),
+    OpCode::new(Op::ENTX, "ENTX", 55, 2, 1, OperandKind::FIXED),
+    OpCode::new(Op::ENNX, "ENNX", 55, 3, 1, OperandKind::FIXED),
+    OpCode::new(Op::CMPA, "CMPA", 56, 5, 2, OperandKind::FIELD),
+    OpCode::new(Op::FCMP, "FCMP", 56, 6, 4, OperandKind::FIXED),
+    OpCode::new(Op::CMP1, "CMP1", 57, 5, 2, OperandKind::FIELD),
+    OpCode::new(Op::CMP2, "CMP2", 58, 5, 2, OperandKind::FIELD),
+    OpCode::new(Op::CMP3, "CMP3", 59, 5, 2, OperandKind::FIELD),
+    OpCode::new(Op::CMP4, "CMP4", 60, 5, 2, OperandKind::FIELD),
+    OpCode::new(Op::CMP5, "CMP5", 61, 5, 2, OperandKind::FIELD),
+    OpCode::new(Op::CMP6, "CMP6", 62, 5, 2, OperandKind::FIELD),
+    OpCode::new(Op::CMPX, "CMPX", 63, 5, 2, OperandKind::FIELD),
+];
+
+#[test]
+fn should_declare_table_in_enum_order() {
+    for (idx, opcode) in OPCODES.iter().enumerate() {
+        assert_eq!(opcode.op as usize, idx);
+        assert_eq!(opcode.op.opcode(), opcode);
+    }
+}
@@ -0,0 +1,96 @@
+use std::fmt;
+
+// Where each assembled word came from, so an address can be reported the way the programmer
+// wrote it: the file and line, and the nearest label at or before it.
+#[derive(Debug, PartialEq, Eq, Default, Clone)]
+pub struct SourceMap {
+    pub files: Vec<String>,
+    // address, index into files and line number for every word that came from a line, which
+    // literals and undefined symbols didn't
+    pub lines: Vec<(i32, usize, usize)>,
+    // symbols that name a location, unlike EQU ones which can be any number
+    pub labels: Vec<(String, i32)>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SourceLocation {
+    pub file: String,
+    pub line: usize,
+    // the nearest label at or before the address and how far past it the address is
+    pub label: Option<(String, i32)>,
+}
+
+// primes.mixal:18 LOOP+2
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.file, self.line)?;
+        match &self.label {
+            Some((name, 0)) => write!(f, " {}", name),
+            Some((name, offset)) => write!(f, " {}+{}", name, offset),
+            None => Ok(()),
+        }
+    }
+}
+
+impl SourceMap {
+    // the index of a file, adding it if it's new
+    pub fn file(&mut self, name: &str) -> usize {
+        match self.files.iter().position(|x| x == name) {
+            Some(x) => x,
+            None => {
+                self.files.push(name.to_string());
+                self.files.len() - 1
+            }
+        }
+    }
+
+    pub fn line_of_address(&self, address: i32) -> Option<(usize, usize)> {
+        self.lines
+            .iter()
+            .find(|(x, _, _)| *x == address)
+            .map(|(_, file, line)| (*file, *line))
+    }
+
+    pub fn address_of_line(&self, file: usize, line: usize) -> Option<i32> {
+        self.lines
+            .iter()
+            .find(|(_, x, y)| *x == file && *y == line)
+            .map(|(address, _, _)| *address)
+    }
+
+    pub fn label_before(&self, address: i32) -> Option<(String, i32)> {
+        self.labels
+            .iter()
+            .filter(|(_, x)| *x <= address)
+            .max_by_key(|(_, x)| *x)
+            .map(|(name, x)| (name.clone(), address - x))
+    }
+
+    pub fn locate(&self, address: i32) -> Option<SourceLocation> {
+        let (file, line) = self.line_of_address(address)?;
+        Some(SourceLocation {
+            file: self.files[file].clone(),
+            line,
+            label: self.label_before(address),
+        })
+    }
+}
+
+#[test]
+fn should_locate_addresses_by_line_and_label() {
+    let mut map = SourceMap::default();
+    let file = map.file("main.mixal");
+    assert_eq!(map.file("main.mixal"), file);
+    map.lines = vec![(3000, file, 4), (3001, file, 5), (3002, file, 7)];
+    map.labels = vec![("START".to_string(), 3000), ("LOOP".to_string(), 3002)];
+    assert_eq!(
+        map.locate(3001).map(|x| x.to_string()),
+        Some("main.mixal:5 START+1".to_string())
+    );
+    assert_eq!(
+        map.locate(3002).map(|x| x.to_string()),
+        Some("main.mixal:7 LOOP".to_string())
+    );
+    assert_eq!(map.locate(3003), None);
+    assert_eq!(map.address_of_line(file, 7), Some(3002));
+}
@@ -0,0 +1,294 @@
+use std::fmt::Write as _;
+
+use crate::{
+    assembler::{self, AssemblerOptions},
+    computer::{
+        devices::{Devices, TapeUnit, TextInput, TextOutput},
+        Computer, StopReason,
+    },
+    data_types::{ByteSize, Word},
+};
+
+// For tests of whole programs: give the MIXAL source, what the devices hold and what the machine
+// should look like when it halts. run assembles and runs it and panics with every difference
+// found, so one failing test shows everything that's wrong rather than just the first thing.
+//
+//     ProgramTest::new(source)
+//         .with_cards(&["HELLO"])
+//         .expect_register(Register::A, 5)
+//         .expect_printer(&["HELLO"])
+//         .run();
+pub struct ProgramTest {
+    source: String,
+    options: AssemblerOptions,
+    cards: Vec<String>,
+    tapes: Vec<(usize, Vec<Word>)>,
+    limit: u64,
+    registers: Vec<(Register, i64)>,
+    memory: Vec<(i32, Contents)>,
+    printer: Option<Vec<String>>,
+    punch: Option<Vec<String>>,
+    overflow: Option<bool>,
+}
+
+// a number is compared as a number, so +0 and -0 are the same, and a word byte for byte
+enum Contents {
+    Value(i64),
+    Word(Word),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Register {
+    A,
+    X,
+    I1,
+    I2,
+    I3,
+    I4,
+    I5,
+    I6,
+    J,
+}
+
+impl Register {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::A => "rA",
+            Self::X => "rX",
+            Self::I1 => "rI1",
+            Self::I2 => "rI2",
+            Self::I3 => "rI3",
+            Self::I4 => "rI4",
+            Self::I5 => "rI5",
+            Self::I6 => "rI6",
+            Self::J => "rJ",
+        }
+    }
+
+    fn value(&self, computer: &Computer) -> i64 {
+        let registers = &computer.registers;
+        let size = computer.byte_size;
+        match self {
+            Self::A => registers.a.to_i64_in(size),
+            Self::X => registers.x.to_i64_in(size),
+            Self::I1 => registers.i1.to_i32_in(size).into(),
+            Self::I2 => registers.i2.to_i32_in(size).into(),
+            Self::I3 => registers.i3.to_i32_in(size).into(),
+            Self::I4 => registers.i4.to_i32_in(size).into(),
+            Self::I5 => registers.i5.to_i32_in(size).into(),
+            Self::I6 => registers.i6.to_i32_in(size).into(),
+            Self::J => registers.j.to_i32_in(size).into(),
+        }
+    }
+}
+
+impl ProgramTest {
+    pub fn new(source: &str) -> Self {
+        Self {
+            source: source.to_string(),
+            options: AssemblerOptions::default(),
+            cards: Vec::new(),
+            tapes: Vec::new(),
+            limit: 1_000_000,
+            registers: Vec::new(),
+            memory: Vec::new(),
+            printer: None,
+            punch: None,
+            overflow: None,
+        }
+    }
+
+    pub fn with_byte_size(mut self, byte_size: ByteSize) -> Self {
+        self.options.byte_size = byte_size;
+        self
+    }
+
+    // names the source in errors and lets it INCLUDE files next to it
+    pub fn with_file_name(mut self, file_name: &str) -> Self {
+        self.options.file_name = Some(file_name.to_string());
+        self
+    }
+
+    pub fn with_cards(mut self, cards: &[&str]) -> Self {
+        self.cards = cards.iter().map(|x| x.to_string()).collect();
+        self
+    }
+
+    pub fn with_tape(mut self, unit: usize, words: &[Word]) -> Self {
+        self.tapes.push((unit, words.to_vec()));
+        self
+    }
+
+    // how many instructions it can run before it's taken to be stuck
+    pub fn with_limit(mut self, limit: u64) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    pub fn expect_register(mut self, register: Register, value: i64) -> Self {
+        self.registers.push((register, value));
+        self
+    }
+
+    pub fn expect_memory(mut self, address: i32, value: i64) -> Self {
+        self.memory.push((address, Contents::Value(value)));
+        self
+    }
+
+    // for when the sign of zero or the bytes themselves matter
+    pub fn expect_word(mut self, address: i32, word: Word) -> Self {
+        self.memory.push((address, Contents::Word(word)));
+        self
+    }
+
+    pub fn expect_printer(mut self, lines: &[&str]) -> Self {
+        self.printer = Some(lines.iter().map(|x| x.to_string()).collect());
+        self
+    }
+
+    pub fn expect_punch(mut self, lines: &[&str]) -> Self {
+        self.punch = Some(lines.iter().map(|x| x.to_string()).collect());
+        self
+    }
+
+    pub fn expect_overflow(mut self, overflow: bool) -> Self {
+        self.overflow = Some(overflow);
+        self
+    }
+
+    // the machine after it halted for any further checks
+    pub fn run(&self) -> Computer {
+        match self.check() {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    // like run, with what went wrong as the error
+    pub fn check(&self) -> Result<Computer, String> {
+        let program = assembler::assemble_with(&self.source, &self.options)
+            .map_err(|e| format!("the program didn't assemble: {}", e))?;
+        let mut computer = Computer::with_byte_size(self.options.byte_size);
+        computer.devices = Devices::new();
+        computer.devices.card_reader = Some(TextInput::card_reader(self.cards.clone()));
+        computer.devices.card_punch = Some(TextOutput::card_punch());
+        computer.devices.line_printer = Some(TextOutput::line_printer());
+        for (unit, words) in self.tapes.iter() {
+            match computer.devices.tapes.get_mut(*unit) {
+                Some(x) => *x = Some(TapeUnit::from_words(0, words)),
+                None => return Err(format!("tape unit {} doesn't exist", unit)),
+            }
+        }
+        computer
+            .load_program(&program)
+            .map_err(|e| format!("the program didn't load: {}", e))?;
+        match computer.run(Some(self.limit)) {
+            Ok(StopReason::HALTED) => (),
+            Ok(StopReason::LIMIT) => {
+                return Err(format!(
+                    "the program didn't halt within {} instructions, it was at {}",
+                    self.limit,
+                    computer.describe_address(computer.current_instruction_address)
+                ))
+            }
+            Err(fault) => return Err(format!("the program stopped with a {}", fault)),
+        }
+
+        let mut differences = String::new();
+        for (register, expected) in self.registers.iter() {
+            let value = register.value(&computer);
+            if value != *expected {
+                writeln!(
+                    differences,
+                    "{}: expected {}, got {}",
+                    register.name(),
+                    expected,
+                    value
+                )
+                .unwrap();
+            }
+        }
+        for (address, expected) in self.memory.iter() {
+            let word = match computer.memory.get(*address) {
+                Ok(x) => x,
+                Err(_) => {
+                    writeln!(differences, "{}: not a memory location", address).unwrap();
+                    continue;
+                }
+            };
+            let size = computer.byte_size;
+            let (matches, expected) = match expected {
+                Contents::Value(x) => (word.to_i64_in(size) == *x, x.to_string()),
+                Contents::Word(x) => (word == *x, describe_word(x, size)),
+            };
+            if !matches {
+                writeln!(
+                    differences,
+                    "{}: expected {}, got {}",
+                    computer.describe_address(*address),
+                    expected,
+                    describe_word(&word, size)
+                )
+                .unwrap();
+            }
+        }
+        if let Some(expected) = self.overflow {
+            if computer.overflow != expected {
+                writeln!(
+                    differences,
+                    "overflow: expected {}, got {}",
+                    on_off(expected),
+                    on_off(computer.overflow)
+                )
+                .unwrap();
+            }
+        }
+        let devices = &computer.devices;
+        let outputs = [
+            ("printer", &self.printer, &devices.line_printer),
+            ("punch", &self.punch, &devices.card_punch),
+        ];
+        for (device, expected, output) in outputs {
+            if let (Some(expected), Some(output)) = (expected, output) {
+                if *expected != output.lines {
+                    differences += &diff_lines(device, expected, &output.lines);
+                }
+            }
+        }
+        match differences.is_empty() {
+            true => Ok(computer),
+            false => Err(differences),
+        }
+    }
+}
+
+// 5 (+ 0 0 0 0 5)
+fn describe_word(word: &Word, size: ByteSize) -> String {
+    format!("{} ({})", word.to_i64_in(size), word.to_byte_string())
+}
+
+fn on_off(value: bool) -> &'static str {
+    match value {
+        true => "on",
+        false => "off",
+    }
+}
+
+// every line, with - before what was expected and + before what was produced where they differ
+fn diff_lines(device: &str, expected: &[String], lines: &[String]) -> String {
+    let mut diff = format!("{} output differs, - expected, + produced:\n", device);
+    for idx in 0..expected.len().max(lines.len()) {
+        match (expected.get(idx), lines.get(idx)) {
+            (Some(x), Some(y)) if x == y => writeln!(diff, "  {}", x).unwrap(),
+            (x, y) => {
+                if let Some(x) = x {
+                    writeln!(diff, "- {}", x).unwrap();
+                }
+                if let Some(y) = y {
+                    writeln!(diff, "+ {}", y).unwrap();
+                }
+            }
+        }
+    }
+    diff
+}
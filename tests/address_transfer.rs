@@ -1639,7 +1639,7 @@ mod deca {
         computer.registers.i1 = Index::from_i32(8).unwrap();
         let instruction = Word::from_instruction_parts(Sign::PLUS, 3, 1, FIELD, CODE).unwrap();
         computer.handle_instruction(instruction);
-        assert_eq!(computer.registers.a.to_i32(), 6);
+        assert_eq!(computer.registers.a.to_i32(), -10);
         assert!(!computer.overflow);
     }
 }
@@ -1709,7 +1709,7 @@ mod decx {
         computer.registers.i1 = Index::from_i32(8).unwrap();
         let instruction = Word::from_instruction_parts(Sign::PLUS, 3, 1, FIELD, CODE).unwrap();
         computer.handle_instruction(instruction);
-        assert_eq!(computer.registers.x.to_i32(), 6);
+        assert_eq!(computer.registers.x.to_i32(), -10);
         assert!(!computer.overflow);
     }
 }
@@ -1759,7 +1759,7 @@ mod dec1 {
         computer.registers.i2 = Index::from_i32(8).unwrap();
         let instruction = Word::from_instruction_parts(Sign::PLUS, 3, 2, FIELD, CODE).unwrap();
         computer.handle_instruction(instruction);
-        assert_eq!(computer.registers.i1.to_i32(), 6);
+        assert_eq!(computer.registers.i1.to_i32(), -10);
         assert!(!computer.overflow);
     }
 }
@@ -1809,7 +1809,7 @@ mod dec2 {
         computer.registers.i2 = Index::from_i32(8).unwrap();
         let instruction = Word::from_instruction_parts(Sign::PLUS, 3, 1, FIELD, CODE).unwrap();
         computer.handle_instruction(instruction);
-        assert_eq!(computer.registers.i2.to_i32(), 6);
+        assert_eq!(computer.registers.i2.to_i32(), 4);
         assert!(!computer.overflow);
     }
 }
@@ -1859,7 +1859,7 @@ mod dec3 {
         computer.registers.i2 = Index::from_i32(8).unwrap();
         let instruction = Word::from_instruction_parts(Sign::PLUS, 3, 2, FIELD, CODE).unwrap();
         computer.handle_instruction(instruction);
-        assert_eq!(computer.registers.i3.to_i32(), 6);
+        assert_eq!(computer.registers.i3.to_i32(), -10);
         assert!(!computer.overflow);
     }
 }
@@ -1909,7 +1909,7 @@ mod dec4 {
         computer.registers.i2 = Index::from_i32(8).unwrap();
         let instruction = Word::from_instruction_parts(Sign::PLUS, 3, 2, FIELD, CODE).unwrap();
         computer.handle_instruction(instruction);
-        assert_eq!(computer.registers.i4.to_i32(), 6);
+        assert_eq!(computer.registers.i4.to_i32(), -10);
         assert!(!computer.overflow);
     }
 }
@@ -1959,7 +1959,7 @@ mod dec5 {
         computer.registers.i2 = Index::from_i32(8).unwrap();
         let instruction = Word::from_instruction_parts(Sign::PLUS, 3, 2, FIELD, CODE).unwrap();
         computer.handle_instruction(instruction);
-        assert_eq!(computer.registers.i5.to_i32(), 6);
+        assert_eq!(computer.registers.i5.to_i32(), -10);
         assert!(!computer.overflow);
     }
 }
@@ -2009,7 +2009,7 @@ mod dec6 {
         computer.registers.i2 = Index::from_i32(8).unwrap();
         let instruction = Word::from_instruction_parts(Sign::PLUS, 3, 2, FIELD, CODE).unwrap();
         computer.handle_instruction(instruction);
-        assert_eq!(computer.registers.i6.to_i32(), 6);
+        assert_eq!(computer.registers.i6.to_i32(), -10);
         assert!(!computer.overflow);
     }
 }
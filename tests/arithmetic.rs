@@ -53,7 +53,7 @@ mod add {
         let instruction = Word::from_instruction_parts(Sign::PLUS, 3000, 0, 5, 1).unwrap();
         computer.handle_instruction(instruction);
         assert_eq!(computer.registers.a.to_i32(), 0);
-        assert_eq!(computer.overflow, true);
+        assert!(computer.overflow);
     }
 
     #[test]
@@ -64,7 +64,7 @@ mod add {
         let instruction = Word::from_instruction_parts(Sign::PLUS, 3001, 0, 5, 1).unwrap();
         computer.handle_instruction(instruction);
         assert_eq!(computer.registers.a.to_i32(), 0);
-        assert_eq!(computer.overflow, true);
+        assert!(computer.overflow);
     }
 
     #[test]
@@ -101,9 +101,10 @@ mod add {
         computer.handle_instruction(instruction2);
         assert_eq!(computer.registers.a.to_i32(), 6 + 128 + 3);
 
+        // a field without the sign is positive, even from a negative word
         let instruction3 = Word::from_instruction_parts(Sign::PLUS, 1001, 0, 28, 1).unwrap();
         computer.handle_instruction(instruction3);
-        assert_eq!(computer.registers.a.to_i32(), 137 - 192 - 4);
+        assert_eq!(computer.registers.a.to_i32(), 137 + 192 + 4);
     }
 
     #[test]
@@ -183,7 +184,7 @@ mod sub {
         let instruction = Word::from_instruction_parts(Sign::PLUS, 3001, 0, 5, 2).unwrap();
         computer.handle_instruction(instruction);
         assert_eq!(computer.registers.a.to_i32(), 0);
-        assert_eq!(computer.overflow, true);
+        assert!(computer.overflow);
     }
 
     #[test]
@@ -194,7 +195,7 @@ mod sub {
         let instruction = Word::from_instruction_parts(Sign::PLUS, 3000, 0, 5, 2).unwrap();
         computer.handle_instruction(instruction);
         assert_eq!(computer.registers.a.to_i32(), 0);
-        assert_eq!(computer.overflow, true);
+        assert!(computer.overflow);
     }
 
     #[test]
@@ -223,17 +224,18 @@ mod sub {
     fn should_add_parts_of_word() {
         let mut computer = setup_computer();
 
+        // a field without the sign is positive, even from a negative word
         let instruction = Word::from_instruction_parts(Sign::PLUS, 1001, 0, 45, 2).unwrap();
         computer.handle_instruction(instruction);
-        assert_eq!(computer.registers.a.to_i32(), 6);
+        assert_eq!(computer.registers.a.to_i32(), 1 - 5);
 
         let instruction2 = Word::from_instruction_parts(Sign::PLUS, 1001, 0, 19, 2).unwrap();
         computer.handle_instruction(instruction2);
-        assert_eq!(computer.registers.a.to_i32(), 6 + 128 + 3);
+        assert_eq!(computer.registers.a.to_i32(), -4 - 128 - 3);
 
         let instruction3 = Word::from_instruction_parts(Sign::PLUS, 1000, 0, 28, 2).unwrap();
         computer.handle_instruction(instruction3);
-        assert_eq!(computer.registers.a.to_i32(), 137 - 192 - 4);
+        assert_eq!(computer.registers.a.to_i32(), -135 - 192 - 4);
     }
 
     #[test]
@@ -284,7 +286,7 @@ mod mul {
         assert_eq!(computer.registers.x.to_i32(), 15);
         assert_eq!(computer.registers.a.to_i32(), 0);
         assert_eq!(computer.registers.a.sign, Sign::PLUS);
-        assert_eq!(computer.overflow, false);
+        assert!(!computer.overflow);
     }
 
     #[test]
@@ -294,7 +296,7 @@ mod mul {
         assert_eq!(computer.registers.x.to_i32(), -12);
         assert_eq!(computer.registers.a.to_i32(), 0);
         assert_eq!(computer.registers.a.sign, Sign::MINUS);
-        assert_eq!(computer.overflow, false);
+        assert!(!computer.overflow);
     }
 
     #[test]
@@ -304,7 +306,7 @@ mod mul {
         assert_eq!(computer.registers.x.to_i32(), 0);
         assert_eq!(computer.registers.x.sign, Sign::PLUS);
         assert_eq!(computer.registers.a.to_i32(), 64_i32.pow(2));
-        assert_eq!(computer.overflow, false);
+        assert!(!computer.overflow);
     }
 
     #[test]
@@ -314,7 +316,7 @@ mod mul {
         assert_eq!(computer.registers.x.to_i32(), 0);
         assert_eq!(computer.registers.x.sign, Sign::MINUS);
         assert_eq!(computer.registers.a.to_i32(), -64_i32.pow(2));
-        assert_eq!(computer.overflow, false);
+        assert!(!computer.overflow);
     }
 
     #[test]
@@ -459,3 +461,49 @@ mod div {
         assert!(computer.overflow);
     }
 }
+
+// DECA, DECX and DECi subtract all of M = A + rIi, not just A
+mod dec {
+    use mix_emu::computer::*;
+    use mix_emu::data_types::*;
+
+    fn setup_computer() -> Computer {
+        let mut computer = Computer::new();
+        computer.registers.a = Word::from_i32(100).unwrap();
+        computer.registers.x = Word::from_i32(100).unwrap();
+        computer.registers.i1 = Index::from_i32(5).unwrap();
+        computer.registers.i2 = Index::from_i32(100).unwrap();
+        computer
+    }
+
+    #[test]
+    fn should_subtract_the_index_register_for_deca() {
+        let mut computer = setup_computer();
+        // DECA 10,1
+        let instruction = Word::from_instruction_parts(Sign::PLUS, 10, 1, 1, 48).unwrap();
+        computer.handle_instruction(instruction);
+        assert_eq!(computer.registers.a.to_i32(), 85);
+    }
+
+    #[test]
+    fn should_subtract_the_index_register_for_decx() {
+        let mut computer = setup_computer();
+        // DECX -10,1
+        let instruction = Word::from_instruction_parts(Sign::MINUS, 10, 1, 1, 55).unwrap();
+        computer.handle_instruction(instruction);
+        assert_eq!(computer.registers.x.to_i32(), 105);
+    }
+
+    #[test]
+    fn should_subtract_the_index_register_for_deci() {
+        let mut computer = setup_computer();
+        // DEC2 10,1
+        let instruction = Word::from_instruction_parts(Sign::PLUS, 10, 1, 1, 50).unwrap();
+        computer.handle_instruction(instruction);
+        assert_eq!(computer.registers.i2.to_i32(), 85);
+        // DEC1 3,1 takes rI1 to -3
+        let instruction = Word::from_instruction_parts(Sign::PLUS, 3, 1, 1, 49).unwrap();
+        computer.handle_instruction(instruction);
+        assert_eq!(computer.registers.i1.to_i32(), -3);
+    }
+}
@@ -0,0 +1,204 @@
+use mix_emu::assembler::*;
+use mix_emu::computer::*;
+use mix_emu::data_types::*;
+use mix_emu::object::*;
+
+fn word_at(program: &Program, address: i32) -> Word {
+    program
+        .words
+        .iter()
+        .find(|(x, _)| *x == address)
+        .map(|(_, word)| *word)
+        .unwrap()
+}
+
+#[test]
+fn should_assemble_program_p() {
+    let source = include_str!("programs/primes.mixal");
+    let program = assemble(source).unwrap();
+
+    assert_eq!(program.start, Some(3000));
+    assert_eq!(program.symbol("PRIME"), Some(-1));
+    assert_eq!(program.symbol("TITLE"), Some(1995));
+    // LD1 =1-L= uses a literal placed after the last word of the program
+    assert_eq!(
+        word_at(&program, 3001),
+        Word::from_instruction_parts(Sign::PLUS, 2050, 0, 5, 9).unwrap()
+    );
+    assert_eq!(word_at(&program, 2050).to_i32(), -499);
+    assert_eq!(word_at(&program, 2051).to_i32(), 3);
+    // ST2 PRIME+L,1
+    assert_eq!(
+        word_at(&program, 3004),
+        Word::from_instruction_parts(Sign::PLUS, 499, 1, 5, 26).unwrap()
+    );
+    // J1Z 2F and JXZ 4B
+    assert_eq!(word_at(&program, 3005).address(), 3016);
+    assert_eq!(word_at(&program, 3011).address(), 3006);
+    assert_eq!(
+        word_at(&program, 1995),
+        Word::from_byte_values(Sign::PLUS, 6, 9, 19, 22, 23).unwrap()
+    );
+}
+
+#[test]
+fn should_run_assembled_program() {
+    let source = include_str!("programs/primes.mixal");
+    let program = assemble(source).unwrap();
+    let mut computer = Computer::new();
+    computer.load_program(&program).unwrap();
+    computer.start();
+    while computer.running {
+        computer.handle_next_instruction();
+    }
+    assert_eq!(computer.memory.get(0).unwrap().to_i32(), 2);
+    assert_eq!(computer.memory.get(499).unwrap().to_i32(), 3571);
+}
+
+#[test]
+fn should_evaluate_expressions_left_to_right() {
+    let source = " ORIG 100
+X EQU 1+2*3
+Y EQU -1//3
+Z EQU 1:3
+ CON X
+ CON Y
+ CON Z
+ CON *-1
+ CON 1(1:1),2(2:2),-0(0:0)
+ CON -0
+";
+    let program = assemble(source).unwrap();
+    assert_eq!(program.symbol("X"), Some(9));
+    assert_eq!(program.symbol("Y"), Some(-(64_i32.pow(5) / 3)));
+    assert_eq!(program.symbol("Z"), Some(11));
+    assert_eq!(word_at(&program, 103).to_i32(), 102);
+    assert_eq!(
+        word_at(&program, 104),
+        Word::from_byte_values(Sign::MINUS, 1, 2, 0, 0, 0).unwrap()
+    );
+    assert_eq!(word_at(&program, 105), Word::ZERO.with_sign(Sign::MINUS));
+}
+
+#[test]
+fn should_resolve_local_symbols_to_nearest_definition() {
+    let source = "1H NOP
+ JMP 1B
+ JMP 1F
+1H NOP
+ JMP 1B
+";
+    let program = assemble(source).unwrap();
+    assert_eq!(word_at(&program, 1).address(), 0);
+    assert_eq!(word_at(&program, 2).address(), 3);
+    assert_eq!(word_at(&program, 4).address(), 3);
+}
+
+#[test]
+fn should_allocate_words_for_undefined_symbols_after_literals() {
+    let source = " LDA COUNT
+ LDX =7=
+ STA COUNT(1:2)
+ END 0
+";
+    let program = assemble(source).unwrap();
+    assert_eq!(program.start, Some(0));
+    assert_eq!(word_at(&program, 0).address(), 4);
+    assert_eq!(word_at(&program, 1).address(), 3);
+    assert_eq!(word_at(&program, 2).address(), 4);
+    assert_eq!(word_at(&program, 3).to_i32(), 7);
+    assert_eq!(word_at(&program, 4), Word::ZERO);
+    assert_eq!(program.symbol("COUNT"), Some(4));
+}
+
+#[test]
+fn should_report_literals_and_undefined_symbols_outside_memory() {
+    for (origin, count) in [(3900, 99), (3990, 9)] {
+        let mut source = format!(" ORIG {}\n", origin);
+        for i in 0..count {
+            source += &format!(" LDA ={}=\n", i);
+        }
+        // the second literal is the first one past the end of memory
+        match assemble(&source) {
+            Err(AssemblerError::InLine(3, e)) => assert_eq!(
+                e.to_string(),
+                "the literal would be at location 4000, outside memory"
+            ),
+            x => panic!("expected error on line 3, got {:?}", x),
+        }
+    }
+    match assemble(" ORIG 3997\n LDA =1=\n STA COUNT\n") {
+        Err(AssemblerError::InLine(3, e)) => assert_eq!(
+            e.to_string(),
+            "COUNT would be at location 4000, outside memory"
+        ),
+        x => panic!("expected error on line 3, got {:?}", x),
+    }
+    // right up to the end is fine
+    let program = assemble(" ORIG 3996\n LDA =1=\n STA COUNT\n").unwrap();
+    assert_eq!(program.symbol("COUNT"), Some(3999));
+}
+
+#[test]
+fn should_report_line_of_error() {
+    let source = " NOP\n LDA 1+UNKNOWN\n";
+    match assemble(source) {
+        Err(AssemblerError::InLine(2, e)) => {
+            assert!(matches!(*e, AssemblerError::UndefinedSymbol(ref x) if x == "UNKNOWN"))
+        }
+        x => panic!("expected error on line 2, got {:?}", x),
+    }
+    assert!(matches!(
+        assemble("X NOP\nX NOP\n"),
+        Err(AssemblerError::InLine(2, _))
+    ));
+    assert!(matches!(
+        assemble(" HLT 0(3)\n"),
+        Err(AssemblerError::InLine(1, _))
+    ));
+
+    let message = |source: &str| assemble(source).unwrap_err().to_string();
+    assert_eq!(
+        message(" NOP\n ENNA 98765\n"),
+        "line 2: address 98765 doesn't fit in two bytes"
+    );
+    assert_eq!(message(" LDA 1000)\n"), "line 1: Unexpected ')' in address");
+    assert_eq!(message(" LDB 1000\n"), "line 1: unknown op code 'LDB'");
+}
+
+#[test]
+fn should_write_and_read_object_files() {
+    let program = assemble(include_str!("programs/primes.mixal")).unwrap();
+    let mut buffer = Vec::new();
+    program.write_object(&mut buffer).unwrap();
+    let text = String::from_utf8(buffer.clone()).unwrap();
+    assert!(text.starts_with("MIX-OBJECT 1\nSTART 3000\n"));
+    assert!(text.contains("\nWORD 3001 + 32 2 0 5 9\n"));
+
+    let read_back = Program::read_object(buffer.as_slice()).unwrap();
+    assert_eq!(read_back, program);
+}
+
+#[test]
+fn should_reject_bad_object_files() {
+    assert!(matches!(
+        Program::read_object("MIX-OBJECT 2\n".as_bytes()),
+        Err(ObjectError::UnsupportedVersion(2))
+    ));
+    assert!(matches!(
+        Program::read_object("MIX-OBJECT 1\nWORD 1 + 1 2 3\n".as_bytes()),
+        Err(ObjectError::BadLine(2, _))
+    ));
+}
+
+#[test]
+fn should_load_program_into_memory() {
+    let program = assemble(" ORIG 100\nSTART ENTA 5\n HLT\n END START\n").unwrap();
+    let mut computer = Computer::new();
+    computer.load_program(&program).unwrap();
+    assert_eq!(computer.current_instruction_address, 100);
+    computer.start();
+    computer.handle_next_instruction();
+    assert!(!computer.running);
+    assert_eq!(computer.registers.a.to_i32(), 5);
+}
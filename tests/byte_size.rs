@@ -0,0 +1,198 @@
+use mix_emu::assembler::*;
+use mix_emu::computer::devices::*;
+use mix_emu::computer::*;
+use mix_emu::data_types::*;
+use mix_emu::object::Program;
+
+fn run_on(source: &str, byte_size: ByteSize) -> Computer {
+    let options = AssemblerOptions {
+        byte_size,
+        ..Default::default()
+    };
+    let program = assemble_with(source, &options).unwrap();
+    assert_eq!(program.byte_size, byte_size);
+    let mut computer = Computer::with_byte_size(byte_size);
+    computer.devices = Devices::new();
+    computer.devices.line_printer = Some(TextOutput::line_printer());
+    computer.load_program(&program).unwrap();
+    assert_eq!(computer.run(None), Ok(StopReason::HALTED));
+    computer
+}
+
+#[test]
+fn should_print_the_same_primes_on_both_machines() {
+    let source = include_str!("programs/primes.mixal");
+    let binary = run_on(source, ByteSize::BINARY);
+    let decimal = run_on(source, ByteSize::DECIMAL);
+    let lines = |computer: &Computer| {
+        computer
+            .devices
+            .line_printer
+            .as_ref()
+            .unwrap()
+            .lines
+            .clone()
+    };
+    assert_eq!(lines(&binary), lines(&decimal));
+    assert_eq!(binary.time, decimal.time);
+}
+
+#[test]
+fn should_split_numbers_into_bytes_of_the_machines_size() {
+    let source = " ORIG 3000
+START LDA X(5:5)
+ HLT
+X CON 1000
+ END START
+";
+    // 1000 is 15 * 64 + 40 on a binary machine but 10 * 100 on a decimal one, so a program that
+    // picks bytes out of numbers gets a different answer
+    let binary = run_on(source, ByteSize::BINARY);
+    assert_eq!(
+        binary.memory.get(3002).unwrap().to_byte_string(),
+        "+ 0 0 0 15 40"
+    );
+    assert_eq!(binary.registers.a.to_i32(), 40);
+    let decimal = run_on(source, ByteSize::DECIMAL);
+    assert_eq!(
+        decimal.memory.get(3002).unwrap().to_byte_string(),
+        "+ 0 0 0 10 0"
+    );
+    assert_eq!(decimal.registers.a, Word::ZERO);
+}
+
+#[test]
+fn should_overflow_at_the_machines_word_size() {
+    let source = " ORIG 3000
+START LDA BIG
+ ADD BIG
+ HLT
+BIG CON 1000000000
+ END START
+";
+    let binary = run_on(source, ByteSize::BINARY);
+    assert!(binary.overflow);
+    let decimal = run_on(source, ByteSize::DECIMAL);
+    assert!(!decimal.overflow);
+    assert_eq!(
+        decimal.registers.a.to_i64_in(ByteSize::DECIMAL),
+        2_000_000_000
+    );
+}
+
+#[test]
+fn should_convert_characters_and_floating_point_in_decimal() {
+    let computer = run_on(
+        " ORIG 3000
+START LDA =9876543210=
+ CHAR
+ STA 1000
+ STX 1001
+ NUM
+ STA 1002
+ ENTA 1
+ FLOT
+ FDIV THREE
+ HLT
+THREE CON 51(1:1),3(2:2)
+ END START
+",
+        ByteSize::DECIMAL,
+    );
+    let memory = &computer.memory;
+    assert_eq!(
+        memory.get(1000).unwrap().to_byte_string(),
+        "+ 39 38 37 36 35"
+    );
+    assert_eq!(
+        memory.get(1001).unwrap().to_byte_string(),
+        "+ 34 33 32 31 30"
+    );
+    assert_eq!(
+        memory.get(1002).unwrap().to_i64_in(ByteSize::DECIMAL),
+        9_876_543_210
+    );
+    // a third in base 100, with the exponent in excess 50
+    assert_eq!(computer.registers.a.to_byte_string(), "+ 50 33 33 33 33");
+}
+
+#[test]
+fn should_keep_the_byte_size_in_objects_and_snapshots() {
+    let options = AssemblerOptions {
+        byte_size: ByteSize::DECIMAL,
+        ..Default::default()
+    };
+    let program = assemble_with(
+        " ORIG 3999
+START JMP 3999
+ END START
+",
+        &options,
+    )
+    .unwrap();
+    let mut object = Vec::new();
+    program.write_object(&mut object).unwrap();
+    let text = String::from_utf8(object).unwrap();
+    assert!(text.starts_with("MIX-OBJECT 1\nBYTES 100\n"));
+    assert!(text.contains("WORD 3999 + 39 99 0 0 39\n"));
+    let read = Program::read_object(text.as_bytes()).unwrap();
+    assert_eq!(read, program);
+
+    let mut computer = Computer::with_byte_size(ByteSize::DECIMAL);
+    computer.load_program(&program).unwrap();
+    assert_eq!(computer.run(Some(3)), Ok(StopReason::LIMIT));
+    let mut snapshot = Vec::new();
+    computer.save_snapshot(&mut snapshot).unwrap();
+    let text = String::from_utf8(snapshot).unwrap();
+    assert!(text.starts_with("MIX-SNAPSHOT 1\nBYTES 100\n"));
+    assert!(text.contains("J 40 0\n"));
+    let restored = Computer::restore_snapshot(text.as_bytes()).unwrap();
+    assert_eq!(restored.byte_size, ByteSize::DECIMAL);
+    assert_eq!(restored.registers.j.to_i32_in(ByteSize::DECIMAL), 4000);
+    assert_eq!(
+        restored.memory.get(3999).unwrap(),
+        computer.memory.get(3999).unwrap()
+    );
+}
+
+#[test]
+fn should_only_load_programs_for_the_machines_byte_size() {
+    let options = AssemblerOptions {
+        byte_size: ByteSize::DECIMAL,
+        ..Default::default()
+    };
+    let program = assemble_with(" ORIG 3000\nSTART ENTA 99\n HLT\n END START\n", &options).unwrap();
+    let mut computer = Computer::new();
+    let result = computer.load_program(&program);
+    assert_eq!(result, Err(LoadError::ByteSize(ByteSize::DECIMAL)));
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "assembled for 100 values per byte"
+    );
+    // nothing was loaded
+    assert_eq!(computer.memory.get(3000).unwrap(), Word::ZERO);
+}
+
+#[test]
+fn should_decode_large_fields_on_a_decimal_machine() {
+    use mix_emu::opcodes::{decode, Op};
+    let word = Word::from_instruction_parts_in(Sign::PLUS, 0, 0, 70, 1, ByteSize::DECIMAL).unwrap();
+    assert_eq!(decode(word).map(|x| x.op), Some(Op::ADD));
+    let word = Word::from_instruction_parts_in(Sign::PLUS, 0, 0, 99, 5, ByteSize::DECIMAL).unwrap();
+    assert_eq!(decode(word), None);
+}
+
+#[test]
+fn should_only_assemble_binary_instructions_for_binary_mix() {
+    let source = " ORIG 3000\nSTART ENTA 5\n SLB 2\n JAE *+2\n HLT\n JXO *\n HLT\n END START\n";
+    let computer = run_on(source, ByteSize::BINARY);
+    assert_eq!(computer.registers.a.to_i32(), 20);
+    let options = AssemblerOptions {
+        byte_size: ByteSize::DECIMAL,
+        ..Default::default()
+    };
+    assert_eq!(
+        assemble_with(source, &options).unwrap_err().to_string(),
+        "line 3: SLB is only on binary MIX"
+    );
+}
@@ -0,0 +1,20 @@
+// xorshift64, so every run tries the same cases and a failure can be repeated. Each test adds
+// its own ways of picking words, fields or instructions in an impl Random of its own.
+pub struct Random(pub u64);
+
+impl Random {
+    pub fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    // from 0 up to but not including limit, in whatever integer type the test is counting in
+    pub fn below<T: TryFrom<u64> + TryInto<u64>>(&mut self, limit: T) -> T {
+        let limit: u64 = limit.try_into().ok().expect("a positive limit");
+        T::try_from(self.next() % limit)
+            .ok()
+            .expect("less than the limit")
+    }
+}
@@ -0,0 +1,174 @@
+use mix_emu::assembler::*;
+use mix_emu::computer::*;
+use mix_emu::data_types::*;
+use mix_emu::object::*;
+
+fn assemble_defining(source: &str, defines: &[(&str, i64)]) -> Result<Program, AssemblerError> {
+    let options = AssemblerOptions {
+        defines: defines.iter().map(|(x, y)| (x.to_string(), *y)).collect(),
+        ..Default::default()
+    };
+    assemble_with(source, &options)
+}
+
+fn error(source: &str) -> String {
+    match assemble(source) {
+        Ok(_) => panic!("expected an error"),
+        Err(e) => e.to_string(),
+    }
+}
+
+fn run(program: &Program) -> Computer {
+    let mut computer = Computer::with_byte_size(program.byte_size);
+    computer.load_program(program).unwrap();
+    assert_eq!(computer.run(Some(1000)), Ok(StopReason::HALTED));
+    computer
+}
+
+// the largest number two bytes hold, whichever byte size the program is assembled for
+const TWO_BYTES: &str = " ORIG 3000
+ IFDEF DECIMAL
+START ENTA 9999
+ ELSE
+START ENTA 4095
+ ENDIF
+ HLT
+ END START
+";
+
+#[test]
+fn should_choose_lines_by_what_is_defined() {
+    let binary = assemble_defining(TWO_BYTES, &[]).unwrap();
+    assert_eq!(run(&binary).registers.a.to_i32(), 4095);
+    let options = AssemblerOptions {
+        byte_size: ByteSize::DECIMAL,
+        defines: vec![("DECIMAL".to_string(), 1)],
+        ..Default::default()
+    };
+    let decimal = assemble_with(TWO_BYTES, &options).unwrap();
+    assert_eq!(run(&decimal).registers.a.to_i64_in(ByteSize::DECIMAL), 9999);
+    // defines are symbols like any other
+    assert_eq!(decimal.symbol("DECIMAL"), Some(1));
+}
+
+#[test]
+fn should_choose_lines_by_expressions() {
+    let source = "SIZE EQU 10
+LIMIT EQU SIZE*2
+ ORIG 3000
+ IF LIMIT-20
+START ENTA 1
+ ELSE
+ IF DEBUG
+START ENTA 2
+ ELSE
+START ENTA 3
+ ENDIF
+ ENDIF
+ HLT
+ END START
+";
+    let program = assemble_defining(source, &[("DEBUG", 1)]).unwrap();
+    assert_eq!(run(&program).registers.a.to_i32(), 2);
+    let program = assemble_defining(source, &[("DEBUG", 0)]).unwrap();
+    assert_eq!(run(&program).registers.a.to_i32(), 3);
+    let program = assemble_defining(source, &[("DEBUG", 0), ("SIZE", 1)]);
+    assert_eq!(
+        program.unwrap_err().to_string(),
+        "line 1: symbol SIZE is already defined"
+    );
+}
+
+#[test]
+fn should_leave_out_everything_in_a_false_condition() {
+    let program = assemble(
+        " IFDEF NEVER
+BROKEN MACRO
+ IF NOWHERE
+ ENDM
+ INCLUDE \"missing.mixal\"
+ IF *
+ LDA UNDEFINED
+ ENDIF
+ ENDIF
+ ORIG 3000
+ IFNDEF NEVER
+START HLT
+ ENDIF
+ END START
+",
+    )
+    .unwrap();
+    assert_eq!(program.words.len(), 1);
+    assert_eq!(program.symbol("START"), Some(3000));
+}
+
+#[test]
+fn should_decide_conditions_in_macros_each_time_they_are_used() {
+    let program = assemble(
+        "PLUS MACRO N
+ IF N-1
+ INCA N
+ ELSE
+ INCA 1
+ INCA 0
+ ENDIF
+ ENDM
+ ORIG 3000
+START PLUS 5
+ PLUS 1
+ HLT
+ END START
+",
+    )
+    .unwrap();
+    assert_eq!(program.words.len(), 4);
+    assert_eq!(run(&program).registers.a.to_i32(), 6);
+}
+
+#[test]
+fn should_report_unbalanced_conditions() {
+    assert_eq!(error(" IF 1\n NOP\n"), "line 1: IF has no ENDIF");
+    assert_eq!(error(" NOP\n ENDIF\n"), "line 2: ENDIF without IF");
+    assert_eq!(error(" ELSE\n"), "line 1: ELSE without IF");
+    assert_eq!(
+        error(" IF 0\n ELSE\n ELSE\n ENDIF\n"),
+        "line 3: IF has more than one ELSE"
+    );
+    assert_eq!(
+        error("A MACRO\n NOP\n IF 1\n ENDM\n A\n"),
+        "line 5: macro A line 3: IF has no ENDIF"
+    );
+    // a macro can't end a condition it didn't start
+    assert_eq!(
+        error("A MACRO\n ENDIF\n ENDM\n IF 1\n A\n ENDIF\n"),
+        "line 5: macro A line 2: ENDIF without IF"
+    );
+}
+
+#[test]
+fn should_report_bad_conditions() {
+    assert_eq!(
+        error(" IF *\n ENDIF\n"),
+        "line 1: * isn't known until the program is assembled"
+    );
+    // only symbols defined before the IF can be used
+    assert_eq!(
+        error(" IF LATER\n ENDIF\nLATER EQU 1\n"),
+        "line 1: undefined symbol LATER"
+    );
+    assert_eq!(error("X IF 1\n ENDIF\n"), "line 1: IF can't have a LOC");
+    assert_eq!(
+        error(" IFDEF 1+2\n ENDIF\n"),
+        "line 1: IFDEF takes a symbol"
+    );
+}
+
+#[test]
+fn should_parse_command_line_definitions() {
+    assert_eq!(parse_define("DEBUG"), Some(("DEBUG".to_string(), 1)));
+    assert_eq!(parse_define("SIZE=-20"), Some(("SIZE".to_string(), -20)));
+    assert_eq!(parse_define("SIZE=big"), None);
+    assert_eq!(parse_define("2H=1"), None);
+    assert_eq!(parse_define("A B"), None);
+}
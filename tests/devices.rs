@@ -0,0 +1,191 @@
+use mix_emu::assembler::*;
+use mix_emu::computer::devices::*;
+use mix_emu::computer::*;
+use mix_emu::data_types::*;
+
+fn computer_with(source: &str) -> Computer {
+    let program = assemble(source).unwrap();
+    let mut computer = Computer::new();
+    computer.devices = Devices::new();
+    computer.load_program(&program).unwrap();
+    computer
+}
+
+#[test]
+fn should_copy_cards_to_the_line_printer() {
+    let mut computer = computer_with(
+        "BUF EQU 1000
+ ORIG 3000
+START IN BUF(16)
+ OUT BUF(18)
+ IN BUF(16)
+ OUT BUF(18)
+ HLT
+ END START
+",
+    );
+    computer.devices.card_reader = Some(TextInput::card_reader(vec![
+        "HELLO, WORLD".to_string(),
+        "  12345".to_string(),
+    ]));
+    computer.devices.line_printer = Some(TextOutput::line_printer());
+
+    assert_eq!(computer.run(None), Ok(StopReason::HALTED));
+    let printer = computer.devices.line_printer.as_ref().unwrap();
+    assert_eq!(printer.lines, vec!["HELLO, WORLD", "  12345"]);
+    assert_eq!(computer.instruction_count, 5);
+    assert_eq!(computer.time, 14);
+}
+
+#[test]
+fn should_print_the_primes() {
+    let program = assemble(include_str!("programs/primes.mixal")).unwrap();
+    let mut computer = Computer::new();
+    computer.devices.line_printer = Some(TextOutput::line_printer());
+    computer.load_program(&program).unwrap();
+
+    assert_eq!(computer.run(None), Ok(StopReason::HALTED));
+    let lines = &computer.devices.line_printer.as_ref().unwrap().lines;
+    // IOC 0(18) to start a new page, the title then 50 lines of primes
+    assert_eq!(lines.len(), 52);
+    assert_eq!(lines[0], "");
+    assert_eq!(lines[1], "FIRST FIVE HUNDRED PRIMES");
+    assert_eq!(
+        lines[2],
+        "     0002 0233 0547 0877 1229 1597 1993 2371 2749 3187"
+    );
+}
+
+#[test]
+fn should_rewind_and_reread_tapes() {
+    let mut computer = computer_with(
+        " ORIG 3000
+START OUT 0(3)
+ OUT 100(3)
+ IOC 0(3)
+ IOC 1(3)
+ IN 1000(3)
+ IOC -2(3)
+ IN 2000(3)
+ HLT
+ END START
+",
+    );
+    computer.memory.set(0, Word::from_i32(11).unwrap()).unwrap();
+    computer
+        .memory
+        .set(100, Word::from_i32(22).unwrap())
+        .unwrap();
+    computer.devices.tapes[3] = Some(TapeUnit::new(2));
+
+    assert_eq!(computer.run(None), Ok(StopReason::HALTED));
+    assert_eq!(computer.memory.get(1000).unwrap().to_i32(), 22);
+    assert_eq!(computer.memory.get(2000).unwrap().to_i32(), 11);
+    assert_eq!(computer.devices.tapes[3].as_ref().unwrap().position, 1);
+}
+
+#[test]
+fn should_stop_skipping_back_at_the_start_of_the_tape() {
+    let mut computer = computer_with(
+        " ORIG 3000
+START OUT 0(3)
+ IOC -5(3)
+ IN 1000(3)
+ HLT
+ END START
+",
+    );
+    computer.memory.set(0, Word::from_i32(11).unwrap()).unwrap();
+    computer.devices.tapes[3] = Some(TapeUnit::new(2));
+
+    assert_eq!(computer.run(None), Ok(StopReason::HALTED));
+    assert_eq!(computer.memory.get(1000).unwrap().to_i32(), 11);
+    assert_eq!(computer.devices.tapes[3].as_ref().unwrap().position, 1);
+}
+
+#[test]
+fn should_use_rx_as_disk_position() {
+    let mut computer = computer_with(
+        " ORIG 3000
+START ENTX 7
+ OUT 0(9)
+ IN 1000(9)
+ HLT
+ END START
+",
+    );
+    computer
+        .memory
+        .set(99, Word::from_i32(-5).unwrap())
+        .unwrap();
+    computer.devices.disks[1] = Some(DiskUnit::new(8));
+
+    assert_eq!(computer.run(None), Ok(StopReason::HALTED));
+    let disk = computer.devices.disks[1].as_ref().unwrap();
+    assert_eq!(disk.data[7][99].to_i32(), -5);
+    assert_eq!(computer.memory.get(1099).unwrap().to_i32(), -5);
+}
+
+#[test]
+fn should_jump_on_busy_and_ready() {
+    let mut computer = computer_with(
+        " ORIG 3000
+START JBUS BAD(0)
+ JRED OK(0)
+BAD HLT
+OK ENTA 1
+ HLT
+ END START
+",
+    );
+    computer.devices.tapes[0] = Some(TapeUnit::new(1));
+    assert_eq!(computer.run(None), Ok(StopReason::HALTED));
+    assert_eq!(computer.registers.a.to_i32(), 1);
+}
+
+#[test]
+fn should_fault_on_missing_devices_and_input() {
+    let mut computer = computer_with(
+        " ORIG 3000
+START IN 1000(16)
+ IN 1000(16)
+ END START
+",
+    );
+    let fault = computer.run(None).unwrap_err();
+    assert_eq!(fault.address, 3000);
+    assert_eq!(
+        fault.kind,
+        FaultKind::Device(16, DeviceError::NotAttached(16))
+    );
+    assert!(!computer.running);
+
+    let mut computer = computer_with(
+        " ORIG 3000
+START IN 1000(16)
+ IN 1000(16)
+ END START
+",
+    );
+    computer.devices.card_reader = Some(TextInput::card_reader(vec!["ONE".to_string()]));
+    let fault = computer.run(None).unwrap_err();
+    assert_eq!(fault.address, 3001);
+    assert_eq!(fault.kind, FaultKind::Device(16, DeviceError::EndOfInput));
+    assert_eq!(
+        fault.to_string(),
+        "fault at 3001 (<input>:3 START+1): unit 16: no input left"
+    );
+}
+
+#[test]
+fn should_stop_at_the_instruction_limit() {
+    let mut computer = computer_with(
+        " ORIG 3000
+START JMP START
+ END START
+",
+    );
+    assert_eq!(computer.run(Some(10)), Ok(StopReason::LIMIT));
+    assert_eq!(computer.instruction_count, 10);
+    assert_eq!(computer.time, 10);
+}
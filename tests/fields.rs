@@ -0,0 +1,154 @@
+mod common;
+
+use common::Random;
+use mix_emu::data_types::*;
+
+// Property tests for Word::get_field and Word::with_field. Words are random, from a fixed seed so
+// failures repeat, and the answers are checked against plain arithmetic on the magnitude: byte i
+// of a word is (|w| / b^(5-i)) % b.
+
+const ROUNDS: usize = 2000;
+
+impl Random {
+    fn word(&mut self, size: ByteSize) -> Word {
+        let sign = match self.next() % 2 {
+            0 => Sign::PLUS,
+            _ => Sign::MINUS,
+        };
+        // zero bytes are common in real words and make the edges more interesting
+        let values = [0; 5].map(|_| match self.next() % 4 {
+            0 => 0,
+            _ => self.below(size.values()),
+        });
+        Word::from_byte_values_in(sign, values, size).unwrap()
+    }
+
+    fn field(&mut self) -> FieldSpecification {
+        let left = self.below(6);
+        let right = left + self.below(6 - left);
+        FieldSpecification { left, right }
+    }
+}
+
+fn magnitude(word: Word, size: ByteSize) -> i64 {
+    word.to_i64_in(size).abs()
+}
+
+fn byte_of(magnitude: i64, index: i32, size: ByteSize) -> i64 {
+    magnitude / size.capacity(5 - index as u32) % size.values() as i64
+}
+
+// how many bytes (not counting the sign) a field covers
+fn length(field: FieldSpecification) -> i32 {
+    match field.right {
+        0 => 0,
+        right => right - field.left.max(1) + 1,
+    }
+}
+
+fn reference_get(word: Word, field: FieldSpecification, size: ByteSize) -> (Sign, i64) {
+    let sign = match field.left {
+        0 => word.sign,
+        _ => Sign::PLUS,
+    };
+    let value = magnitude(word, size) / size.capacity(5 - field.right as u32)
+        % size.capacity(length(field) as u32);
+    (sign, value)
+}
+
+fn reference_with(word: Word, field: FieldSpecification, value: Word, size: ByteSize) -> Word {
+    let (word_magnitude, value_magnitude) = (magnitude(word, size), magnitude(value, size));
+    let mut bytes = [0; 5];
+    for index in 1..=5 {
+        bytes[index as usize - 1] = match field.left.max(1) <= index && index <= field.right {
+            // the field's last byte comes from the value's last byte and so on leftwards
+            true => byte_of(value_magnitude, 5 - (field.right - index), size),
+            false => byte_of(word_magnitude, index, size),
+        } as i32;
+    }
+    let sign = match field.left {
+        0 => value.sign,
+        _ => word.sign,
+    };
+    Word::from_byte_values_in(sign, bytes, size).unwrap()
+}
+
+fn each_case(seed: u64, mut check: impl FnMut(&mut Random, ByteSize)) {
+    for size in [ByteSize::BINARY, ByteSize::DECIMAL] {
+        let mut random = Random(seed);
+        for _ in 0..ROUNDS {
+            check(&mut random, size);
+        }
+    }
+}
+
+#[test]
+fn should_get_fields_like_the_reference() {
+    each_case(0x5eed_0001, |random, size| {
+        let (word, field) = (random.word(size), random.field());
+        let result = word.get_field(field);
+        let (sign, value) = reference_get(word, field, size);
+        // compare the sign separately so a -0 result is checked too
+        assert_eq!(result.sign, sign, "{} ({:?})", word.to_byte_string(), field);
+        assert_eq!(
+            magnitude(result, size),
+            value,
+            "{} ({:?})",
+            word.to_byte_string(),
+            field
+        );
+    });
+}
+
+#[test]
+fn should_set_fields_like_the_reference() {
+    each_case(0x5eed_0002, |random, size| {
+        let (word, value, field) = (random.word(size), random.word(size), random.field());
+        assert_eq!(
+            word.with_field(field, value),
+            reference_with(word, field, value, size),
+            "{} ({:?}) from {}",
+            word.to_byte_string(),
+            field,
+            value.to_byte_string()
+        );
+    });
+}
+
+#[test]
+fn should_leave_a_word_alone_when_setting_a_field_to_itself() {
+    each_case(0x5eed_0003, |random, size| {
+        let (word, field) = (random.word(size), random.field());
+        assert_eq!(word.with_field(field, word.get_field(field)), word);
+    });
+}
+
+#[test]
+fn should_get_back_what_was_set() {
+    each_case(0x5eed_0004, |random, size| {
+        let (word, value, field) = (random.word(size), random.word(size), random.field());
+        let result = word.with_field(field, value).get_field(field);
+        let expected_sign = match field.left {
+            0 => value.sign,
+            _ => Sign::PLUS,
+        };
+        assert_eq!(result.sign, expected_sign);
+        assert_eq!(
+            magnitude(result, size),
+            magnitude(value, size) % size.capacity(length(field) as u32)
+        );
+    });
+}
+
+#[test]
+fn should_treat_the_whole_word_and_the_sign_alone_as_edges() {
+    let word = Word::from_byte_values(Sign::MINUS, 1, 2, 3, 4, 5).unwrap();
+    let value = Word::from_byte_values(Sign::PLUS, 6, 7, 8, 9, 10).unwrap();
+    let whole = FieldSpecification { left: 0, right: 5 };
+    let sign = FieldSpecification { left: 0, right: 0 };
+    assert_eq!(word.get_field(whole), word);
+    assert_eq!(word.with_field(whole, value), value);
+    // (0:0) is a minus zero when the word is negative
+    assert_eq!(word.get_field(sign), Word::ZERO.with_sign(Sign::MINUS));
+    assert_eq!(word.with_field(sign, value), word.with_sign(Sign::PLUS));
+}
@@ -0,0 +1,203 @@
+use mix_emu::assembler::*;
+use mix_emu::computer::*;
+use mix_emu::data_types::*;
+
+// a floating point word, e is the stored exponent so 32 means b^0
+fn float(sign: Sign, e: i32, f1: i32, f2: i32, f3: i32, f4: i32) -> Word {
+    Word::from_byte_values(sign, e, f1, f2, f3, f4).unwrap()
+}
+
+fn run_with(a: Word, code: i32, field: i32, v: Word) -> Computer {
+    let mut computer = Computer::new();
+    computer.registers.a = a;
+    computer.memory.set(1000, v).unwrap();
+    computer.handle_instruction(
+        Word::from_instruction_parts(Sign::PLUS, 1000, 0, field, code).unwrap(),
+    );
+    computer
+}
+
+fn fadd(a: Word, v: Word) -> Computer {
+    run_with(a, 1, 6, v)
+}
+
+const ONE: (i32, i32) = (33, 1);
+
+fn one() -> Word {
+    float(Sign::PLUS, ONE.0, ONE.1, 0, 0, 0)
+}
+
+#[test]
+fn should_add_and_subtract() {
+    let computer = fadd(one(), one());
+    assert_eq!(computer.registers.a, float(Sign::PLUS, 33, 2, 0, 0, 0));
+    assert!(!computer.overflow);
+
+    // 1 - 1.5 = -0.5, which normalizes down an exponent
+    let computer = run_with(one(), 2, 6, float(Sign::PLUS, 33, 1, 32, 0, 0));
+    assert_eq!(computer.registers.a, float(Sign::MINUS, 32, 32, 0, 0, 0));
+
+    let computer = run_with(one(), 2, 6, one());
+    assert_eq!(computer.registers.a, Word::ZERO);
+}
+
+#[test]
+fn should_round_to_nearest_and_ties_to_even() {
+    // half a unit in the last place of 1.0
+    let half = float(Sign::PLUS, 29, 32, 0, 0, 0);
+    assert_eq!(fadd(one(), half).registers.a, one());
+    assert_eq!(
+        fadd(float(Sign::PLUS, 33, 1, 0, 0, 1), half).registers.a,
+        float(Sign::PLUS, 33, 1, 0, 0, 2)
+    );
+    // a little more than half rounds up
+    let more = float(Sign::PLUS, 29, 32, 0, 0, 1);
+    assert_eq!(
+        fadd(one(), more).registers.a,
+        float(Sign::PLUS, 33, 1, 0, 0, 1)
+    );
+    // something far too small to matter
+    let tiny = float(Sign::PLUS, 1, 1, 0, 0, 0);
+    assert_eq!(fadd(one(), tiny).registers.a, one());
+    assert_eq!(run_with(one(), 2, 6, tiny).registers.a, one());
+}
+
+#[test]
+fn should_multiply_and_divide() {
+    let two = float(Sign::PLUS, 33, 2, 0, 0, 0);
+    let three = float(Sign::MINUS, 33, 3, 0, 0, 0);
+    let computer = run_with(two, 3, 6, three);
+    assert_eq!(computer.registers.a, float(Sign::MINUS, 33, 6, 0, 0, 0));
+
+    // one third is 0.252525... in base 64
+    let computer = run_with(one(), 4, 6, float(Sign::PLUS, 33, 3, 0, 0, 0));
+    assert_eq!(computer.registers.a, float(Sign::PLUS, 32, 21, 21, 21, 21));
+    assert!(!computer.overflow);
+}
+
+#[test]
+fn should_set_overflow_when_exponent_leaves_a_byte() {
+    let big = float(Sign::PLUS, 63, 1, 0, 0, 0);
+    let computer = run_with(big, 3, 6, big);
+    assert!(computer.overflow);
+    // b^30 * b^30 = b^60, which needs an exponent of 93, wrapped to 29
+    assert_eq!(computer.registers.a, float(Sign::PLUS, 29, 1, 0, 0, 0));
+
+    let small = float(Sign::PLUS, 1, 1, 0, 0, 0);
+    let computer = run_with(small, 3, 6, small);
+    assert!(computer.overflow);
+
+    let computer = run_with(one(), 4, 6, Word::ZERO);
+    assert!(computer.overflow);
+    assert_eq!(computer.registers.a, one());
+}
+
+#[test]
+fn should_convert_integers_to_floating_point() {
+    // 1000 = 15 * 64 + 40
+    let computer = run_with(Word::from_i32(-1000).unwrap(), 5, 6, Word::ZERO);
+    assert_eq!(computer.registers.a, float(Sign::MINUS, 34, 15, 40, 0, 0));
+
+    // five significant bytes have to be rounded to four
+    let computer = run_with(
+        Word::from_byte_values(Sign::PLUS, 1, 0, 0, 1, 32).unwrap(),
+        5,
+        6,
+        Word::ZERO,
+    );
+    assert_eq!(computer.registers.a, float(Sign::PLUS, 37, 1, 0, 0, 2));
+
+    let computer = run_with(Word::ZERO, 5, 6, Word::ZERO);
+    assert_eq!(computer.registers.a, Word::ZERO);
+}
+
+#[test]
+fn should_convert_floating_point_to_integers() {
+    let fix = |a: Word| run_with(a, 5, 7, Word::ZERO);
+    // 2.5 and 3.5 go to the even neighbour
+    assert_eq!(
+        fix(float(Sign::PLUS, 33, 2, 32, 0, 0)).registers.a.to_i32(),
+        2
+    );
+    assert_eq!(
+        fix(float(Sign::PLUS, 33, 3, 32, 0, 0)).registers.a.to_i32(),
+        4
+    );
+    assert_eq!(
+        fix(float(Sign::MINUS, 34, 15, 40, 0, 0))
+            .registers
+            .a
+            .to_i32(),
+        -1000
+    );
+    assert_eq!(
+        fix(float(Sign::PLUS, 20, 1, 0, 0, 0)).registers.a,
+        Word::ZERO
+    );
+
+    let computer = fix(float(Sign::PLUS, 38, 1, 0, 0, 0));
+    assert!(computer.overflow);
+}
+
+#[test]
+fn should_compare_within_epsilon_from_location_zero() {
+    let next = float(Sign::PLUS, 33, 1, 0, 0, 1);
+    let fcmp = |epsilon: Word, a: Word, v: Word| {
+        let mut computer = Computer::new();
+        computer.memory.set(0, epsilon).unwrap();
+        computer.registers.a = a;
+        computer.memory.set(1000, v).unwrap();
+        computer
+            .handle_instruction(Word::from_instruction_parts(Sign::PLUS, 1000, 0, 6, 56).unwrap());
+        computer.comparison_indicator.unwrap()
+    };
+    assert_eq!(
+        fcmp(Word::ZERO, one(), next),
+        ComparisonIndicatorState::LESS
+    );
+    assert_eq!(
+        fcmp(Word::ZERO, next, one()),
+        ComparisonIndicatorState::GREATER
+    );
+    assert_eq!(
+        fcmp(Word::ZERO, one(), one()),
+        ComparisonIndicatorState::EQUAL
+    );
+
+    // epsilon of b^-3 allows differences up to b^-3 * b^1
+    let epsilon = float(Sign::PLUS, 30, 1, 0, 0, 0);
+    assert_eq!(fcmp(epsilon, one(), next), ComparisonIndicatorState::EQUAL);
+    let far = float(Sign::PLUS, 33, 1, 0, 1, 1);
+    assert_eq!(fcmp(epsilon, one(), far), ComparisonIndicatorState::LESS);
+}
+
+#[test]
+fn should_assemble_and_time_floating_point_programs() {
+    let program = assemble(
+        " ORIG 3000
+START ENTA 7
+ FLOT
+ FMUL HALF
+ FADD HALF
+ FSUB HALF
+ FDIV HALF
+ FCMP SEVEN
+ FIX
+ HLT
+HALF CON 32(1:1),32(2:2)
+SEVEN CON 33(1:1),7(2:2)
+ END START
+",
+    )
+    .unwrap();
+    let mut computer = Computer::new();
+    computer.load_program(&program).unwrap();
+    assert_eq!(computer.run(None), Ok(StopReason::HALTED));
+    assert_eq!(computer.registers.a.to_i32(), 7);
+    assert_eq!(
+        computer.comparison_indicator,
+        Some(ComparisonIndicatorState::EQUAL)
+    );
+    // ENTA 1, FLOT 3, FMUL 9, FADD 4, FSUB 4, FDIV 11, FCMP 4, FIX 3, HLT 10
+    assert_eq!(computer.time, 49);
+}
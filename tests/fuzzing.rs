@@ -0,0 +1,214 @@
+mod common;
+
+use common::Random;
+use mix_emu::assembler::{self, AssemblerOptions};
+use mix_emu::computer::*;
+use mix_emu::data_types::*;
+use mix_emu::fuzzing::run_arbitrary;
+
+// The same checks as the targets in fuzz/, on inputs from a fixed seed so they run with the rest
+// of the tests. `cargo fuzz` explores far more, these catch the obvious regressions.
+
+const ROUNDS: usize = 2000;
+
+// what MIXAL gives a meaning to, so mutations make lines that nearly assemble
+const PUNCTUATION: &[u8] = b" ,()=*+-/:'\"0123456789ABHFLT#\n\t";
+
+// a bit of everything the assembler understands that primes doesn't use
+const FEATURES: &str = "ADDTO MACRO REG,N
+ INC#REG N
+ ENDM
+SIZE EQU 2*5+1
+ ORIG 3000
+START ENT1 SIZE
+1H ADDTO A,=3-1=
+ DEC1 1
+ J1P 1B
+ JMP 2F
+2H STA BUF+1,1(0:5)
+ LDX =-7*2=
+ MOVE BUF(SIZE)
+ HLT
+BUF CON 1(1:1),-2(4:5)
+ ALF \"A B C\"
+ END START
+";
+
+// mostly instructions with a real index register and a small field, which get further than
+// random bytes before faulting, and now and then a jump to a negative location, which has to fault
+// even when the timer is about to interrupt
+fn memory_image(random: &mut Random) -> Vec<u8> {
+    let mut data = vec![random.next() as u8];
+    for _ in 0..random.below(200) {
+        let [sign, a1, a2, _, _, _, _, code] = random.next().to_le_bytes();
+        let index = random.below(8) as u8;
+        let field = random.below(14) as u8;
+        match random.below(10) {
+            0 => data.extend([1, a1 % 64, a2, 0, 0, 39]),
+            _ => data.extend([sign, a1 % 64, a2, index, field, code]),
+        }
+    }
+    data
+}
+
+// literals and undefined symbols go after the last word, so near the end of memory there can be
+// more of them than fit
+fn literal_pool(random: &mut Random) -> String {
+    let mut source = format!(" ORIG {}\n", 3800 + random.below(200));
+    for idx in 0..random.below(250) {
+        match random.below(2) {
+            0 => source += &format!(" LDA ={}=\n", idx),
+            _ => source += &format!(" STA U{}\n", idx),
+        }
+    }
+    source
+}
+
+#[test]
+fn should_survive_arbitrary_memory_images() {
+    let mut random = Random(0x2545f4914f6cdd1d);
+    let mut faulted = 0;
+    for _ in 0..ROUNDS {
+        let (_, result) = run_arbitrary(&memory_image(&mut random), 1000);
+        if result.is_err() {
+            faulted += 1;
+        }
+    }
+    // it isn't only faulting on the first instruction
+    assert!(faulted < ROUNDS);
+    // and an empty image is a machine full of NOPs
+    let (computer, _) = run_arbitrary(&[], 100);
+    assert_eq!(computer.instruction_count, 100);
+    assert_eq!(computer.byte_size, ByteSize::BINARY);
+}
+
+#[test]
+fn should_survive_arbitrary_source() {
+    let mut random = Random(0x9e3779b97f4a7c15);
+    for _ in 0..ROUNDS {
+        let program = match random.below(3) {
+            0 => include_str!("programs/primes.mixal").to_string(),
+            1 => FEATURES.to_string(),
+            _ => literal_pool(&mut random),
+        };
+        let mut source: Vec<char> = program.chars().collect();
+        for _ in 0..random.below(9) {
+            let at = random.below(source.len());
+            match random.below(4) {
+                0 => {
+                    source.remove(at);
+                }
+                1 => source.insert(at, PUNCTUATION[random.below(PUNCTUATION.len())] as char),
+                2 => {
+                    let other = random.below(source.len());
+                    source.swap(at, other);
+                }
+                _ => source.insert(
+                    at,
+                    char::from_u32(random.below(0x3000) as u32).unwrap_or('?'),
+                ),
+            }
+        }
+        let source: String = source.into_iter().collect();
+        for byte_size in [ByteSize::BINARY, ByteSize::DECIMAL] {
+            let options = AssemblerOptions {
+                byte_size,
+                ..Default::default()
+            };
+            // anything that assembles can be loaded
+            if let Ok(program) = assembler::assemble_with(&source, &options) {
+                let mut computer = Computer::with_byte_size(byte_size);
+                computer.enable_interrupts();
+                assert!(computer.load_program(&program).is_ok(), "{}", source);
+            }
+        }
+    }
+}
+
+// runs one instruction at 0
+fn fault(instruction: Word, setup: impl FnOnce(&mut Computer)) -> Option<FaultKind> {
+    let mut computer = Computer::new();
+    computer.memory.set(0, instruction).unwrap();
+    setup(&mut computer);
+    computer.run(Some(1)).err().map(|x| x.kind)
+}
+
+// without the checks on each part, so the index can be out of range
+fn instruction(sign: Sign, address: i32, index: i32, field: i32, code: i32) -> Word {
+    let byte = |x: i32| Byte::from_i32(x).unwrap();
+    Word {
+        sign,
+        bytes: (
+            byte(address / 64),
+            byte(address % 64),
+            byte(index),
+            byte(field),
+            byte(code),
+        ),
+    }
+}
+
+#[test]
+fn should_fault_on_operands_that_used_to_panic() {
+    // LDA 0,7
+    let lda = instruction(Sign::PLUS, 0, 7, 5, 8);
+    assert_eq!(fault(lda, |_| ()), Some(FaultKind::InvalidInstruction(lda)));
+    // LDA 0(2:1)
+    let lda = instruction(Sign::PLUS, 0, 0, 17, 8);
+    assert_eq!(fault(lda, |_| ()), Some(FaultKind::InvalidInstruction(lda)));
+    // LDA 4000 on a machine without negative locations either
+    let lda = instruction(Sign::PLUS, 4000, 0, 5, 8);
+    assert_eq!(fault(lda, |_| ()), Some(FaultKind::InvalidAddress(4000)));
+    // SLA -1
+    let sla = instruction(Sign::MINUS, 1, 0, 0, 6);
+    assert_eq!(fault(sla, |_| ()), Some(FaultKind::InvalidInstruction(sla)));
+}
+
+#[test]
+fn should_fault_when_an_index_register_overflows() {
+    // ENT1 4000,1 with rI1 = 100
+    let ent1 = instruction(Sign::PLUS, 4000, 1, 2, 49);
+    let overflow = fault(ent1, |x| x.registers.i1 = Index::from_i32(100).unwrap());
+    assert_eq!(overflow, Some(FaultKind::IndexOverflow(4100)));
+    // INC2 1 with rI2 = 4095
+    let inc2 = instruction(Sign::PLUS, 1, 0, 0, 50);
+    let overflow = fault(inc2, |x| x.registers.i2 = Index::from_i32(4095).unwrap());
+    assert_eq!(overflow, Some(FaultKind::IndexOverflow(4096)));
+    // DEC3 1 with rI3 = -4095
+    let dec3 = instruction(Sign::PLUS, 1, 0, 1, 51);
+    let overflow = fault(dec3, |x| x.registers.i3 = Index::from_i32(-4095).unwrap());
+    assert_eq!(overflow, Some(FaultKind::IndexOverflow(-4096)));
+    // DEC1 1,2 with rI1 = -4000 and rI2 = 100, which subtracts all of M = 101
+    let dec1 = instruction(Sign::PLUS, 1, 2, 1, 49);
+    let overflow = fault(dec1, |x| {
+        x.registers.i1 = Index::from_i32(-4000).unwrap();
+        x.registers.i2 = Index::from_i32(100).unwrap();
+    });
+    assert_eq!(overflow, Some(FaultKind::IndexOverflow(-4101)));
+    // LD4N 1 with 3 bytes at 1, though LD4N 1(4:5) is fine
+    let ld4n = instruction(Sign::PLUS, 1, 0, 5, 20);
+    let large = |x: &mut Computer| x.memory.set(1, Word::from_i32(4096).unwrap()).unwrap();
+    assert_eq!(fault(ld4n, large), Some(FaultKind::IndexOverflow(4096)));
+    let ld4n = instruction(Sign::PLUS, 1, 0, 37, 20);
+    assert_eq!(fault(ld4n, large), None);
+}
+
+// what fuzzing found before, each of which used to panic
+#[test]
+fn should_fault_on_inputs_that_used_to_panic() {
+    // JMP -5 with interrupts on and the timer at -10 running out during it
+    let (_, result) = run_arbitrary(&[1 << 2 | 2, 1, 0, 5, 0, 0, 39], 10);
+    assert_eq!(result.unwrap_err().kind, FaultKind::InvalidAddress(-5));
+
+    // a literal pool that runs past the end of memory, by a lot or only just
+    for (origin, count) in [(3900, 99), (3990, 9)] {
+        let mut source = format!(" ORIG {}\n", origin);
+        for idx in 0..count {
+            source += &format!(" LDA ={}=\n", idx);
+        }
+        assert!(matches!(
+            assembler::assemble(&source),
+            Err(assembler::AssemblerError::InLine(3, _))
+        ));
+    }
+}
@@ -0,0 +1,161 @@
+use mix_emu::computer::*;
+use mix_emu::grader::*;
+
+// prints the first card twice
+const ECHO: &str = " ORIG 3000
+START IN 1000(16)
+ JBUS *(16)
+ OUT 1000(18)
+ OUT 1000(18)
+ HLT
+ END START
+";
+
+fn assignment() -> Assignment {
+    Assignment {
+        cards: vec!["HELLO".to_string()],
+        expected_printer: Some(vec!["HELLO".to_string(), "HELLO".to_string()]),
+        instruction_limit: Some(1000),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn should_pass_programs_with_the_expected_output() {
+    let report = grade_source(&assignment(), "echo", ECHO);
+    assert_eq!(report.outcome, Outcome::PASSED);
+    assert_eq!(report.details, None);
+    assert_eq!(report.instructions, 5);
+    assert!(report.time > 0);
+}
+
+#[test]
+fn should_say_where_the_output_is_wrong() {
+    let source = ECHO.replacen(" OUT 1000(18)\n", "", 1);
+    let report = grade_source(&assignment(), "once", &source);
+    assert_eq!(report.outcome, Outcome::INCORRECT);
+    assert_eq!(report.details.unwrap(), "printer expected 2 lines, got 1");
+
+    let mut assignment = assignment();
+    assignment.expected_punch = Some(vec!["HELLO".to_string()]);
+    let report = grade_source(&assignment, "echo", ECHO);
+    assert_eq!(report.outcome, Outcome::INCORRECT);
+    assert_eq!(report.details.unwrap(), "punch expected 1 lines, got 0");
+
+    assignment.expected_printer = Some(vec!["HELLO".to_string(), "WORLD".to_string()]);
+    let report = grade_source(&assignment, "echo", ECHO);
+    assert_eq!(
+        report.details.unwrap(),
+        "printer line 2: expected \"WORLD\", got \"HELLO\""
+    );
+}
+
+#[test]
+fn should_report_programs_that_go_wrong() {
+    let report = grade_source(&assignment(), "typo", " LDB 1000\n");
+    assert_eq!(report.outcome, Outcome::UNASSEMBLED);
+    assert_eq!(report.details.unwrap(), "line 1: unknown op code 'LDB'");
+
+    let report = grade_source(
+        &assignment(),
+        "loop",
+        " ORIG 3000\nSTART JMP *\n END START\n",
+    );
+    assert_eq!(report.outcome, Outcome::LIMIT);
+    assert_eq!(report.instructions, 1000);
+
+    let mut assignment = assignment();
+    assignment.time_limit = Some(100);
+    let report = grade_source(&assignment, "loop", " ORIG 3000\nSTART JMP *\n END START\n");
+    assert_eq!(report.outcome, Outcome::TIMEOUT);
+    assert_eq!(report.time, 100);
+
+    // there are no more cards the second time
+    let source = ECHO.replacen(" HLT\n", " JMP START\n", 1);
+    let report = grade_source(&assignment, "again", &source);
+    assert_eq!(report.outcome, Outcome::FAULTED);
+    assert!(report
+        .details
+        .unwrap()
+        .starts_with("fault at 3000 (again.mixal:2 START): unit 16"));
+}
+
+#[test]
+fn should_grade_a_directory_on_several_threads() {
+    let directory = std::env::temp_dir().join(format!("mix-grade-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&directory);
+    std::fs::create_dir_all(directory.join("carol")).unwrap();
+    std::fs::write(directory.join("alice.mixal"), ECHO).unwrap();
+    std::fs::write(directory.join("bob.mixal"), " HLT\n").unwrap();
+    std::fs::write(directory.join("notes.txt"), "not a submission").unwrap();
+    // carol split hers over two files, which are assembled in name order
+    let (first, second) = ECHO.split_at(ECHO.find(" OUT").unwrap());
+    std::fs::write(directory.join("carol").join("1.mixal"), first).unwrap();
+    std::fs::write(directory.join("carol").join("2.mixal"), second).unwrap();
+
+    let submissions = find_submissions(&directory).unwrap();
+    let names: Vec<&str> = submissions.iter().map(|(x, _)| x.as_str()).collect();
+    assert_eq!(names, ["alice", "bob", "carol"]);
+    let reports = grade_all(&assignment(), &submissions, 4);
+    std::fs::remove_dir_all(&directory).unwrap();
+
+    let outcomes: Vec<Outcome> = reports.iter().map(|x| x.outcome).collect();
+    assert_eq!(
+        outcomes,
+        [Outcome::PASSED, Outcome::INCORRECT, Outcome::PASSED]
+    );
+    let json = json_report(&reports);
+    assert!(json.starts_with("{\n  \"total\": 3,\n  \"passed\": 2,\n  \"submissions\": [\n"));
+    assert!(json.contains(
+        "{\"name\": \"bob\", \"outcome\": \"incorrect\", \"passed\": false, \"instructions\": 1, \
+         \"time\": 10, \"details\": \"printer expected 2 lines, got 0\"}"
+    ));
+}
+
+#[test]
+fn should_report_tape_units_that_dont_exist() {
+    let directory = std::env::temp_dir().join(format!("mix-grade-tapes-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&directory);
+    std::fs::create_dir_all(&directory).unwrap();
+    std::fs::write(directory.join("alice.mixal"), ECHO).unwrap();
+    std::fs::write(directory.join("bob.mixal"), " NOTANOP\n").unwrap();
+    // there's no tape unit 99, which only matters to submissions that assemble
+    let assignment = Assignment {
+        tapes: vec![(99, Vec::new())],
+        ..assignment()
+    };
+    let submissions = find_submissions(&directory).unwrap();
+    let reports = grade_all(&assignment, &submissions, 2);
+    std::fs::remove_dir_all(&directory).unwrap();
+
+    assert_eq!(reports[0].name, "alice");
+    assert_eq!(reports[0].outcome, Outcome::FAULTED);
+    assert_eq!(
+        reports[0].details.as_deref(),
+        Some("tape unit 99 doesn't exist")
+    );
+    assert_eq!(reports[1].name, "bob");
+    assert_eq!(reports[1].outcome, Outcome::UNASSEMBLED);
+    let details = reports[1].details.as_deref().unwrap();
+    assert!(details.ends_with("bob.mixal: line 1: unknown op code 'NOTANOP'"));
+}
+
+#[test]
+fn should_escape_details_in_the_report() {
+    let report = Report {
+        name: "quote\"d".to_string(),
+        outcome: Outcome::INCORRECT,
+        details: Some("a\\b\nc".to_string()),
+        instructions: 0,
+        time: 0,
+    };
+    let json = json_report(&[report]);
+    assert!(json.contains("\"name\": \"quote\\\"d\""));
+    assert!(json.contains("\"details\": \"a\\\\b\\nc\""));
+}
+
+#[test]
+fn should_let_computers_move_between_threads() {
+    fn assert_send<T: Send>() {}
+    assert_send::<Computer>();
+}
@@ -0,0 +1,144 @@
+use std::fs;
+use std::path::PathBuf;
+
+use mix_emu::assembler::*;
+use mix_emu::computer::*;
+use mix_emu::object::*;
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/programs/include")
+        .join(name)
+}
+
+fn options(file: &str) -> AssemblerOptions {
+    AssemblerOptions {
+        file_name: Some(fixture(file).display().to_string()),
+        include_paths: vec![fixture("lib")],
+        ..Default::default()
+    }
+}
+
+fn assemble_fixture(file: &str) -> Result<Program, AssemblerError> {
+    let source = fs::read_to_string(fixture(file)).unwrap();
+    assemble_with(&source, &options(file))
+}
+
+fn run(program: &Program) -> Computer {
+    let mut computer = Computer::new();
+    computer.load_program(program).unwrap();
+    assert_eq!(computer.run(Some(1000)), Ok(StopReason::HALTED));
+    computer
+}
+
+#[test]
+fn should_include_files_from_the_include_paths() {
+    let program = assemble_fixture("main.mixal").unwrap();
+    // io.mixal is included by conv.mixal and main.mixal but only read once
+    assert_eq!(program.symbol("PRINT"), Some(0));
+    assert_eq!(program.symbol("OUT"), Some(1000));
+    assert_eq!(program.start, Some(3000));
+    assert_eq!(run(&program).memory.get(1000).unwrap().to_i32(), 42);
+}
+
+#[test]
+fn should_map_included_words_to_their_own_file() {
+    let program = assemble_fixture("main.mixal").unwrap();
+    let map = &program.source_map;
+    let files: Vec<PathBuf> = map.files.iter().map(PathBuf::from).collect();
+    assert_eq!(
+        files,
+        vec![
+            fixture("main.mixal"),
+            fixture("lib").join("conv.mixal"),
+            fixture("lib").join("io.mixal"),
+        ]
+    );
+    assert_eq!(map.line_of_address(1), Some((2, 4)));
+    assert_eq!(map.line_of_address(3001), Some((0, 4)));
+    assert!(map
+        .locate(2)
+        .unwrap()
+        .to_string()
+        .ends_with("io.mixal:5 PRINT+2"));
+}
+
+#[test]
+fn should_name_the_file_an_error_is_in() {
+    let message = assemble_fixture("broken.mixal").unwrap_err().to_string();
+    let file = fixture("lib/broken.mixal").display().to_string();
+    assert_eq!(
+        message,
+        format!("line 2: {}: line 2: undefined symbol MISSING", file)
+    );
+    let message = assemble(" INCLUDE \"nowhere.mixal\"\n")
+        .unwrap_err()
+        .to_string();
+    assert_eq!(message, "line 1: can't find nowhere.mixal to include");
+    let message = assemble("A MACRO\n INCLUDE \"io.mixal\"\n ENDM\n A\n")
+        .unwrap_err()
+        .to_string();
+    assert_eq!(
+        message,
+        "line 4: macro A line 2: INCLUDE can't be used in a macro"
+    );
+    let message = assemble(" INCLUDE io.mixal\n").unwrap_err().to_string();
+    assert_eq!(message, "line 1: INCLUDE takes a file name enclosed in \"s");
+}
+
+#[test]
+fn should_assemble_several_files_as_one_program() {
+    let paths = [
+        fixture("lib/io.mixal"),
+        fixture("start.mixal"),
+        fixture("end.mixal"),
+    ];
+    let program = assemble_files(&paths, &AssemblerOptions::default()).unwrap();
+    assert_eq!(program.start, Some(3000));
+    assert_eq!(program.source_map.files.len(), 3);
+    assert_eq!(run(&program).memory.get(1000).unwrap().to_i32(), 7);
+
+    // the files are named in errors even when there's only one
+    let broken = fixture("lib/broken.mixal");
+    let message = assemble_files(&[&broken], &AssemblerOptions::default())
+        .unwrap_err()
+        .to_string();
+    let expected = format!("{}: line 2: undefined symbol MISSING", broken.display());
+    assert_eq!(message, expected);
+    let message = assemble_files(&[fixture("missing.mixal")], &AssemblerOptions::default())
+        .unwrap_err()
+        .to_string();
+    assert!(message.starts_with(&fixture("missing.mixal").display().to_string()));
+    let message = assemble_files(&[] as &[PathBuf], &AssemblerOptions::default())
+        .unwrap_err()
+        .to_string();
+    assert_eq!(message, "no source files");
+}
+
+#[test]
+fn should_list_included_files_where_they_were_included() {
+    let source = fs::read_to_string(fixture("main.mixal")).unwrap();
+    let (_, listing) = assemble_with_listing(&source, &options("main.mixal")).unwrap();
+    let heading = |path: PathBuf| format!("                              * {}", path.display());
+    let lines: Vec<String> = listing.lines().map(|x| x.to_string()).collect();
+    assert_eq!(
+        lines[1],
+        "    1                          INCLUDE \"conv.mixal\""
+    );
+    assert_eq!(lines[2], heading(fixture("lib").join("conv.mixal")));
+    assert_eq!(
+        lines[4],
+        "    2                          INCLUDE \"io.mixal\""
+    );
+    assert_eq!(lines[5], heading(fixture("lib").join("io.mixal")));
+    assert_eq!(lines[8], "    3    0  + 0 2 0 2 32      PRINT STJ 9F");
+    assert_eq!(lines[11], heading(fixture("lib").join("conv.mixal")));
+    assert_eq!(lines[16], heading(fixture("main.mixal")));
+    // the second INCLUDE of io.mixal reads nothing
+    assert_eq!(
+        lines[17],
+        "    2                          INCLUDE \"io.mixal\""
+    );
+    assert_eq!(lines[18], "    3                          ORIG 3000");
+    assert_eq!(lines.len(), 25);
+}
@@ -12,7 +12,7 @@ fn should_start_running_from_current_address() {
 
     computer.start();
 
-    assert_eq!(computer.running, true);
+    assert!(computer.running);
     assert_eq!(computer.current_instruction_address, 8);
     assert_eq!(computer.registers.a.to_i32(), 1234);
 }
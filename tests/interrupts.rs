@@ -0,0 +1,143 @@
+use mix_emu::assembler::*;
+use mix_emu::computer::interrupts::*;
+use mix_emu::computer::*;
+use mix_emu::data_types::*;
+
+fn with_interrupts(source: &str) -> Computer {
+    let program = assemble(source).unwrap();
+    let mut computer = Computer::new();
+    computer.enable_interrupts();
+    computer.load_program(&program).unwrap();
+    computer
+}
+
+#[test]
+fn should_call_the_operating_system_with_int() {
+    // the handler adds 5 to the saved rA and goes back
+    let mut computer = with_interrupts(
+        " ORIG -12
+ JMP OS
+ ORIG -1000
+OS LDA -9
+ INCA 5
+ STA -9
+ ENTX 0
+ CMPX =1=
+ INT
+ ORIG 3000
+START ENTA 1
+ ENTX 7
+ ENT3 -2
+ CMPA =1=
+ INT
+ HLT
+ END START
+",
+    );
+    assert_eq!(computer.run(Some(100)), Ok(StopReason::HALTED));
+    assert!(!computer.in_control_state());
+    assert_eq!(computer.registers.a.to_i32(), 6);
+    assert_eq!(computer.registers.x.to_i32(), 7);
+    assert_eq!(computer.registers.i3.to_i32(), -2);
+    assert_eq!(
+        computer.comparison_indicator,
+        Some(ComparisonIndicatorState::EQUAL)
+    );
+    // INT came from 3004, so the program went on at 3005
+    let saved = computer.memory.get_in_control_state(SAVED_STATE).unwrap();
+    let location = saved.get_field(FieldSpecification { left: 1, right: 2 });
+    assert_eq!(location.to_i32(), 3005);
+}
+
+#[test]
+fn should_interrupt_when_the_timer_runs_out() {
+    // each tick counts in 2000 and sets the timer going again
+    let mut computer = with_interrupts(
+        " ORIG -11
+ JMP TICK
+ CON 0
+ ORIG -10
+ CON 20
+ ORIG -1000
+TICK LDA 2000
+ INCA 1
+ STA 2000
+ ENTA 20
+ STA -10
+ INT
+ ORIG 3000
+START ENT1 100
+1H DEC1 1
+ J1P 1B
+ HLT
+ END START
+",
+    );
+    assert_eq!(computer.run(Some(1000)), Ok(StopReason::HALTED));
+    assert_eq!(computer.registers.i1.to_i32(), 0);
+    // the loop takes 201u, and as the timer also counts down through the STA and INT after it's
+    // set, each tick leaves 16u of it
+    assert_eq!(computer.memory.get(2000).unwrap().to_i32(), 12);
+}
+
+#[test]
+fn should_interrupt_when_a_device_finishes() {
+    let mut computer = with_interrupts(
+        " ORIG -20
+ JMP DONE
+ ORIG -1000
+DONE ENTA 1
+ STA 2000
+ INT
+ ORIG 3000
+START OUT 1000(0)
+ JBUS *(0)
+ HLT
+ END START
+",
+    );
+    computer.interrupts.as_mut().unwrap().io_time = 100;
+    assert_eq!(computer.run(Some(1000)), Ok(StopReason::HALTED));
+    assert_eq!(computer.memory.get(2000).unwrap().to_i32(), 1);
+    // JBUS waited for the tape until the interrupt
+    assert!(computer.time >= 100);
+    assert!(computer.interrupts.as_ref().unwrap().pending().is_empty());
+}
+
+#[test]
+fn should_keep_the_base_machine_without_interrupts() {
+    let program = assemble(" ORIG 3000\nSTART INT\n END START\n").unwrap();
+    let mut computer = Computer::new();
+    computer.load_program(&program).unwrap();
+    let fault = computer.run(Some(10)).unwrap_err();
+    assert!(matches!(fault.kind, FaultKind::InvalidInstruction(_)));
+
+    let program = assemble(" ORIG -10\n CON 5\n").unwrap();
+    assert!(Computer::new().load_program(&program).is_err());
+}
+
+#[test]
+fn should_keep_programs_out_of_control_state_locations() {
+    let mut computer =
+        with_interrupts(" ORIG -100\nOS HLT\n ORIG 3000\nSTART JMP OS\n END START\n");
+    let fault = computer.run(Some(10)).unwrap_err();
+    assert_eq!(fault.kind, FaultKind::InvalidAddress(-100));
+
+    // a program starting in a negative location starts in control state
+    let mut computer = with_interrupts(" ORIG -100\nOS HLT\n END OS\n");
+    assert!(computer.in_control_state());
+    assert_eq!(computer.run(Some(10)), Ok(StopReason::HALTED));
+}
+
+#[test]
+fn should_fault_on_a_jump_outside_memory_with_an_interrupt_due() {
+    // the timer runs out during the JMP, which used to take the interrupt with -5 as the location
+    // to go back to
+    let mut computer = with_interrupts(" ORIG -10\n CON 1\n ORIG 0\nSTART JMP -5\n END START\n");
+    let fault = computer.run(Some(10)).unwrap_err();
+    assert_eq!(fault.kind, FaultKind::InvalidAddress(-5));
+    assert!(!computer.in_control_state());
+    // and the interrupt is still waiting
+    let pending = computer.interrupts.as_ref().unwrap().pending();
+    assert_eq!(pending, [(1, TIMER_INTERRUPT)]);
+}
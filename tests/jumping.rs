@@ -96,7 +96,7 @@ mod jov {
         computer.handle_instruction(instruction);
 
         assert_eq!(computer.current_instruction_address, 100);
-        assert_eq!(computer.overflow, false);
+        assert!(!computer.overflow);
     }
 
     #[test]
@@ -109,7 +109,7 @@ mod jov {
         computer.handle_instruction(instruction);
 
         assert_eq!(computer.current_instruction_address, 12);
-        assert_eq!(computer.overflow, false);
+        assert!(!computer.overflow);
     }
 }
 
@@ -130,7 +130,7 @@ mod jnov {
         computer.handle_instruction(instruction);
 
         assert_eq!(computer.current_instruction_address, 100);
-        assert_eq!(computer.overflow, false);
+        assert!(!computer.overflow);
     }
 
     #[test]
@@ -143,6 +143,124 @@ mod jnov {
         computer.handle_instruction(instruction);
 
         assert_eq!(computer.current_instruction_address, 12);
-        assert_eq!(computer.overflow, false);
+        assert!(!computer.overflow);
+    }
+}
+
+mod jae {
+    use mix_emu::computer::*;
+    use mix_emu::data_types::*;
+
+    const FIELD: i32 = 6;
+    const CODE: i32 = 40;
+
+    #[test]
+    fn should_jump_if_a_even() {
+        for (value, jumps) in [(0, true), (-4, true), (7, false), (-1, false)] {
+            let mut computer = Computer::new();
+            computer.registers.a = Word::from_i32(value).unwrap();
+            computer.current_instruction_address = 12;
+
+            let instruction =
+                Word::from_instruction_parts(Sign::PLUS, 100, 0, FIELD, CODE).unwrap();
+            computer.handle_instruction(instruction);
+
+            let expected = if jumps { 100 } else { 12 };
+            assert_eq!(computer.current_instruction_address, expected, "{}", value);
+        }
+    }
+}
+
+mod jao {
+    use mix_emu::computer::*;
+    use mix_emu::data_types::*;
+
+    const FIELD: i32 = 7;
+    const CODE: i32 = 40;
+
+    #[test]
+    fn should_jump_if_a_odd() {
+        let mut computer = Computer::new();
+        computer.registers.a = Word::from_i32(-3).unwrap();
+        computer.current_instruction_address = 12;
+
+        let instruction = Word::from_instruction_parts(Sign::PLUS, 100, 0, FIELD, CODE).unwrap();
+        computer.handle_instruction(instruction);
+
+        assert_eq!(computer.current_instruction_address, 100);
+        assert_eq!(computer.registers.j.to_i32(), 12);
+    }
+}
+
+mod jxe {
+    use mix_emu::computer::*;
+    use mix_emu::data_types::*;
+
+    const FIELD: i32 = 6;
+    const CODE: i32 = 47;
+
+    #[test]
+    fn should_not_jump_if_x_odd() {
+        let mut computer = Computer::new();
+        computer.registers.x = Word::from_i32(4095).unwrap();
+        computer.current_instruction_address = 12;
+
+        let instruction = Word::from_instruction_parts(Sign::PLUS, 100, 0, FIELD, CODE).unwrap();
+        computer.handle_instruction(instruction);
+
+        assert_eq!(computer.current_instruction_address, 12);
+    }
+}
+
+mod jxo {
+    use mix_emu::computer::*;
+    use mix_emu::data_types::*;
+
+    const FIELD: i32 = 7;
+    const CODE: i32 = 47;
+
+    #[test]
+    fn should_jump_if_x_odd() {
+        let mut computer = Computer::new();
+        computer.registers.x = Word::from_i32(4095).unwrap();
+        computer.current_instruction_address = 12;
+
+        let instruction = Word::from_instruction_parts(Sign::PLUS, 100, 0, FIELD, CODE).unwrap();
+        computer.handle_instruction(instruction);
+
+        assert_eq!(computer.current_instruction_address, 100);
+    }
+}
+
+mod jbus {
+    use mix_emu::computer::*;
+    use mix_emu::data_types::*;
+
+    const CODE: i32 = 34;
+
+    // JBUS 100(0), which once panicked whatever the unit because the field was negated
+    fn jbus() -> Word {
+        Word::from_instruction_parts(Sign::PLUS, 100, 0, 0, CODE).unwrap()
+    }
+
+    #[test]
+    fn should_not_jump_when_the_unit_is_ready() {
+        let mut computer = Computer::new();
+        computer.current_instruction_address = 10;
+        computer.handle_instruction(jbus());
+
+        assert_eq!(computer.current_instruction_address, 10);
+        assert!(computer.fault.is_none());
+    }
+
+    #[test]
+    fn should_jump_while_the_unit_is_busy() {
+        let mut computer = Computer::new();
+        computer.current_instruction_address = 10;
+        computer.devices.tapes[0].as_mut().unwrap().ready = false;
+        computer.handle_instruction(jbus());
+
+        assert_eq!(computer.current_instruction_address, 100);
+        assert_eq!(computer.registers.j.to_i32(), 10);
     }
 }
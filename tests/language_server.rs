@@ -0,0 +1,306 @@
+use std::io;
+
+use mix_emu::assembler::*;
+use mix_emu::language_server::{json::Json, *};
+
+const URI: &str = "file:///tmp/sum.mixal";
+
+const SUM: &str = "ADDTO MACRO N
+ ADD N
+ ENDM
+TOTAL EQU 1000
+ ORIG 3000
+START ENTA 0
+1H ADDTO TOTAL
+ JANP 1F
+ JMP 1B
+1H SLB 1
+ HLT
+ END START
+";
+
+fn message(text: &str) -> String {
+    text.replace('\'', "\"")
+}
+
+fn open(server: &mut Server, text: &str) -> Vec<Json> {
+    let params = Json::object(vec![(
+        "textDocument",
+        Json::object(vec![("uri", URI.into()), ("text", text.into())]),
+    )]);
+    let notification = Json::object(vec![
+        ("jsonrpc", "2.0".into()),
+        ("method", "textDocument/didOpen".into()),
+        ("params", params),
+    ]);
+    server.handle(&notification.to_string())
+}
+
+// the result of a request about the given line and character of SUM
+fn request(server: &mut Server, method: &str, line: usize, character: usize) -> Json {
+    let request = format!(
+        "{{'jsonrpc': '2.0', 'id': 7, 'method': '{}', 'params': {{'textDocument': {{'uri': '{}'}}, \
+         'position': {{'line': {}, 'character': {}}}, 'context': {{'includeDeclaration': true}}}}}}",
+        method, URI, line, character
+    );
+    let mut responses = server.handle(&message(&request));
+    assert_eq!(responses.len(), 1);
+    let response = responses.remove(0);
+    assert_eq!(response.get("id").as_i64(), Some(7));
+    response.get("result").clone()
+}
+
+fn server() -> Server {
+    let mut server = Server::new();
+    open(&mut server, SUM);
+    server
+}
+
+// line and character of the start of a location
+fn start(location: &Json) -> (i64, i64) {
+    let start = location.get("range").get("start");
+    (
+        start.get("line").as_i64().unwrap(),
+        start.get("character").as_i64().unwrap(),
+    )
+}
+
+#[test]
+fn should_find_symbols_in_the_source() {
+    let uses = symbol_uses(" LDA X,1\nX CON 5\n* LDA Y\n ALF \"A B\"\n");
+    let uses: Vec<(&str, usize, usize, SymbolKind)> = uses
+        .iter()
+        .map(|x| (x.name.as_str(), x.line, x.column, x.kind))
+        .collect();
+    assert_eq!(
+        uses,
+        [
+            ("LDA", 1, 1, SymbolKind::OP),
+            ("X", 1, 5, SymbolKind::REFERENCE),
+            ("X", 2, 0, SymbolKind::DEFINITION),
+            ("CON", 2, 2, SymbolKind::OP),
+            ("ALF", 4, 1, SymbolKind::OP),
+        ]
+    );
+    assert_eq!(field_at("LOOP LDA X", 2), SourceField::LOC);
+    assert_eq!(field_at("LOOP LDA X", 7), SourceField::OP);
+    assert_eq!(field_at(" LDA X", 5), SourceField::ADDRESS);
+    assert_eq!(field_at(" LDA X remarks", 9), SourceField::REMARKS);
+}
+
+#[test]
+fn should_start_and_stop() {
+    let mut server = Server::new();
+    let responses = server.handle(&message(
+        "{'jsonrpc': '2.0', 'id': 1, 'method': 'initialize', 'params': {}}",
+    ));
+    let capabilities = responses[0].get("result").get("capabilities");
+    assert_eq!(capabilities.get("textDocumentSync").as_i64(), Some(1));
+    assert_eq!(capabilities.get("hoverProvider").as_bool(), Some(true));
+
+    let responses = server.handle(&message(
+        "{'jsonrpc': '2.0', 'id': 2, 'method': 'shutdown'}",
+    ));
+    assert_eq!(
+        responses[0].to_string(),
+        message("{'jsonrpc':'2.0','id':2,'result':null}")
+    );
+    assert_eq!(server.exit, None);
+    server.handle(&message("{'jsonrpc': '2.0', 'method': 'exit'}"));
+    assert_eq!(server.exit, Some(0));
+
+    // exiting without shutting down first is an error
+    let mut server = Server::new();
+    server.handle(&message("{'jsonrpc': '2.0', 'method': 'exit'}"));
+    assert_eq!(server.exit, Some(1));
+}
+
+#[test]
+fn should_answer_bad_requests_with_errors() {
+    let mut server = Server::new();
+    let responses = server.handle("{");
+    assert_eq!(responses[0].get("error").get("code").as_i64(), Some(-32700));
+    // nesting deep enough to overflow the stack is an error rather than a crash
+    let responses = server.handle(&"[".repeat(200_000));
+    assert_eq!(responses[0].get("error").get("code").as_i64(), Some(-32700));
+    assert_eq!(
+        responses[0].get("error").get("message").as_str(),
+        Some("nested too deeply")
+    );
+    assert!(Json::parse(&format!("{}{}", "[".repeat(128), "]".repeat(128))).is_ok());
+    let responses = server.handle(&message(
+        "{'jsonrpc': '2.0', 'id': 1, 'method': 'nonsense'}",
+    ));
+    assert_eq!(responses[0].get("id").as_i64(), Some(1));
+    assert_eq!(responses[0].get("error").get("code").as_i64(), Some(-32601));
+    // and notifications it doesn't know are ignored
+    assert!(server
+        .handle(&message("{'jsonrpc': '2.0', 'method': 'initialized'}"))
+        .is_empty());
+}
+
+#[test]
+fn should_publish_the_first_error() {
+    let mut server = Server::new();
+    let notifications = open(&mut server, SUM);
+    let params = notifications[0].get("params");
+    assert_eq!(params.get("uri").as_str(), Some(URI));
+    assert_eq!(params.get("diagnostics"), &Json::Array(Vec::new()));
+
+    let notifications = open(&mut server, &SUM.replace(" HLT", " HLT 1(9:9)"));
+    let diagnostic = &notifications[0]
+        .get("params")
+        .get("diagnostics")
+        .as_array()
+        .unwrap()[0];
+    assert_eq!(start(diagnostic), (10, 0));
+    assert_eq!(
+        diagnostic.get("range").get("end").get("character").as_i64(),
+        Some(11)
+    );
+    assert_eq!(
+        diagnostic.get("message").as_str(),
+        Some("HLT does not take a field")
+    );
+
+    // closing the file clears them
+    let notifications = server.handle(&message(
+        "{'jsonrpc': '2.0', 'method': 'textDocument/didClose', \
+         'params': {'textDocument': {'uri': 'file:///tmp/sum.mixal'}}}",
+    ));
+    assert_eq!(
+        notifications[0].get("params").get("diagnostics"),
+        &Json::Array(Vec::new())
+    );
+}
+
+#[test]
+fn should_go_to_definitions() {
+    let mut server = server();
+    // TOTAL in ADDTO TOTAL
+    let definition = request(&mut server, "textDocument/definition", 6, 10);
+    assert_eq!(definition.get("uri").as_str(), Some(URI));
+    assert_eq!(start(&definition), (3, 0));
+    // 1F goes to the 1H after it and 1B to the one before
+    let definition = request(&mut server, "textDocument/definition", 7, 6);
+    assert_eq!(start(&definition), (9, 0));
+    let definition = request(&mut server, "textDocument/definition", 8, 5);
+    assert_eq!(start(&definition), (6, 0));
+    // the macro from where it's used
+    let definition = request(&mut server, "textDocument/definition", 6, 4);
+    assert_eq!(start(&definition), (0, 0));
+    // and nothing for a mnemonic or a number
+    assert_eq!(
+        request(&mut server, "textDocument/definition", 5, 7),
+        Json::Null
+    );
+    assert_eq!(
+        request(&mut server, "textDocument/definition", 5, 11),
+        Json::Null
+    );
+}
+
+#[test]
+fn should_find_references() {
+    let mut server = server();
+    // the first 1H is only used by 1B, not by 1F which comes after it
+    let references = request(&mut server, "textDocument/references", 6, 0);
+    let starts: Vec<(i64, i64)> = references.as_array().unwrap().iter().map(start).collect();
+    assert_eq!(starts, [(6, 0), (8, 5)]);
+    let references = request(&mut server, "textDocument/references", 3, 1);
+    let starts: Vec<(i64, i64)> = references.as_array().unwrap().iter().map(start).collect();
+    assert_eq!(starts, [(3, 0), (6, 9)]);
+}
+
+#[test]
+fn should_describe_what_the_cursor_is_on() {
+    let mut server = server();
+    let hover = |server: &mut Server, line, character| {
+        let result = request(server, "textDocument/hover", line, character);
+        result
+            .get("contents")
+            .get("value")
+            .as_str()
+            .unwrap()
+            .to_string()
+    };
+    assert_eq!(hover(&mut server, 5, 7), "**ENTA** C = 48, F = 2, 1u");
+    assert_eq!(hover(&mut server, 1, 2), "**ADD** C = 1, F = (0:5), 2u");
+    assert_eq!(
+        hover(&mut server, 9, 4),
+        "**SLB** C = 6, F = 6, 2u, binary MIX only"
+    );
+    assert_eq!(
+        hover(&mut server, 3, 7),
+        "**EQU** gives the LOC the value of the address"
+    );
+    assert_eq!(
+        hover(&mut server, 6, 12),
+        "```mixal\nTOTAL EQU 1000\n```\nline 4"
+    );
+    let result = request(&mut server, "textDocument/hover", 6, 12);
+    assert_eq!(start(&result), (6, 9));
+}
+
+#[test]
+fn should_complete_ops_and_symbols() {
+    let mut server = server();
+    let labels = |result: Json| -> Vec<String> {
+        result
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|x| x.get("label").as_str().unwrap().to_string())
+            .collect()
+    };
+    let ops = labels(request(&mut server, "textDocument/completion", 10, 2));
+    assert_eq!(ops.len(), 158 + 7 + 8 + 1);
+    for op in ["NOP", "LDA", "JXO", "EQU", "INCLUDE", "ADDTO"] {
+        assert!(ops.iter().any(|x| x == op), "{}", op);
+    }
+    let symbols = request(&mut server, "textDocument/completion", 8, 5);
+    assert_eq!(labels(symbols.clone()), ["TOTAL", "START"]);
+    assert_eq!(
+        symbols.as_array().unwrap()[0].get("detail").as_str(),
+        Some("TOTAL EQU 1000")
+    );
+    assert_eq!(
+        request(&mut server, "textDocument/completion", 8, 0),
+        Json::Array(Vec::new())
+    );
+}
+
+#[test]
+fn should_read_and_write_messages() {
+    let body = "{\"jsonrpc\":\"2.0\",\"id\":1,\"result\":\"é\"}";
+    let mut output = Vec::new();
+    write_message(&mut output, &Json::parse(body).unwrap()).unwrap();
+    assert_eq!(
+        String::from_utf8(output.clone()).unwrap(),
+        format!("Content-Length: {}\r\n\r\n{}", body.len(), body)
+    );
+    let mut input = output.as_slice();
+    assert_eq!(read_message(&mut input).unwrap().unwrap(), body);
+    assert_eq!(read_message(&mut input).unwrap(), None);
+
+    let mut input = "Content-Type: x\r\n\r\n{}".as_bytes();
+    assert!(read_message(&mut input).is_err());
+    let mut input = "Content-Length: 18446744073709551615\r\n\r\n{}".as_bytes();
+    let error = read_message(&mut input).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn should_parse_and_print_json() {
+    let text = "{\"a\": [1, -2.5, true, null], \"b\": \"q\\\"\\n\\u00e9\\ud83d\\ude00\"}";
+    let json = Json::parse(text).unwrap();
+    assert_eq!(json.get("a").as_array().unwrap()[1], Json::Number(-2.5));
+    assert_eq!(json.get("b").as_str(), Some("q\"\né😀"));
+    assert_eq!(json.get("c"), &Json::Null);
+    assert_eq!(
+        json.to_string(),
+        "{\"a\":[1,-2.5,true,null],\"b\":\"q\\\"\\né😀\"}"
+    );
+    assert!(Json::parse("[1,]").is_err());
+    assert!(Json::parse("{} x").is_err());
+}
@@ -0,0 +1,217 @@
+use mix_emu::assembler::*;
+use mix_emu::computer::*;
+use mix_emu::data_types::*;
+use mix_emu::linker::*;
+use mix_emu::object::*;
+
+const MAIN: &str = " EXTERN PRINT,OUT
+START ENTA 42
+ JMP PRINT
+ LDA OUT
+ ADD =1=
+ STA OUT+1
+ HLT
+ END START
+";
+
+const IO: &str = " EXPORT PRINT,OUT
+OUT EQU 1000
+PRINT STJ 9F
+ STA OUT
+9H JMP *
+ END
+";
+
+fn module(source: &str, file_name: &str) -> Program {
+    let options = AssemblerOptions {
+        file_name: Some(file_name.to_string()),
+        module: true,
+        ..Default::default()
+    };
+    assemble_with(source, &options).unwrap()
+}
+
+fn error(source: &str) -> String {
+    assemble(source).unwrap_err().to_string()
+}
+
+fn module_error(source: &str) -> String {
+    let options = AssemblerOptions {
+        module: true,
+        ..Default::default()
+    };
+    assemble_with(source, &options).unwrap_err().to_string()
+}
+
+fn field(left: i32, right: i32) -> FieldSpecification {
+    FieldSpecification { left, right }
+}
+
+#[test]
+fn should_record_what_the_linker_has_to_move() {
+    let main = module(MAIN, "main.mixal");
+    let linkage = main.linkage.as_ref().unwrap();
+    assert!(linkage.exports.is_empty());
+    assert_eq!(
+        linkage.externals,
+        vec![
+            (1, field(0, 2), "PRINT".to_string()),
+            (2, field(0, 2), "OUT".to_string()),
+            (4, field(0, 2), "OUT".to_string()),
+        ]
+    );
+    // the literal is placed after the module, so the ADD using it moves with it
+    assert_eq!(linkage.relocations, vec![(3, field(0, 2))]);
+    assert_eq!(main.start, Some(0));
+
+    let io = module(IO, "io.mixal");
+    let linkage = io.linkage.as_ref().unwrap();
+    assert_eq!(
+        linkage.exports,
+        vec![
+            ("PRINT".to_string(), 0, true),
+            ("OUT".to_string(), 1000, false)
+        ]
+    );
+    assert_eq!(
+        linkage.relocations,
+        vec![(0, field(0, 2)), (2, field(0, 2))]
+    );
+    assert_eq!(io.start, None);
+}
+
+#[test]
+fn should_keep_linkage_in_object_files() {
+    let io = module(IO, "io.mixal");
+    let mut object = Vec::new();
+    io.write_object(&mut object).unwrap();
+    let text = String::from_utf8(object).unwrap();
+    assert!(text.contains("MODULE\nEXPORT PRINT 0 RELATIVE\nEXPORT OUT 1000 ABSOLUTE\n"));
+    assert!(text.contains("RELOCATE 2 2\n"));
+    assert_eq!(Program::read_object(text.as_bytes()).unwrap(), io);
+
+    let main = module(MAIN, "main.mixal");
+    let mut object = Vec::new();
+    main.write_object(&mut object).unwrap();
+    let text = String::from_utf8(object).unwrap();
+    assert!(text.contains("EXTERNAL 1 2 PRINT\n"));
+    assert_eq!(Program::read_object(text.as_bytes()).unwrap(), main);
+}
+
+#[test]
+fn should_link_modules_into_a_program() {
+    let main = module(MAIN, "main.mixal");
+    let io = module(IO, "io.mixal");
+    let program = link(&[(&main, Some(3000)), (&io, None)]).unwrap();
+    assert_eq!(program.linkage, None);
+    assert_eq!(program.start, Some(3000));
+    // io goes after main's six instructions and its literal
+    assert_eq!(program.symbol("PRINT"), Some(3007));
+    assert_eq!(program.symbol("OUT"), Some(1000));
+    assert_eq!(
+        program.source_map.locate(3009).unwrap().to_string(),
+        "io.mixal:5 PRINT+2"
+    );
+
+    let mut computer = Computer::new();
+    computer.load_program(&program).unwrap();
+    assert_eq!(computer.run(Some(100)), Ok(StopReason::HALTED));
+    assert_eq!(computer.memory.get(1000).unwrap().to_i32(), 42);
+    assert_eq!(computer.memory.get(1001).unwrap().to_i32(), 43);
+}
+
+#[test]
+fn should_link_the_same_modules_anywhere() {
+    let main = module(MAIN, "main.mixal");
+    let io = module(IO, "io.mixal");
+    let program = link(&[(&io, Some(100)), (&main, Some(2000))]).unwrap();
+    assert_eq!(program.start, Some(2000));
+    assert_eq!(program.symbol("PRINT"), Some(100));
+    let mut computer = Computer::new();
+    computer.load_program(&program).unwrap();
+    assert_eq!(computer.run(Some(100)), Ok(StopReason::HALTED));
+    assert_eq!(computer.memory.get(1001).unwrap().to_i32(), 43);
+}
+
+#[test]
+fn should_only_let_modules_add_to_addresses() {
+    assert_eq!(
+        module_error("A NOP\n LDA A*2\n"),
+        "line 2: an address the linker moves can only have numbers added to or taken away from it"
+    );
+    assert_eq!(
+        module_error("A NOP\n LDA -A\n"),
+        "line 2: an address the linker moves can't be used here"
+    );
+    assert_eq!(
+        module_error(" EXTERN X\n LDA 0,X\n"),
+        "line 2: an address the linker moves can't be used here"
+    );
+    assert_eq!(
+        module_error(" ORIG 100\n"),
+        "line 1: an address in a module has to be counted from somewhere in it, like * or a label"
+    );
+    assert_eq!(
+        module_error("A NOP\nB CON A(1:2),A(4:5)\nC EQU B\n LDA C\nD EQU B(1:2)\n"),
+        "line 5: an address the linker moves has to be the whole W-value"
+    );
+    // the distance between two addresses in the module doesn't move
+    let program = module("A NOP\nB NOP\n LDA B-A\n CON B-A\n", "a.mixal");
+    assert!(program.linkage.unwrap().relocations.is_empty());
+}
+
+#[test]
+fn should_report_bad_exports_and_externs() {
+    assert_eq!(
+        error(" EXTERN PRINT\n"),
+        "line 1: EXTERN and EXPORT can only be used when assembling a module"
+    );
+    assert_eq!(
+        module_error(" EXPORT MISSING\n"),
+        "line 1: undefined symbol MISSING"
+    );
+    assert_eq!(
+        module_error(" EXTERN X\n EXPORT X\n"),
+        "line 2: X is in another module, which is the one to export it"
+    );
+    assert_eq!(
+        module_error(" EXPORT A,\n"),
+        "line 1: EXTERN and EXPORT take symbols separated by commas"
+    );
+    assert_eq!(
+        module_error(" EXTERN PRINT\nMAIN JMP PRNT\n HLT\n"),
+        "line 2: PRNT is undefined, declare it with EXTERN if another module exports it"
+    );
+}
+
+#[test]
+fn should_report_link_errors() {
+    let main = module(MAIN, "main.mixal");
+    let io = module(IO, "io.mixal");
+    assert_eq!(
+        link(&[(&main, None)]).unwrap_err().to_string(),
+        "main.mixal: PRINT isn't exported by any module"
+    );
+    assert_eq!(
+        link(&[(&io, None), (&io, None)]).unwrap_err(),
+        LinkError::DuplicateExport("PRINT".to_string())
+    );
+    assert_eq!(
+        link(&[(&main, Some(3000)), (&io, Some(3005))]).unwrap_err(),
+        LinkError::Overlap("io.mixal".to_string(), 3005)
+    );
+    assert_eq!(
+        link(&[(&io, Some(3998))]).unwrap_err(),
+        LinkError::OutsideMemory("io.mixal".to_string(), 4000)
+    );
+    let program = assemble(" HLT\n").unwrap();
+    assert_eq!(
+        link(&[(&program, None)]).unwrap_err().to_string(),
+        "<input> is not a module, assemble it with --module"
+    );
+    let other = module("START NOP\n END START\n", "other.mixal");
+    assert_eq!(
+        link(&[(&main, None), (&io, None), (&other, None)]).unwrap_err(),
+        LinkError::MultipleStarts("other.mixal".to_string())
+    );
+}
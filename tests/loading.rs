@@ -577,7 +577,7 @@ mod ldan_tests {
         let instruction = Word::from_instruction_parts(Sign::PLUS, 200, 4, 5, 16).unwrap();
         computer.handle_instruction(instruction);
 
-        assert_eq!(computer.registers.a.to_i32(), content.to_i32() * (-1));
+        assert_eq!(computer.registers.a.to_i32(), -content.to_i32());
     }
 
     #[test]
@@ -613,7 +613,7 @@ mod ldan_tests {
 
         assert_eq!(
             computer.registers.a.to_i32(),
-            (-1) * (2 * 64_i32.pow(2) + 3 * 64 + 4)
+            -(2 * 64_i32.pow(2) + 3 * 64 + 4)
         );
     }
 }
@@ -0,0 +1,196 @@
+use mix_emu::assembler::*;
+use mix_emu::computer::*;
+use mix_emu::data_types::*;
+use mix_emu::object::*;
+
+fn word_at(program: &Program, address: i32) -> Word {
+    program
+        .words
+        .iter()
+        .find(|(x, _)| *x == address)
+        .map(|(_, word)| *word)
+        .unwrap()
+}
+
+fn error(source: &str) -> String {
+    match assemble(source) {
+        Ok(_) => panic!("expected an error"),
+        Err(e) => e.to_string(),
+    }
+}
+
+fn run(program: &Program) -> Computer {
+    let mut computer = Computer::new();
+    computer.load_program(program).unwrap();
+    assert_eq!(computer.run(Some(10000)), Ok(StopReason::HALTED));
+    computer
+}
+
+const COUNTING: &str = "ADDTO MACRO REG,N
+ INC#REG N
+ ENDM
+COUNT MACRO N,BY
+ ENT1 N
+LOOP ADDTO A,BY
+ DEC1 1
+ J1P LOOP
+ ENDM
+ ORIG 3000
+START ENTA 0
+ COUNT 3,2
+ COUNT 4,5
+ HLT
+ END START
+";
+
+#[test]
+fn should_replace_parameters_with_arguments() {
+    let program = assemble(
+        "STORE MACRO WHERE,FIELD
+ STA WHERE#FIELD
+ ENDM
+ ORIG 3000
+ STORE 1000,(1:2)
+ STORE =5=,(0:5)
+ STORE 1001
+ END 3000
+",
+    )
+    .unwrap();
+    assert_eq!(
+        word_at(&program, 3000),
+        Word::from_instruction_parts(Sign::PLUS, 1000, 0, 10, 24).unwrap()
+    );
+    assert_eq!(word_at(&program, 3001).address(), 3003);
+    assert_eq!(word_at(&program, 3001).field().value(), 5);
+    // a missing argument is replaced by nothing
+    assert_eq!(
+        word_at(&program, 3002),
+        Word::from_instruction_parts(Sign::PLUS, 1001, 0, 5, 24).unwrap()
+    );
+}
+
+#[test]
+fn should_give_each_expansion_its_own_labels() {
+    let source = COUNTING.replace("ADDTO A,BY", "INCA BY");
+    let program = assemble(&source).unwrap();
+    assert_eq!(run(&program).registers.a.to_i32(), 3 * 2 + 4 * 5);
+    let labels: Vec<&str> = program
+        .symbols
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect();
+    assert_eq!(labels, vec!["START", "LOOP%1", "LOOP%2"]);
+}
+
+#[test]
+fn should_expand_macros_used_by_macros() {
+    let program = assemble(COUNTING).unwrap();
+    assert_eq!(run(&program).registers.a.to_i32(), 26);
+    // the label on the line using ADDTO names the word ADDTO generates, and each use of COUNT
+    // and of the ADDTO inside it counts as an expansion
+    assert_eq!(program.symbol("LOOP%1"), Some(3002));
+    assert_eq!(program.symbol("LOOP%3"), Some(3006));
+}
+
+#[test]
+fn should_keep_local_symbols_apart_in_each_expansion() {
+    let program = assemble(
+        "SKIP MACRO
+ JMP 1F
+ NOP
+1H NOP
+ ENDM
+ ORIG 3000
+START SKIP
+ SKIP
+ HLT
+ END START
+",
+    )
+    .unwrap();
+    assert_eq!(word_at(&program, 3000).address(), 3002);
+    assert_eq!(word_at(&program, 3003).address(), 3005);
+    assert_eq!(program.symbol("START"), Some(3000));
+}
+
+#[test]
+fn should_name_the_first_word_with_the_label_of_the_use() {
+    let program = assemble(
+        "NOTHING MACRO
+ ENDM
+LABELLED MACRO
+HERE NOP
+ ENDM
+ ORIG 3000
+A LABELLED
+B NOTHING
+ HLT
+C NOTHING
+ END A
+",
+    )
+    .unwrap();
+    assert_eq!(program.symbol("A"), Some(3000));
+    assert_eq!(program.symbol("HERE%1"), Some(3000));
+    assert_eq!(program.symbol("B"), Some(3001));
+    assert_eq!(program.symbol("C"), Some(3002));
+}
+
+#[test]
+fn should_map_expanded_words_to_the_line_using_the_macro() {
+    let program = assemble(COUNTING).unwrap();
+    let map = &program.source_map;
+    assert_eq!(map.line_of_address(3001), Some((0, 12)));
+    assert_eq!(map.line_of_address(3005), Some((0, 13)));
+    assert_eq!(map.locate(3003).unwrap().to_string(), "<input>:12 LOOP%1+1");
+}
+
+#[test]
+fn should_list_expanded_lines() {
+    let (_, listing) = assemble_with_listing(COUNTING, &AssemblerOptions::default()).unwrap();
+    let lines: Vec<&str> = listing.lines().collect();
+    assert_eq!(lines[0], " LINE  LOC  WORD              SOURCE");
+    assert_eq!(lines[1], "    1                         ADDTO MACRO REG,N");
+    assert_eq!(lines[11], "   11 3000  + 0 0 0 2 48      START ENTA 0");
+    assert_eq!(lines[12], "   12                          COUNT 3,2");
+    assert_eq!(lines[13], "      3001  + 0 3 0 2 49      + ENT1 3");
+    assert_eq!(lines[14], "      3002  + 0 2 0 0 48      ++LOOP%1 INCA 2");
+}
+
+#[test]
+fn should_report_errors_in_expanded_lines() {
+    assert_eq!(
+        error(" ORIG 3000\nBAD MACRO\n NOP\n LDA NOWHERE+1\n ENDM\n BAD\n END 3000\n"),
+        "line 6: macro BAD line 4: undefined symbol NOWHERE"
+    );
+    assert_eq!(
+        error("A MACRO X\n STA X\n ENDM\n A 1,2\n"),
+        "line 4: macro A takes 1 arguments, not 2"
+    );
+    assert_eq!(error("A MACRO\n NOP\n"), "line 1: macro A has no ENDM");
+    assert_eq!(
+        error("A MACRO\nB MACRO\n ENDM\n ENDM\n"),
+        "line 2: macros can't be defined inside a macro"
+    );
+    assert_eq!(
+        error("LDA MACRO\n ENDM\n"),
+        "line 1: LDA is already an operation"
+    );
+    assert_eq!(
+        error("A MACRO\n ENDM\nA MACRO\n ENDM\n"),
+        "line 3: macro A is already defined"
+    );
+    assert_eq!(error(" ENDM\n"), "line 1: ENDM without MACRO");
+    assert_eq!(
+        error("A MACRO X,X\n ENDM\n"),
+        "line 1: MACRO parameters must be different symbols separated by commas"
+    );
+}
+
+#[test]
+fn should_stop_macros_using_themselves() {
+    let message = error("FOREVER MACRO\n NOP\n FOREVER\n ENDM\n FOREVER\n");
+    assert!(message.starts_with("line 5: macro FOREVER line 3: macro FOREVER line 3:"));
+    assert!(message.ends_with("macros are nested more than 16 deep, does FOREVER use itself?"));
+}
@@ -48,3 +48,77 @@ fn overlapping_region_edge_case() {
     }
     assert_eq!(computer.registers.i1.to_i32(), 1004);
 }
+
+#[test]
+fn should_clear_memory_by_moving_onto_itself() {
+    let mut computer = setup_computer();
+    computer.memory.set(1000, Word::ZERO).unwrap();
+    computer.registers.i1 = Index::from_i32(1001).unwrap();
+
+    // MOVE 1000(49) copies the zero at 1000 forward one word at a time
+    let instruction = Word::from_byte_values(Sign::PLUS, 1000 / 64, 1000 % 64, 0, 49, 7).unwrap();
+    computer.handle_instruction(instruction);
+
+    for i in 999..1052 {
+        let contents = computer.memory.get(i).unwrap();
+        match i {
+            1000..=1049 => assert_eq!(contents.to_i32(), 0),
+            _ => assert_eq!(contents.to_i32(), i),
+        }
+    }
+    assert_eq!(computer.registers.i1.to_i32(), 1050);
+    assert_eq!(computer.time, 1 + 2 * 49);
+}
+
+#[test]
+fn should_do_nothing_but_take_time_for_no_words() {
+    let mut computer = setup_computer();
+    computer.registers.i1 = Index::from_i32(5).unwrap();
+
+    let instruction = Word::from_byte_values(Sign::PLUS, 0, 10, 0, 0, 7).unwrap();
+    computer.handle_instruction(instruction);
+
+    assert_eq!(computer.memory.get(5).unwrap().to_i32(), 5);
+    assert_eq!(computer.registers.i1.to_i32(), 5);
+    assert_eq!(computer.time, 1);
+}
+
+#[test]
+fn should_fault_when_the_source_runs_off_memory() {
+    let mut computer = setup_computer();
+    computer.registers.i1 = Index::from_i32(100).unwrap();
+
+    let instruction = Word::from_byte_values(Sign::PLUS, 3998 / 64, 3998 % 64, 0, 4, 7).unwrap();
+    computer.handle_instruction(instruction);
+
+    assert_eq!(
+        computer.fault.unwrap().kind,
+        FaultKind::InvalidAddress(4000)
+    );
+    // the two words that could be moved were
+    assert_eq!(computer.memory.get(100).unwrap().to_i32(), 3998);
+    assert_eq!(computer.memory.get(101).unwrap().to_i32(), 3999);
+    assert_eq!(computer.memory.get(102).unwrap().to_i32(), 102);
+    assert_eq!(computer.registers.i1.to_i32(), 102);
+}
+
+#[test]
+fn should_fault_when_the_destination_runs_off_memory() {
+    let mut computer = setup_computer();
+    computer.registers.i1 = Index::from_i32(3999).unwrap();
+
+    let instruction = Word::from_byte_values(Sign::PLUS, 0, 10, 0, 3, 7).unwrap();
+    computer.handle_instruction(instruction);
+
+    assert_eq!(
+        computer.fault.unwrap().kind,
+        FaultKind::InvalidAddress(4000)
+    );
+    assert_eq!(computer.memory.get(3999).unwrap().to_i32(), 10);
+    assert_eq!(computer.registers.i1.to_i32(), 4000);
+
+    let mut computer = setup_computer();
+    computer.registers.i1 = Index::from_i32(-1).unwrap();
+    computer.handle_instruction(instruction);
+    assert_eq!(computer.fault.unwrap().kind, FaultKind::InvalidAddress(-1));
+}
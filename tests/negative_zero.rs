@@ -0,0 +1,250 @@
+use mix_emu::computer::*;
+use mix_emu::data_types::*;
+
+// MIX words are sign and magnitude, so +0 and -0 are different words. These follow the rules in
+// section 1.3.1: a sum of zero keeps the sign rA had, products and quotients have the sign rules
+// of algebra even when they're zero, ENTA and friends take the instruction's sign when M is zero,
+// NUM, CHAR and the shifts leave signs alone, and comparisons and jumps can't tell the zeros apart.
+
+fn minus_zero() -> Word {
+    -Word::ZERO
+}
+
+fn word(value: &str) -> Word {
+    value.parse().unwrap()
+}
+
+fn execute(computer: &mut Computer, sign: Sign, address: i32, field: i32, code: i32) {
+    computer
+        .handle_instruction(Word::from_instruction_parts(sign, address, 0, field, code).unwrap());
+}
+
+// runs code with field 5 on the word at 1000
+fn with_memory(a: Word, v: Word, code: i32) -> Computer {
+    let mut computer = Computer::new();
+    computer.registers.a = a;
+    computer.memory.set(1000, v).unwrap();
+    execute(&mut computer, Sign::PLUS, 1000, 5, code);
+    computer
+}
+
+#[test]
+fn should_keep_the_sign_of_r_a_when_a_sum_is_zero() {
+    assert_eq!(
+        with_memory(minus_zero(), Word::ZERO, 1).registers.a,
+        minus_zero()
+    );
+    assert_eq!(
+        with_memory(word("-5"), word("5"), 1).registers.a,
+        minus_zero()
+    );
+    assert_eq!(
+        with_memory(word("5"), word("-5"), 1).registers.a,
+        Word::ZERO
+    );
+    assert_eq!(
+        with_memory(minus_zero(), minus_zero(), 2).registers.a,
+        minus_zero()
+    );
+    assert_eq!(
+        with_memory(word("-5"), word("-5"), 2).registers.a,
+        minus_zero()
+    );
+    assert_eq!(
+        with_memory(Word::ZERO, Word::ZERO, 2).registers.a,
+        Word::ZERO
+    );
+}
+
+#[test]
+fn should_give_the_sum_its_own_sign_when_it_wraps_to_zero() {
+    let computer = with_memory(Word::MIN, word("-1"), 1);
+    assert!(computer.overflow);
+    assert_eq!(computer.registers.a, minus_zero());
+    let computer = with_memory(Word::MAX, word("1"), 1);
+    assert!(computer.overflow);
+    assert_eq!(computer.registers.a, Word::ZERO);
+}
+
+#[test]
+fn should_sign_zero_products() {
+    let computer = with_memory(minus_zero(), word("5"), 3);
+    assert_eq!(computer.registers.a, minus_zero());
+    assert_eq!(computer.registers.x, minus_zero());
+    let computer = with_memory(word("-5"), minus_zero(), 3);
+    assert_eq!(computer.registers.a, Word::ZERO);
+    assert_eq!(computer.registers.x, Word::ZERO);
+}
+
+#[test]
+fn should_sign_products_with_the_sign_of_the_field() {
+    // V is LDA's view of the field, positive unless the field includes the sign
+    let mut computer = Computer::new();
+    computer.registers.a = word("5");
+    computer.memory.set(1000, word("-3")).unwrap();
+    execute(&mut computer, Sign::PLUS, 1000, 13, 3);
+    assert_eq!(computer.registers.x, word("15"));
+    assert_eq!(computer.registers.a, Word::ZERO);
+}
+
+#[test]
+fn should_sign_zero_quotients_and_remainders() {
+    let mut computer = Computer::new();
+    computer.registers.a = minus_zero();
+    computer.registers.x = Word::ZERO;
+    computer.memory.set(1000, word("5")).unwrap();
+    execute(&mut computer, Sign::PLUS, 1000, 5, 4);
+    assert_eq!(computer.registers.a, minus_zero());
+    assert_eq!(computer.registers.x, minus_zero());
+    assert!(!computer.overflow);
+
+    // the remainder takes rA's old sign, the quotient the sign of the product
+    let mut computer = Computer::new();
+    computer.registers.a = Word::ZERO;
+    computer.registers.x = word("4");
+    computer.memory.set(1000, word("-5")).unwrap();
+    execute(&mut computer, Sign::PLUS, 1000, 5, 4);
+    assert_eq!(computer.registers.a, minus_zero());
+    assert_eq!(computer.registers.x, word("4"));
+}
+
+#[test]
+fn should_divide_r_a_x_with_the_sign_of_r_a() {
+    let mut computer = Computer::new();
+    computer.registers.a = Word::ZERO;
+    computer.registers.x = word("-7");
+    computer.memory.set(1000, word("2")).unwrap();
+    execute(&mut computer, Sign::PLUS, 1000, 5, 4);
+    assert_eq!(computer.registers.a, word("3"));
+    assert_eq!(computer.registers.x, word("1"));
+}
+
+#[test]
+fn should_keep_register_signs_when_increments_give_zero() {
+    let mut computer = Computer::new();
+    computer.registers.a = word("-5");
+    execute(&mut computer, Sign::PLUS, 5, 0, 48);
+    assert_eq!(computer.registers.a, minus_zero());
+    // DECA 0 and INCA 0 leave zero alone
+    execute(&mut computer, Sign::PLUS, 0, 1, 48);
+    assert_eq!(computer.registers.a, minus_zero());
+    computer.registers.a = Word::ZERO;
+    execute(&mut computer, Sign::MINUS, 0, 0, 48);
+    assert_eq!(computer.registers.a, Word::ZERO);
+
+    computer.registers.x = word("5");
+    execute(&mut computer, Sign::PLUS, 5, 1, 55);
+    assert_eq!(computer.registers.x, Word::ZERO);
+    computer.registers.x = word("-5");
+    execute(&mut computer, Sign::PLUS, 5, 0, 55);
+    assert_eq!(computer.registers.x, minus_zero());
+
+    computer.registers.i3 = "-3".parse().unwrap();
+    execute(&mut computer, Sign::PLUS, 3, 0, 51);
+    assert_eq!(computer.registers.i3, -Index::ZERO);
+    computer.registers.i3 = "3".parse().unwrap();
+    execute(&mut computer, Sign::PLUS, 3, 1, 51);
+    assert_eq!(computer.registers.i3, Index::ZERO);
+}
+
+#[test]
+fn should_enter_the_sign_of_the_instruction_when_m_is_zero() {
+    let mut computer = Computer::new();
+    // ENNA 0 is the usual way to get -0 into rA
+    execute(&mut computer, Sign::PLUS, 0, 3, 48);
+    assert_eq!(computer.registers.a, minus_zero());
+    execute(&mut computer, Sign::MINUS, 0, 2, 48);
+    assert_eq!(computer.registers.a, minus_zero());
+    execute(&mut computer, Sign::PLUS, 0, 2, 55);
+    assert_eq!(computer.registers.x, Word::ZERO);
+    execute(&mut computer, Sign::MINUS, 0, 2, 49);
+    assert_eq!(computer.registers.i1, -Index::ZERO);
+
+    // M is zero here even though the address isn't, so it's still the instruction's sign
+    computer.registers.i2 = "-7".parse().unwrap();
+    computer.handle_instruction(Word::from_instruction_parts(Sign::PLUS, 7, 2, 2, 48).unwrap());
+    assert_eq!(computer.registers.a, Word::ZERO);
+}
+
+#[test]
+fn should_load_and_store_signed_zeros() {
+    let mut computer = Computer::new();
+    computer.memory.set(1000, word("-5")).unwrap();
+    // LDA 1000(0:0)
+    execute(&mut computer, Sign::PLUS, 1000, 0, 8);
+    assert_eq!(computer.registers.a, minus_zero());
+    // LDAN of +0
+    execute(&mut computer, Sign::PLUS, 1001, 5, 16);
+    assert_eq!(computer.registers.a, minus_zero());
+    // STA 1002 then LD1 1002
+    execute(&mut computer, Sign::PLUS, 1002, 5, 24);
+    assert_eq!(computer.memory.get(1002).unwrap(), minus_zero());
+    execute(&mut computer, Sign::PLUS, 1002, 5, 9);
+    assert_eq!(computer.registers.i1, -Index::ZERO);
+    // STZ clears the sign too
+    execute(&mut computer, Sign::PLUS, 1002, 5, 33);
+    assert_eq!(computer.memory.get(1002).unwrap(), Word::ZERO);
+}
+
+#[test]
+fn should_leave_signs_alone_in_conversions_and_shifts() {
+    let mut computer = Computer::new();
+    computer.registers.a = minus_zero();
+    computer.registers.x = word("-30");
+    execute(&mut computer, Sign::PLUS, 0, 0, 5);
+    assert_eq!(computer.registers.a, minus_zero());
+    assert_eq!(computer.registers.x.sign, Sign::MINUS);
+
+    execute(&mut computer, Sign::PLUS, 0, 1, 5);
+    assert_eq!(computer.registers.a, word("- 30 30 30 30 30"));
+    assert_eq!(computer.registers.x, word("- 30 30 30 30 30"));
+
+    computer.registers.a = word("-1");
+    // SLA 5 and SRAX 1
+    execute(&mut computer, Sign::PLUS, 5, 0, 6);
+    assert_eq!(computer.registers.a, minus_zero());
+    computer.registers.x = word("-1");
+    execute(&mut computer, Sign::PLUS, 1, 3, 6);
+    assert_eq!(computer.registers.a, minus_zero());
+    assert_eq!(computer.registers.x, minus_zero());
+}
+
+#[test]
+fn should_treat_both_zeros_as_equal() {
+    let mut computer = Computer::new();
+    computer.registers.a = minus_zero();
+    computer.registers.i1 = -Index::ZERO;
+    // CMPA 1000 and CMP1 1000 against +0
+    execute(&mut computer, Sign::PLUS, 1000, 5, 56);
+    assert_eq!(
+        computer.comparison_indicator,
+        Some(ComparisonIndicatorState::EQUAL)
+    );
+    computer.memory.set(1000, minus_zero()).unwrap();
+    execute(&mut computer, Sign::PLUS, 1000, 5, 57);
+    assert_eq!(
+        computer.comparison_indicator,
+        Some(ComparisonIndicatorState::EQUAL)
+    );
+}
+
+#[test]
+fn should_jump_on_minus_zero_as_zero() {
+    let jumps = |code: i32, field: i32| {
+        let mut computer = Computer::new();
+        computer.registers.a = minus_zero();
+        computer.registers.i4 = -Index::ZERO;
+        execute(&mut computer, Sign::PLUS, 2000, field, code);
+        computer.current_instruction_address == 2000
+    };
+    // JAN, JAZ, JAP, JANN, JANZ, JANP
+    assert_eq!(
+        [0, 1, 2, 3, 4, 5].map(|field| jumps(40, field)),
+        [false, true, false, true, false, true]
+    );
+    // and the same for rI4
+    assert_eq!(
+        [0, 1, 2, 3, 4, 5].map(|field| jumps(44, field)),
+        [false, true, false, true, false, true]
+    );
+}
@@ -0,0 +1,65 @@
+use mix_emu::assembler::read_source_string_as_instructions;
+use mix_emu::data_types::*;
+use mix_emu::disassembler::disassemble;
+use mix_emu::opcodes::*;
+
+fn assemble_line(line: &str) -> Word {
+    let words = read_source_string_as_instructions(&format!(" {}\n", line)).unwrap();
+    assert_eq!(words.len(), 1);
+    words[0]
+}
+
+#[test]
+fn assembler_should_use_code_and_default_field_from_table() {
+    for opcode in OPCODES.iter() {
+        let word = assemble_line(opcode.mnemonic);
+        assert_eq!(word.code(), opcode.code, "{}", opcode.mnemonic);
+        assert_eq!(word.field().value(), opcode.field, "{}", opcode.mnemonic);
+    }
+}
+
+#[test]
+fn emulator_should_decode_every_table_entry_to_itself() {
+    for opcode in OPCODES.iter() {
+        let word =
+            Word::from_instruction_parts(Sign::PLUS, 0, 0, opcode.field, opcode.code).unwrap();
+        assert_eq!(decode(word), Some(opcode));
+    }
+}
+
+#[test]
+fn emulator_should_decode_any_field_for_field_instructions() {
+    for opcode in OPCODES.iter().filter(|x| x.operand != OperandKind::FIXED) {
+        let word = Word::from_instruction_parts(Sign::PLUS, 0, 0, 13, opcode.code).unwrap();
+        assert_eq!(decode(word).map(|x| x.op), Some(opcode.op));
+    }
+}
+
+#[test]
+fn disassembler_and_assembler_should_round_trip() {
+    for opcode in OPCODES.iter() {
+        assert_eq!(disassemble(assemble_line(opcode.mnemonic)), opcode.mnemonic);
+
+        let field = match opcode.operand {
+            OperandKind::FIELD => "(1:3)",
+            OperandKind::UNIT => "(18)",
+            OperandKind::COUNT => "(10)",
+            OperandKind::FIXED => "",
+        };
+        let line = format!("{} -2000,4{}", opcode.mnemonic, field);
+        let word = assemble_line(&line);
+        assert_eq!(disassemble(word), line);
+        assert_eq!(word.address(), -2000);
+        assert_eq!(word.index(), 4);
+    }
+}
+
+#[test]
+fn documentation_should_match_table() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/OPCODES.md");
+    let documentation = std::fs::read_to_string(path).unwrap();
+    assert!(
+        documentation.ends_with(&markdown_table()),
+        "OPCODES.md is out of date, regenerate it from opcodes::markdown_table()"
+    );
+}
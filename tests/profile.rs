@@ -0,0 +1,128 @@
+use mix_emu::assembler::*;
+use mix_emu::computer::devices::*;
+use mix_emu::computer::profile::*;
+use mix_emu::computer::*;
+use mix_emu::object::Program;
+
+fn profile(source: &str) -> (Program, Computer) {
+    let program = assemble(source).unwrap();
+    let mut computer = Computer::new();
+    computer.devices = Devices::new();
+    computer.devices.line_printer = Some(TextOutput::line_printer());
+    computer.profile = Some(Profile::new());
+    computer.load_program(&program).unwrap();
+    assert_eq!(computer.run(None), Ok(StopReason::HALTED));
+    (program, computer)
+}
+
+fn count(program: &Program, computer: &Computer, line: usize) -> u64 {
+    let address = program.source_map.address_of_line(0, line).unwrap();
+    computer.profile.as_ref().unwrap().get(address).count
+}
+
+// Program M from section 1.3.2, finding the largest of X[1..n], with a caller
+const PROGRAM_M: &str = "X EQU 1000
+ ORIG 3000
+MAXIMUM STJ EXIT
+INIT ENT3 0,1
+ JMP CHANGEM
+LOOP CMPA X,3
+ JGE *+3
+CHANGEM ENT2 0,3
+ LDA X,3
+ DEC3 1
+ J3P LOOP
+EXIT JMP *
+START ENT1 10
+ JMP MAXIMUM
+ HLT
+ ORIG X+1
+ CON 3
+ CON 1
+ CON 4
+ CON 1
+ CON 5
+ CON 9
+ CON 2
+ CON 6
+ CON 5
+ CON 3
+ END START
+";
+
+#[test]
+fn should_count_program_m_like_knuths_analysis() {
+    let (program, computer) = profile(PROGRAM_M);
+    let data = [3, 1, 4, 1, 5, 9, 2, 6, 5, 3];
+    let n = data.len() as u64;
+    // A is how many times the maximum so far changes, scanning from X[n] down
+    let mut a = 0;
+    let mut max = data[data.len() - 1];
+    for x in data.iter().rev().skip(1) {
+        if *x > max {
+            max = *x;
+            a += 1;
+        }
+    }
+    assert_eq!(computer.registers.a.to_i32(), 9);
+    // the frequencies in Knuth's table: 1, 1, 1, n-1, n-1, A+1, A+1, n, n, 1
+    let expected = [1, 1, 1, n - 1, n - 1, a + 1, a + 1, n, n, 1];
+    let counts: Vec<u64> = (3..=12)
+        .map(|line| count(&program, &computer, line))
+        .collect();
+    assert_eq!(counts, expected);
+    // and the time those add up to for the subroutine, from STJ to the exit
+    let profile = computer.profile.as_ref().unwrap();
+    let time: u64 = (3000..3010).map(|x| profile.get(x).time).sum();
+    assert_eq!(time, 5 * n + 3 * a + 5);
+}
+
+#[test]
+fn should_count_program_p_like_knuths_analysis() {
+    let (program, computer) = profile(include_str!("programs/primes.mixal"));
+    // N candidates are tried and K divisions done to find the first 500 primes
+    assert_eq!(count(&program, &computer, 14), 1784);
+    assert_eq!(count(&program, &computer, 18), 9538);
+    let profile = computer.profile.as_ref().unwrap();
+    assert_eq!(profile.total().count, computer.instruction_count);
+    assert_eq!(profile.total().time, computer.time);
+    // DIV takes 12u, so it dominates
+    assert_eq!(
+        profile.hot_spots(1),
+        vec![(
+            3010,
+            Entry {
+                count: 9538,
+                time: 12 * 9538
+            }
+        )]
+    );
+}
+
+#[test]
+fn should_write_an_annotated_listing() {
+    let source = " ORIG 3000
+START ENT1 3
+* count down
+LOOP DEC1 1
+ J1P LOOP
+ HLT
+ END START
+";
+    let (program, computer) = profile(source);
+    let mut listing = Vec::new();
+    computer
+        .profile
+        .as_ref()
+        .unwrap()
+        .write_listing(source, &program, &mut listing)
+        .unwrap();
+    let listing = String::from_utf8(listing).unwrap();
+    let lines: Vec<&str> = listing.lines().collect();
+    assert_eq!(lines[0], " LINE  LOC     COUNT      TIME  SOURCE");
+    assert_eq!(lines[3], "    3                           * count down");
+    assert_eq!(lines[4], "    4 3001         3         3  LOOP DEC1 1");
+    assert_eq!(lines[6], "    6 3003         1        10   HLT");
+    assert_eq!(lines[9], "8 instructions in 17u, hot spots:");
+    assert_eq!(lines[11], " 3003         1        10  58.8%  HLT");
+}
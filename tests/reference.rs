@@ -0,0 +1,837 @@
+mod common;
+
+use common::Random;
+use mix_emu::computer::*;
+use mix_emu::data_types::*;
+use std::cmp::Ordering;
+
+// A second, deliberately naive model of the instructions, straight from the definitions in
+// section 1.3.1. A word is a sign and a magnitude held in an i128, bytes are digits of the
+// magnitude in base b, and nothing is shared with the emulator except the conversions at the edges.
+// Each test runs thousands of random cases on both byte sizes, with M taken through a random index
+// register, and compares every register, the words around M, the flags and where the next
+// instruction comes from.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Model {
+    negative: bool,
+    magnitude: i128,
+}
+
+impl Model {
+    fn from_word(word: Word, size: ByteSize) -> Self {
+        Self {
+            negative: word.sign == Sign::MINUS,
+            magnitude: word.to_i64_in(size).unsigned_abs() as i128,
+        }
+    }
+
+    fn to_word(self, size: ByteSize) -> Word {
+        Word::from_i64_in(self.magnitude as i64, size)
+            .unwrap()
+            .with_sign(self.sign())
+    }
+
+    fn from_index(index: Index, size: ByteSize) -> Self {
+        Self {
+            negative: index.sign == Sign::MINUS,
+            magnitude: index.to_i32_in(size).unsigned_abs() as i128,
+        }
+    }
+
+    fn to_index(self, size: ByteSize) -> Index {
+        Index::from_i32_in(self.magnitude as i32, size)
+            .unwrap()
+            .with_sign(self.sign())
+    }
+
+    // a value that takes its sign from somewhere else when it's zero
+    fn signed(value: i128, negative_zero: bool) -> Self {
+        Self {
+            negative: if value == 0 { negative_zero } else { value < 0 },
+            magnitude: value.abs(),
+        }
+    }
+
+    fn sign(&self) -> Sign {
+        if self.negative {
+            Sign::MINUS
+        } else {
+            Sign::PLUS
+        }
+    }
+
+    fn negated(self) -> Self {
+        Self {
+            negative: !self.negative,
+            ..self
+        }
+    }
+
+    fn value(&self) -> i128 {
+        if self.negative {
+            -self.magnitude
+        } else {
+            self.magnitude
+        }
+    }
+    // byte 1 to 5, the most significant first
+    fn byte(&self, idx: i32, b: i128) -> i128 {
+        self.magnitude / b.pow(5 - idx as u32) % b
+    }
+
+    fn from_bytes(negative: bool, bytes: [i128; 5], b: i128) -> Self {
+        Self {
+            negative,
+            magnitude: bytes.iter().fold(0, |total, x| total * b + x),
+        }
+    }
+
+    // the field as a load sees it, moved to the right with + unless the sign is included
+    fn field(&self, left: i32, right: i32, b: i128) -> Self {
+        let mut magnitude = 0;
+        for idx in left.max(1)..=right {
+            magnitude = magnitude * b + self.byte(idx, b);
+        }
+        Self {
+            negative: left == 0 && self.negative,
+            magnitude,
+        }
+    }
+
+    // the word with its (L:R) field replaced by the rightmost bytes of register, as a store does
+    fn with_field(&self, register: Model, left: i32, right: i32, b: i128) -> Self {
+        let mut bytes = [1, 2, 3, 4, 5].map(|x| self.byte(x, b));
+        for idx in left.max(1)..=right {
+            bytes[idx as usize - 1] = register.byte(5 - (right - idx), b);
+        }
+        let negative = match left {
+            0 => register.negative,
+            _ => self.negative,
+        };
+        Self::from_bytes(negative, bytes, b)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct State {
+    a: Model,
+    x: Model,
+    i: [Model; 6],
+    j: i128,
+    // where the next instruction comes from, which only a jump changes
+    next: i32,
+    // the words from WINDOW on, where every memory operand is
+    memory: Vec<Model>,
+    overflow: bool,
+    comparison: Option<Ordering>,
+    // set by the reference when rA and rX can be anything afterwards
+    undefined: bool,
+    // set by the reference when the instruction should stop the machine and change nothing
+    fault: bool,
+}
+
+impl State {
+    fn word(&self, address: i32) -> Model {
+        self.memory[(address - WINDOW) as usize]
+    }
+
+    fn set(&mut self, address: i32, word: Model) {
+        self.memory[(address - WINDOW) as usize] = word;
+    }
+
+    // in the order of the jump instructions, rA, rI1 to rI6 and then rX
+    fn register(&self, number: i32) -> Model {
+        match number {
+            0 => self.a,
+            7 => self.x,
+            x => self.i[x as usize - 1],
+        }
+    }
+}
+
+const WINDOW: i32 = 1000;
+const WINDOW_SIZE: i32 = 40;
+const NEXT: i32 = 3001;
+
+impl Random {
+    // mostly the bytes where things go wrong
+    fn byte(&mut self, b: i32) -> i128 {
+        match self.below(4) {
+            0 => 0,
+            1 => (b - 1) as i128,
+            _ => self.below(b) as i128,
+        }
+    }
+
+    fn model(&mut self, b: i32) -> Model {
+        let bytes = match self.below(8) {
+            0 => [0; 5],
+            // a small number, like most of the numbers programs use
+            1 => [0, 0, 0, self.byte(b), self.byte(b)],
+            _ => [0; 5].map(|_| self.byte(b)),
+        };
+        Model::from_bytes(self.below(2) == 0, bytes, b as i128)
+    }
+
+    // mostly small enough that an address can make up the rest of M
+    fn index(&mut self, b: i32) -> Model {
+        let bytes = match self.below(4) {
+            0 => [0; 5],
+            1 => [0, 0, 0, self.byte(b), self.byte(b)],
+            _ => [0, 0, 0, 0, self.byte(b)],
+        };
+        Model::from_bytes(self.below(2) == 0, bytes, b as i128)
+    }
+
+    fn field(&mut self) -> (i32, i32) {
+        let left = self.below(6);
+        (left, left + self.below(6 - left))
+    }
+
+    fn sign(&mut self) -> Sign {
+        match self.below(2) {
+            0 => Sign::PLUS,
+            _ => Sign::MINUS,
+        }
+    }
+}
+
+fn random_state(random: &mut Random, b: i32) -> State {
+    State {
+        a: random.model(b),
+        x: random.model(b),
+        i: [(); 6].map(|_| random.index(b)),
+        j: random.below(b * b) as i128,
+        next: NEXT,
+        memory: (0..WINDOW_SIZE).map(|_| random.model(b)).collect(),
+        overflow: random.below(2) == 0,
+        comparison: match random.below(4) {
+            0 => None,
+            1 => Some(Ordering::Less),
+            2 => Some(Ordering::Equal),
+            _ => Some(Ordering::Greater),
+        },
+        undefined: false,
+        fault: false,
+    }
+}
+
+// runs one instruction on the emulator from the state and reads the state back. The computer is
+// reused from case to case, since a new one comes with a tape to allocate, so everything the state
+// covers is set again.
+fn emulate(computer: &mut Computer, state: &State, instruction: Word) -> State {
+    let size = computer.byte_size;
+    computer.registers.a = state.a.to_word(size);
+    computer.registers.x = state.x.to_word(size);
+    let [i1, i2, i3, i4, i5, i6] = state.i.map(|x| x.to_index(size));
+    computer.registers.i1 = i1;
+    computer.registers.i2 = i2;
+    computer.registers.i3 = i3;
+    computer.registers.i4 = i4;
+    computer.registers.i5 = i5;
+    computer.registers.i6 = i6;
+    computer.registers.j = JumpAddress::from_i32_in(state.j as i32, size).unwrap();
+    computer.current_instruction_address = state.next;
+    for (idx, word) in state.memory.iter().enumerate() {
+        computer
+            .memory
+            .set(WINDOW + idx as i32, word.to_word(size))
+            .unwrap();
+    }
+    computer.overflow = state.overflow;
+    computer.comparison_indicator = state.comparison.map(|x| match x {
+        Ordering::Less => ComparisonIndicatorState::LESS,
+        Ordering::Equal => ComparisonIndicatorState::EQUAL,
+        Ordering::Greater => ComparisonIndicatorState::GREATER,
+    });
+    computer.fault = None;
+    computer.handle_instruction(instruction);
+    let registers = &computer.registers;
+    State {
+        a: Model::from_word(registers.a, size),
+        x: Model::from_word(registers.x, size),
+        i: [
+            registers.i1,
+            registers.i2,
+            registers.i3,
+            registers.i4,
+            registers.i5,
+            registers.i6,
+        ]
+        .map(|x| Model::from_index(x, size)),
+        j: registers.j.to_i32_in(size) as i128,
+        next: computer.current_instruction_address,
+        memory: (WINDOW..WINDOW + WINDOW_SIZE)
+            .map(|x| Model::from_word(computer.memory.get(x).unwrap(), size))
+            .collect(),
+        overflow: computer.overflow,
+        comparison: computer.comparison_indicator.as_ref().map(|x| match x {
+            ComparisonIndicatorState::LESS => Ordering::Less,
+            ComparisonIndicatorState::EQUAL => Ordering::Equal,
+            ComparisonIndicatorState::GREATER => Ordering::Greater,
+        }),
+        undefined: false,
+        fault: computer.fault.is_some(),
+    }
+}
+
+// a register plus a value as ADD, SUB, INCA and INCX do it: a zero sum keeps the sign of the
+// register, and a sum too big for a word leaves what fits with the sign of the true sum. Returns
+// whether it overflowed.
+fn reference_sum(register: &mut Model, value: i128, b: i128) -> bool {
+    let sum = register.value() + value;
+    let capacity = b.pow(5);
+    *register = Model {
+        negative: if sum == 0 { register.negative } else { sum < 0 },
+        magnitude: sum.abs() % capacity,
+    };
+    sum.abs() >= capacity
+}
+
+// rJ becomes the location after the jump, rJ has no sign
+fn reference_jump(state: &mut State, m: i32) {
+    state.j = state.next as i128;
+    state.next = m;
+}
+
+// the parts of an instruction, and the M they give in the state it runs from
+#[derive(Debug, Clone, Copy)]
+struct Operand {
+    sign: Sign,
+    address: i32,
+    index: i32,
+    field: i32,
+    m: i32,
+}
+
+impl Operand {
+    fn new(state: &State, sign: Sign, address: i32, index: i32, field: i32) -> Self {
+        let modifier = match index {
+            0 => 0,
+            x => state.i[x as usize - 1].value() as i32,
+        };
+        Self {
+            sign,
+            address,
+            index,
+            field,
+            m: sign.value() * address + modifier,
+        }
+    }
+
+    fn field(&self) -> (i32, i32) {
+        (self.field / 8, self.field % 8)
+    }
+}
+
+// an operand with M = target, through a random index register when the address can make up the
+// difference and with no index register when it can't
+fn aim(random: &mut Random, state: &State, target: i32, field: i32, b: i32) -> Operand {
+    let index = random.below(7);
+    let modifier = match index {
+        0 => 0,
+        x => state.i[x as usize - 1].value() as i32,
+    };
+    let (index, address) = match target - modifier {
+        x if x.abs() < b * b => (index, x),
+        _ => (0, target),
+    };
+    let sign = match address {
+        0 => random.sign(),
+        x if x < 0 => Sign::MINUS,
+        _ => Sign::PLUS,
+    };
+    Operand::new(state, sign, address.abs(), index, field)
+}
+
+// checks count random cases of an instruction on both byte sizes, reference changes the state the
+// way the instruction should
+fn check(
+    code: i32,
+    count: usize,
+    operand: impl Fn(&mut Random, &mut State, i32) -> Operand,
+    reference: impl Fn(&mut State, &Operand, i128),
+) {
+    for size in [ByteSize::BINARY, ByteSize::DECIMAL] {
+        let b = size.values();
+        let mut random = Random(0x2545_f491_4f6c_dd1d ^ (code * 131 + b) as u64);
+        let mut computer = Computer::with_byte_size(size);
+        for _ in 0..count {
+            let mut state = random_state(&mut random, b);
+            let operand = operand(&mut random, &mut state, b);
+            let instruction = Word::from_instruction_parts_in(
+                operand.sign,
+                operand.address,
+                operand.index,
+                operand.field,
+                code,
+                size,
+            )
+            .unwrap();
+            let mut expected = state.clone();
+            reference(&mut expected, &operand, b as i128);
+            let emulated = emulate(&mut computer, &state, instruction);
+            if expected.undefined {
+                (expected.a, expected.x) = (emulated.a, emulated.x);
+                expected.undefined = false;
+            }
+            assert_eq!(
+                emulated, expected,
+                "C = {} on b = {} with {:?} from {:?}",
+                code, b, operand, state
+            );
+        }
+    }
+}
+
+// M somewhere in the window and a random field
+fn memory_operand(random: &mut Random, state: &mut State, b: i32) -> Operand {
+    let (left, right) = random.field();
+    let target = WINDOW + random.below(WINDOW_SIZE);
+    aim(random, state, target, left * 8 + right, b)
+}
+
+// any address and index register, for the instructions where F picks the operation
+fn fixed_operand(field: i32) -> impl Fn(&mut Random, &mut State, i32) -> Operand {
+    move |random, state, b| {
+        let address = match random.below(3) {
+            0 => 0,
+            _ => random.below(b * b),
+        };
+        Operand::new(state, random.sign(), address, random.below(7), field)
+    }
+}
+
+// a shift count, mostly up to a little past the longest shift that leaves anything but sometimes
+// far past it or negative
+fn shift_operand(field: i32, longest: i32) -> impl Fn(&mut Random, &mut State, i32) -> Operand {
+    move |random, state, b| {
+        let count = match random.below(8) {
+            0 => random.below(4000),
+            1 => -1 - random.below(longest),
+            _ => random.below(longest + 3),
+        };
+        aim(random, state, count, field, b)
+    }
+}
+
+// F words from M to rI1, both in the window and perhaps overlapping
+fn move_operand(random: &mut Random, state: &mut State, b: i32) -> Operand {
+    let count = random.below(20);
+    let to = WINDOW + random.below(WINDOW_SIZE - count + 1);
+    state.i[0] = Model {
+        negative: false,
+        magnitude: to as i128,
+    };
+    let from = WINDOW + random.below(WINDOW_SIZE - count + 1);
+    aim(random, state, from, count, b)
+}
+
+// a negative shift count stops the machine
+fn with_count(reference: impl Fn(&mut State, i32, i128)) -> impl Fn(&mut State, &Operand, i128) {
+    move |state, operand, b| {
+        if operand.m < 0 {
+            state.fault = true;
+            return;
+        }
+        reference(state, operand.m, b);
+    }
+}
+
+const CASES: usize = 1000;
+// for each register of an instruction that comes in one for every register
+const REGISTER_CASES: usize = CASES / 4;
+
+#[test]
+fn add_and_sub_should_match_the_reference() {
+    check(1, CASES, memory_operand, |state, operand, b| {
+        let (left, right) = operand.field();
+        let v = state.word(operand.m).field(left, right, b);
+        state.overflow |= reference_sum(&mut state.a, v.value(), b);
+    });
+    check(2, CASES, memory_operand, |state, operand, b| {
+        let (left, right) = operand.field();
+        let v = state.word(operand.m).field(left, right, b);
+        state.overflow |= reference_sum(&mut state.a, -v.value(), b);
+    });
+}
+
+#[test]
+fn mul_should_match_the_reference() {
+    check(3, CASES, memory_operand, |state, operand, b| {
+        let (left, right) = operand.field();
+        let v = state.word(operand.m).field(left, right, b);
+        let product = state.a.magnitude * v.magnitude;
+        // the sign follows the usual rule even for a zero product
+        let negative = state.a.negative != v.negative;
+        state.a = Model {
+            negative,
+            magnitude: product / b.pow(5),
+        };
+        state.x = Model {
+            negative,
+            magnitude: product % b.pow(5),
+        };
+    });
+}
+
+#[test]
+fn div_should_match_the_reference() {
+    check(4, CASES, memory_operand, |state, operand, b| {
+        let (left, right) = operand.field();
+        let v = state.word(operand.m).field(left, right, b);
+        // Knuth leaves rA and rX undefined after a division that overflows
+        if state.a.magnitude >= v.magnitude {
+            state.overflow = true;
+            state.undefined = true;
+            return;
+        }
+        let dividend = state.a.magnitude * b.pow(5) + state.x.magnitude;
+        let a_negative = state.a.negative;
+        state.a = Model {
+            negative: a_negative != v.negative,
+            magnitude: dividend / v.magnitude,
+        };
+        state.x = Model {
+            negative: a_negative,
+            magnitude: dividend % v.magnitude,
+        };
+    });
+}
+
+#[test]
+fn loads_and_stores_should_match_the_reference() {
+    // LDA, LDX, LDAN, LDXN
+    check(8, CASES, memory_operand, |state, operand, b| {
+        let (left, right) = operand.field();
+        state.a = state.word(operand.m).field(left, right, b);
+    });
+    check(15, CASES, memory_operand, |state, operand, b| {
+        let (left, right) = operand.field();
+        state.x = state.word(operand.m).field(left, right, b);
+    });
+    check(16, CASES, memory_operand, |state, operand, b| {
+        let (left, right) = operand.field();
+        state.a = state.word(operand.m).field(left, right, b).negated();
+    });
+    check(23, CASES, memory_operand, |state, operand, b| {
+        let (left, right) = operand.field();
+        state.x = state.word(operand.m).field(left, right, b).negated();
+    });
+    // STA, STX, then STJ, which stores rJ with a + sign, and STZ
+    check(24, CASES, memory_operand, |state, operand, b| {
+        let (left, right) = operand.field();
+        let stored = state.word(operand.m).with_field(state.a, left, right, b);
+        state.set(operand.m, stored);
+    });
+    check(31, CASES, memory_operand, |state, operand, b| {
+        let (left, right) = operand.field();
+        let stored = state.word(operand.m).with_field(state.x, left, right, b);
+        state.set(operand.m, stored);
+    });
+    check(32, CASES, memory_operand, |state, operand, b| {
+        let (left, right) = operand.field();
+        let j = Model {
+            negative: false,
+            magnitude: state.j,
+        };
+        let stored = state.word(operand.m).with_field(j, left, right, b);
+        state.set(operand.m, stored);
+    });
+    check(33, CASES, memory_operand, |state, operand, b| {
+        let (left, right) = operand.field();
+        let zero = Model {
+            negative: false,
+            magnitude: 0,
+        };
+        let stored = state.word(operand.m).with_field(zero, left, right, b);
+        state.set(operand.m, stored);
+    });
+}
+
+#[test]
+fn index_loads_and_stores_should_match_the_reference() {
+    for n in 1..=6 {
+        // LDi and LDiN, where a value that takes more than two bytes stops the machine
+        for (code, negate) in [(8 + n, false), (16 + n, true)] {
+            check(
+                code,
+                REGISTER_CASES,
+                memory_operand,
+                move |state, operand, b| {
+                    let (left, right) = operand.field();
+                    let v = state.word(operand.m).field(left, right, b);
+                    if v.magnitude >= b * b {
+                        state.fault = true;
+                        return;
+                    }
+                    state.i[n as usize - 1] = if negate { v.negated() } else { v };
+                },
+            );
+        }
+        // STi, as if the register were a word with three zero bytes in front
+        check(
+            24 + n,
+            REGISTER_CASES,
+            memory_operand,
+            move |state, operand, b| {
+                let (left, right) = operand.field();
+                let register = state.i[n as usize - 1];
+                let stored = state.word(operand.m).with_field(register, left, right, b);
+                state.set(operand.m, stored);
+            },
+        );
+    }
+}
+
+#[test]
+fn comparisons_should_match_the_reference() {
+    // CMPA, CMP1 to CMP6 and CMPX, where -0 and +0 are equal
+    for register in 0..8 {
+        check(
+            56 + register,
+            CASES,
+            memory_operand,
+            move |state, operand, b| {
+                let (left, right) = operand.field();
+                let r = state.register(register).field(left, right, b).value();
+                let v = state.word(operand.m).field(left, right, b).value();
+                state.comparison = Some(r.cmp(&v));
+            },
+        );
+    }
+}
+
+#[test]
+fn address_transfers_should_match_the_reference() {
+    // INCA and DECA behave like ADD and SUB, ENTA takes the sign of the instruction when M = 0,
+    // so ENTA -0 gives rA = -0, and ENNA is ENTA with the sign turned round. The same for rX.
+    for code in [48, 55] {
+        for field in 0..4 {
+            check(
+                code,
+                CASES,
+                fixed_operand(field),
+                move |state, operand, b| {
+                    let m = operand.m as i128;
+                    let register = if code == 48 {
+                        &mut state.a
+                    } else {
+                        &mut state.x
+                    };
+                    let entered = Model::signed(m, operand.sign == Sign::MINUS);
+                    match field {
+                        0 => state.overflow |= reference_sum(register, m, b),
+                        1 => state.overflow |= reference_sum(register, -m, b),
+                        2 => *register = entered,
+                        _ => *register = entered.negated(),
+                    }
+                },
+            );
+        }
+    }
+    // the same for rI1 to rI6, except that a result that takes more than two bytes stops the
+    // machine rather than overflowing
+    for n in 1..=6 {
+        for field in 0..4 {
+            check(
+                48 + n,
+                REGISTER_CASES,
+                fixed_operand(field),
+                move |state, operand, b| {
+                    let m = operand.m as i128;
+                    let register = state.i[n as usize - 1];
+                    let entered = Model::signed(m, operand.sign == Sign::MINUS);
+                    let result = match field {
+                        0 => Model::signed(register.value() + m, register.negative),
+                        1 => Model::signed(register.value() - m, register.negative),
+                        2 => entered,
+                        _ => entered.negated(),
+                    };
+                    if result.magnitude >= b * b {
+                        state.fault = true;
+                        return;
+                    }
+                    state.i[n as usize - 1] = result;
+                },
+            );
+        }
+    }
+}
+
+#[test]
+fn shifts_should_match_the_reference() {
+    let shift = |value: i128, count: i32, bytes: u32, left: bool, b: i128| {
+        let capacity = b.pow(bytes);
+        if count as u32 >= bytes {
+            return 0;
+        }
+        match left {
+            true => value * b.pow(count as u32) % capacity,
+            false => value / b.pow(count as u32),
+        }
+    };
+    // SLA and SRA leave rX alone
+    for (field, left) in [(0, true), (1, false)] {
+        check(
+            6,
+            CASES,
+            shift_operand(field, 10),
+            with_count(move |state, count, b| {
+                state.a.magnitude = shift(state.a.magnitude, count, 5, left, b);
+            }),
+        );
+    }
+    // SLAX and SRAX shift the ten bytes of rA and rX together, keeping both signs
+    for (field, left) in [(2, true), (3, false)] {
+        check(
+            6,
+            CASES,
+            shift_operand(field, 10),
+            with_count(move |state, count, b| {
+                let both = state.a.magnitude * b.pow(5) + state.x.magnitude;
+                let shifted = shift(both, count, 10, left, b);
+                state.a.magnitude = shifted / b.pow(5);
+                state.x.magnitude = shifted % b.pow(5);
+            }),
+        );
+    }
+    // SLC and SRC go round, so a shift of n is a shift of n mod 10
+    for (field, left) in [(4, true), (5, false)] {
+        check(
+            6,
+            CASES,
+            shift_operand(field, 10),
+            with_count(move |state, count, b| {
+                let both = state.a.magnitude * b.pow(5) + state.x.magnitude;
+                let count = (count % 10) as u32;
+                let count = if left { count } else { (10 - count) % 10 };
+                let shifted = both * b.pow(count) % b.pow(10) + both / b.pow(10 - count);
+                state.a.magnitude = shifted / b.pow(5);
+                state.x.magnitude = shifted % b.pow(5);
+            }),
+        );
+    }
+    // SLB and SRB shift the 60 bits of rA and rX together, and only binary MIX has them
+    for (field, left) in [(6, true), (7, false)] {
+        check(
+            6,
+            CASES,
+            shift_operand(field, 60),
+            with_count(move |state, count, b| {
+                if b != 64 {
+                    state.fault = true;
+                    return;
+                }
+                let both = state.a.magnitude * b.pow(5) + state.x.magnitude;
+                let shifted = match count {
+                    60.. => 0,
+                    _ if left => (both << count) % (1 << 60),
+                    _ => both >> count,
+                };
+                state.a.magnitude = shifted / b.pow(5);
+                state.x.magnitude = shifted % b.pow(5);
+            }),
+        );
+    }
+}
+
+#[test]
+fn move_should_match_the_reference() {
+    // one word at a time, so a destination just after the source copies the first word along
+    check(7, CASES, move_operand, |state, operand, _| {
+        let mut to = state.i[0].value() as i32;
+        for idx in 0..operand.field {
+            let word = state.word(operand.m + idx);
+            state.set(to, word);
+            to += 1;
+        }
+        state.i[0] = Model {
+            negative: false,
+            magnitude: to as i128,
+        };
+    });
+}
+
+#[test]
+fn jumps_should_match_the_reference() {
+    // JMP, JSJ, which leaves rJ alone, JOV and JNOV, which both turn overflow off, and the jumps
+    // on the comparison indicator
+    for field in 0..10 {
+        check(39, CASES, fixed_operand(field), move |state, operand, _| {
+            let jumps = match field {
+                0 => true,
+                1 => {
+                    state.next = operand.m;
+                    return;
+                }
+                2 => std::mem::take(&mut state.overflow),
+                3 => !std::mem::take(&mut state.overflow),
+                4 => state.comparison == Some(Ordering::Less),
+                5 => state.comparison == Some(Ordering::Equal),
+                6 => state.comparison == Some(Ordering::Greater),
+                7 => state.comparison >= Some(Ordering::Equal),
+                8 => matches!(state.comparison, Some(Ordering::Less | Ordering::Greater)),
+                _ => matches!(state.comparison, Some(Ordering::Less | Ordering::Equal)),
+            };
+            if jumps {
+                reference_jump(state, operand.m);
+            }
+        });
+    }
+    // JAN to JANP, J1N to J6NP and JXN to JXNP, and on binary MIX JAE, JAO, JXE and JXO
+    for register in 0..8 {
+        let fields = if register % 7 == 0 { 0..8 } else { 0..6 };
+        for field in fields {
+            check(
+                40 + register,
+                REGISTER_CASES,
+                fixed_operand(field),
+                move |state, operand, b| {
+                    let value = state.register(register).value();
+                    let jumps = match field {
+                        0 => value < 0,
+                        1 => value == 0,
+                        2 => value > 0,
+                        3 => value >= 0,
+                        4 => value != 0,
+                        5 => value <= 0,
+                        _ if b != 64 => {
+                            state.fault = true;
+                            return;
+                        }
+                        6 => value % 2 == 0,
+                        _ => value % 2 != 0,
+                    };
+                    if jumps {
+                        reference_jump(state, operand.m);
+                    }
+                },
+            );
+        }
+    }
+}
+
+#[test]
+fn num_and_char_should_match_the_reference() {
+    // NUM reads the ten bytes as decimal digits, each byte mod 10, and keeps what fits in rA
+    check(5, CASES, fixed_operand(0), |state, _, b| {
+        let mut number = 0;
+        for register in [state.a, state.x] {
+            for idx in 1..=5 {
+                number = number * 10 + register.byte(idx, b) % 10;
+            }
+        }
+        state.a.magnitude = number % b.pow(5);
+    });
+    // CHAR writes the ten decimal digits of rA as character codes 30 to 39
+    check(5, CASES, fixed_operand(1), |state, _, b| {
+        let digits = format!("{:010}", state.a.magnitude);
+        let codes: Vec<i128> = digits.bytes().map(|x| (x - b'0') as i128 + 30).collect();
+        let a_negative = state.a.negative;
+        let x_negative = state.x.negative;
+        state.a = Model::from_bytes(a_negative, codes[..5].try_into().unwrap(), b);
+        state.x = Model::from_bytes(x_negative, codes[5..].try_into().unwrap(), b);
+    });
+}
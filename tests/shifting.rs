@@ -422,3 +422,81 @@ mod src {
         );
     }
 }
+
+mod slb {
+    use super::setup_computer;
+    use mix_emu::data_types::*;
+
+    const FIELD: i32 = 6;
+    const CODE: i32 = 6;
+
+    #[test]
+    fn should_shift_bits_across_both_registers() {
+        let mut computer = setup_computer();
+
+        let instruction = Word::from_instruction_parts(Sign::PLUS, 3, 0, FIELD, CODE).unwrap();
+        computer.handle_instruction(instruction);
+
+        // each byte is six bits, so three bits moves half of each into the byte before
+        assert_eq!(
+            computer.registers.a,
+            Word::from_byte_values(Sign::PLUS, 8, 16, 24, 32, 40).unwrap()
+        );
+        assert_eq!(
+            computer.registers.x,
+            Word::from_byte_values(Sign::MINUS, 48, 57, 1, 9, 16).unwrap()
+        );
+    }
+
+    #[test]
+    fn should_clear_both_registers_after_sixty_bits() {
+        let mut computer = setup_computer();
+
+        let instruction = Word::from_instruction_parts(Sign::PLUS, 60, 0, FIELD, CODE).unwrap();
+        computer.handle_instruction(instruction);
+
+        assert_eq!(computer.registers.a, Word::ZERO);
+        assert_eq!(computer.registers.x.sign, Sign::MINUS);
+        assert_eq!(computer.registers.x.to_i32(), 0);
+    }
+}
+
+mod srb {
+    use super::setup_computer;
+    use mix_emu::data_types::*;
+
+    const FIELD: i32 = 7;
+    const CODE: i32 = 6;
+
+    #[test]
+    fn should_shift_bits_across_both_registers() {
+        let mut computer = setup_computer();
+
+        let instruction = Word::from_instruction_parts(Sign::PLUS, 7, 0, FIELD, CODE).unwrap();
+        computer.handle_instruction(instruction);
+
+        // a whole byte and one bit more
+        assert_eq!(
+            computer.registers.a,
+            Word::from_byte_values(Sign::PLUS, 0, 0, 33, 1, 34).unwrap()
+        );
+        assert_eq!(
+            computer.registers.x,
+            Word::from_byte_values(Sign::MINUS, 2, 35, 3, 36, 4).unwrap()
+        );
+    }
+
+    #[test]
+    fn should_not_exist_on_a_decimal_machine() {
+        let mut computer = mix_emu::computer::Computer::with_byte_size(ByteSize::DECIMAL);
+
+        let instruction = Word::from_instruction_parts(Sign::PLUS, 1, 0, FIELD, CODE).unwrap();
+        computer.handle_instruction(instruction);
+
+        assert!(matches!(
+            computer.fault.unwrap().kind,
+            mix_emu::computer::FaultKind::InvalidInstruction(_)
+        ));
+        assert_eq!(computer.instruction_count, 0);
+    }
+}
@@ -0,0 +1,220 @@
+use mix_emu::assembler::*;
+use mix_emu::computer::devices::*;
+use mix_emu::computer::interrupts::*;
+use mix_emu::computer::snapshot::*;
+use mix_emu::computer::*;
+use mix_emu::data_types::*;
+
+fn setup_computer() -> Computer {
+    let mut computer = Computer::new();
+    computer.current_instruction_address = 3000;
+    computer.running = true;
+    computer.overflow = true;
+    computer.comparison_indicator = Some(ComparisonIndicatorState::LESS);
+    computer.registers.a = Word::from_byte_values(Sign::MINUS, 1, 2, 3, 4, 5).unwrap();
+    computer.registers.x = Word::ZERO.with_sign(Sign::MINUS);
+    computer.registers.i3 = Index::from_i32(-321).unwrap();
+    computer.registers.j = JumpAddress::from_i32(1234).unwrap();
+    // inca 1, inca 2, hlt
+    computer
+        .memory
+        .set(
+            3000,
+            Word::from_instruction_parts(Sign::PLUS, 1, 0, 0, 48).unwrap(),
+        )
+        .unwrap();
+    computer
+        .memory
+        .set(
+            3001,
+            Word::from_instruction_parts(Sign::PLUS, 2, 0, 0, 48).unwrap(),
+        )
+        .unwrap();
+    computer
+        .memory
+        .set(
+            3002,
+            Word::from_instruction_parts(Sign::PLUS, 0, 0, 2, 5).unwrap(),
+        )
+        .unwrap();
+    computer.memory.set(3999, Word::MIN).unwrap();
+    let tape = computer.devices.tapes[0].as_mut().unwrap();
+    tape.data[7][99] = Word::from_i32(-42).unwrap();
+    tape.ready = false;
+    tape.position = 3;
+    let mut disk = DiskUnit::new(10);
+    disk.data[9][0] = Word::MAX;
+    computer.devices.disks[2] = Some(disk);
+    let mut card_reader = TextInput::card_reader(vec!["FIRST".to_string(), "  SECOND".to_string()]);
+    card_reader.position = 1;
+    computer.devices.card_reader = Some(card_reader);
+    let mut printer = TextOutput::line_printer();
+    printer.lines = vec!["  HELLO  WORLD".to_string(), String::new()];
+    computer.devices.line_printer = Some(printer);
+    computer.instruction_count = 12;
+    computer.time = 34;
+    computer
+}
+
+fn round_trip(computer: &Computer) -> Computer {
+    let mut buffer = Vec::new();
+    computer.save_snapshot(&mut buffer).unwrap();
+    Computer::restore_snapshot(buffer.as_slice()).unwrap()
+}
+
+fn assert_same_state(left: &Computer, right: &Computer) {
+    assert_eq!(
+        left.current_instruction_address,
+        right.current_instruction_address
+    );
+    assert_eq!(left.running, right.running);
+    assert_eq!(left.overflow, right.overflow);
+    assert_eq!(left.comparison_indicator, right.comparison_indicator);
+    assert_eq!(left.registers.a, right.registers.a);
+    assert_eq!(left.registers.x, right.registers.x);
+    assert_eq!(left.registers.i1, right.registers.i1);
+    assert_eq!(left.registers.i2, right.registers.i2);
+    assert_eq!(left.registers.i3, right.registers.i3);
+    assert_eq!(left.registers.i4, right.registers.i4);
+    assert_eq!(left.registers.i5, right.registers.i5);
+    assert_eq!(left.registers.i6, right.registers.i6);
+    assert_eq!(left.registers.j, right.registers.j);
+    for address in 0..4000 {
+        assert_eq!(
+            left.memory.get(address).unwrap(),
+            right.memory.get(address).unwrap()
+        );
+    }
+    assert_eq!(left.instruction_count, right.instruction_count);
+    assert_eq!(left.time, right.time);
+    for (left, right) in left.devices.tapes.iter().zip(right.devices.tapes.iter()) {
+        assert_eq!(left.is_some(), right.is_some());
+        if let (Some(left), Some(right)) = (left, right) {
+            assert_eq!(left.ready, right.ready);
+            assert_eq!(left.capacity, right.capacity);
+            assert_eq!(left.position, right.position);
+            assert!(left.data == right.data);
+        }
+    }
+    for (left, right) in left.devices.disks.iter().zip(right.devices.disks.iter()) {
+        assert_eq!(left.is_some(), right.is_some());
+        if let (Some(left), Some(right)) = (left, right) {
+            assert_eq!(left.capacity, right.capacity);
+            assert!(left.data == right.data);
+        }
+    }
+    let left_cards = left
+        .devices
+        .card_reader
+        .as_ref()
+        .map(|x| (&x.lines, x.position));
+    let right_cards = right
+        .devices
+        .card_reader
+        .as_ref()
+        .map(|x| (&x.lines, x.position));
+    assert_eq!(left_cards, right_cards);
+    let left_printed = left.devices.line_printer.as_ref().map(|x| &x.lines);
+    let right_printed = right.devices.line_printer.as_ref().map(|x| &x.lines);
+    assert_eq!(left_printed, right_printed);
+}
+
+#[test]
+fn should_restore_saved_state() {
+    let computer = setup_computer();
+    let restored = round_trip(&computer);
+    assert_same_state(&computer, &restored);
+    assert_eq!(restored.registers.x.sign, Sign::MINUS);
+}
+
+#[test]
+fn should_continue_running_from_restored_state() {
+    let mut computer = setup_computer();
+    computer.handle_next_instruction();
+    let mut restored = round_trip(&computer);
+
+    computer.handle_next_instruction();
+    computer.handle_next_instruction();
+    restored.handle_next_instruction();
+    restored.handle_next_instruction();
+
+    assert!(!restored.running);
+    assert_same_state(&computer, &restored);
+}
+
+#[test]
+fn should_continue_with_interrupts_in_flight() {
+    // the timer goes off while the tape is still writing, each handler counts its interrupts
+    let program = assemble(
+        " ORIG -20
+ JMP DONE
+ ORIG -11
+ JMP TICK
+ ORIG -10
+ CON 30
+ ORIG -1000
+DONE LDA 2000
+ INCA 1
+ STA 2000
+ INT
+TICK LDA 2001
+ INCA 1
+ STA 2001
+ INT
+ ORIG 3000
+START OUT 1000(0)
+ JBUS *(0)
+ HLT
+ END START
+",
+    )
+    .unwrap();
+    let mut computer = Computer::new();
+    computer.enable_interrupts();
+    computer.interrupts.as_mut().unwrap().io_time = 100;
+    computer.load_program(&program).unwrap();
+    computer.running = true;
+    let mut saved_in_flight = false;
+    while computer.running {
+        let mut restored = round_trip(&computer);
+        let interrupts = computer.interrupts.as_ref().unwrap();
+        let restored_interrupts = restored.interrupts.as_ref().unwrap();
+        assert_eq!(interrupts.pending(), restored_interrupts.pending());
+        assert_eq!(interrupts.io_time, restored_interrupts.io_time);
+        assert_eq!(computer.in_control_state(), restored.in_control_state());
+        saved_in_flight |= computer.in_control_state() && !interrupts.pending().is_empty();
+
+        computer.handle_next_instruction();
+        restored.handle_next_instruction();
+        assert_same_state(&computer, &restored);
+        assert_eq!(
+            computer.memory.get_in_control_state(TIMER).unwrap(),
+            restored.memory.get_in_control_state(TIMER).unwrap()
+        );
+    }
+    assert!(saved_in_flight);
+    assert_eq!(computer.memory.get(2000).unwrap().to_i32(), 1);
+    assert_eq!(computer.memory.get(2001).unwrap().to_i32(), 1);
+}
+
+#[test]
+fn should_save_and_restore_using_a_file() {
+    let computer = setup_computer();
+    let path = std::env::temp_dir().join(format!("mix-snapshot-{}.txt", std::process::id()));
+    computer.save_snapshot_to_file(&path).unwrap();
+    let restored = Computer::restore_snapshot_from_file(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_same_state(&computer, &restored);
+}
+
+#[test]
+fn should_reject_unknown_versions_and_bad_lines() {
+    let result = Computer::restore_snapshot("MIX-SNAPSHOT 99\n".as_bytes());
+    assert!(matches!(result, Err(SnapshotError::UnsupportedVersion(99))));
+
+    let result = Computer::restore_snapshot("not a snapshot\n".as_bytes());
+    assert!(matches!(result, Err(SnapshotError::BadHeader(_))));
+
+    let result = Computer::restore_snapshot("MIX-SNAPSHOT 1\nPC 1\nA + 64 0 0 0 0\n".as_bytes());
+    assert!(matches!(result, Err(SnapshotError::BadLine(3, _))));
+}
@@ -0,0 +1,118 @@
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+use mix_emu::assembler::*;
+use mix_emu::computer::devices::*;
+use mix_emu::computer::*;
+use mix_emu::object::Program;
+
+fn assemble_file(source: &str, file_name: &str) -> Program {
+    let options = AssemblerOptions {
+        file_name: Some(file_name.to_string()),
+        ..Default::default()
+    };
+    assemble_with(source, &options).unwrap()
+}
+
+// a trace destination the test can still read once the computer has it
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn should_map_addresses_to_lines_and_labels() {
+    let program = assemble_file(include_str!("programs/primes.mixal"), "primes.mixal");
+    let map = &program.source_map;
+    assert_eq!(map.files, vec!["primes.mixal"]);
+    // DIV PRIME,3 - local symbols like 6H and EQU symbols like PRIME aren't labels
+    assert_eq!(
+        map.locate(3010).unwrap().to_string(),
+        "primes.mixal:18 START+10"
+    );
+    assert_eq!(
+        map.locate(1997).unwrap().to_string(),
+        "primes.mixal:44 TITLE+2"
+    );
+    // literals don't come from a line
+    assert_eq!(map.locate(2050), None);
+    assert_eq!(map.label_before(2050), Some(("TITLE".to_string(), 55)));
+}
+
+#[test]
+fn should_keep_the_source_map_in_object_files() {
+    let program = assemble_file(
+        include_str!("programs/primes.mixal"),
+        "my programs/primes.mixal",
+    );
+    let mut object = Vec::new();
+    program.write_object(&mut object).unwrap();
+    let text = String::from_utf8(object).unwrap();
+    assert!(text.contains("FILE my programs/primes.mixal\nLINE 3000 8\n"));
+    assert!(text.contains("LABEL START 3000\n"));
+    let read = Program::read_object(text.as_bytes()).unwrap();
+    assert_eq!(read.source_map, program.source_map);
+}
+
+#[test]
+fn should_report_faults_by_source_line() {
+    let program = assemble_file(
+        " ORIG 3000
+START ENTA 1
+LOOP ENTX 2
+ CON 5(5:5),9(4:4)
+ END START
+",
+        "bad.mixal",
+    );
+    let mut computer = Computer::new();
+    computer.load_program(&program).unwrap();
+    let fault = computer.run(None).unwrap_err();
+    assert_eq!(fault.address, 3002);
+    assert_eq!(fault.source.as_ref().unwrap().line, 4);
+    assert!(fault
+        .to_string()
+        .starts_with("fault at 3002 (bad.mixal:4 LOOP+1): invalid instruction"));
+    assert_eq!(computer.describe_address(3001), "bad.mixal:3 LOOP");
+    assert_eq!(computer.describe_address(100), "100");
+}
+
+#[test]
+fn should_trace_instructions_with_their_source() {
+    let program = assemble_file(
+        " ORIG 3000
+START ENT1 2
+LOOP DEC1 1
+ J1P LOOP
+ HLT
+ END START
+",
+        "loop.mixal",
+    );
+    let mut computer = Computer::new();
+    computer.devices = Devices::new();
+    let buffer = SharedBuffer::default();
+    computer.trace = Some(Box::new(buffer.clone()));
+    computer.load_program(&program).unwrap();
+    assert_eq!(computer.run(None), Ok(StopReason::HALTED));
+    let trace = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+    assert_eq!(
+        trace.lines().collect::<Vec<_>>(),
+        vec![
+            "3000 loop.mixal:2 START: ENT1 2",
+            "3001 loop.mixal:3 LOOP: DEC1 1",
+            "3002 loop.mixal:4 LOOP+1: J1P 3001",
+            "3001 loop.mixal:3 LOOP: DEC1 1",
+            "3002 loop.mixal:4 LOOP+1: J1P 3001",
+            "3003 loop.mixal:5 LOOP+2: HLT",
+        ]
+    );
+}
@@ -194,6 +194,19 @@ mod st1 {
             Word::from_byte_values(Sign::MINUS, 1, 2, 3, 4, 7).unwrap()
         );
     }
+
+    #[test]
+    fn should_store_minus_zero() {
+        let mut computer = setup_computer();
+        computer.registers.i1 = Index::from_byte_values(Sign::MINUS, 0, 0).unwrap();
+        let instruction = Word::from_instruction_parts(Sign::PLUS, 2000, 0, 5, 25).unwrap();
+        computer.handle_instruction(instruction);
+
+        assert_eq!(
+            computer.memory.get(2000).unwrap(),
+            Word::ZERO.with_sign(Sign::MINUS)
+        );
+    }
 }
 
 mod st2 {
@@ -0,0 +1,139 @@
+use mix_emu::assembler::*;
+use mix_emu::computer::devices::image::*;
+use mix_emu::computer::devices::*;
+use mix_emu::computer::*;
+use mix_emu::data_types::*;
+
+fn sample_blocks() -> Vec<Block> {
+    let mut blocks = vec![[Word::ZERO; BLOCK_SIZE]; 3];
+    blocks[0][0] = Word::from_i32(-42).unwrap();
+    blocks[0][1] = Word::ZERO.with_sign(Sign::MINUS);
+    blocks[0][2] = Word::from_byte_values(Sign::PLUS, 8, 5, 13, 13, 16).unwrap();
+    blocks[0][99] = Word::MAX;
+    blocks[2][50] = Word::MIN;
+    blocks
+}
+
+fn round_trip(blocks: &[Block], format: ImageFormat) -> Vec<Block> {
+    let mut buffer = Vec::new();
+    write_image(blocks, format, &mut buffer).unwrap();
+    let (read, read_format) = read_image(buffer.as_slice()).unwrap();
+    let expected_format = match format {
+        ImageFormat::BINARY => ImageFormat::BINARY,
+        _ => ImageFormat::TEXT,
+    };
+    assert_eq!(read_format, expected_format);
+    read
+}
+
+#[test]
+fn should_round_trip_every_format() {
+    let blocks = sample_blocks();
+    for format in [ImageFormat::BINARY, ImageFormat::TEXT, ImageFormat::ALF] {
+        assert!(round_trip(&blocks, format) == blocks);
+    }
+}
+
+#[test]
+fn should_write_alf_rows_for_character_data() {
+    let mut buffer = Vec::new();
+    write_image(&sample_blocks(), ImageFormat::ALF, &mut buffer).unwrap();
+    let text = String::from_utf8(buffer).unwrap();
+    assert!(text.starts_with("MIX-TAPE 1\n# block 0\n-42\n-0\nALF \"HELLO\"\n"));
+    // block 1 is empty so it's just the marker
+    assert!(text.contains("# block 1\nBLOCK\n"));
+}
+
+#[test]
+fn should_read_hand_written_text_images() {
+    let text = "MIX-TAPE 1
+# two words then a new block
++ 0 0 0 1 2
+ALF \"AB\"
+BLOCK
+
+-7
+";
+    let (blocks, _) = read_image(text.as_bytes()).unwrap();
+    assert_eq!(blocks.len(), 2);
+    assert_eq!(blocks[0][0].to_i32(), 66);
+    assert_eq!(
+        blocks[0][1],
+        Word::from_byte_values(Sign::PLUS, 1, 2, 0, 0, 0).unwrap()
+    );
+    assert_eq!(blocks[0][2], Word::ZERO);
+    assert_eq!(blocks[1][0].to_i32(), -7);
+}
+
+#[test]
+fn should_start_a_new_block_every_hundred_words() {
+    let mut text = "MIX-TAPE 1\n".to_string();
+    for value in 0..150 {
+        text.push_str(&format!("{}\n", value));
+    }
+    let (blocks, _) = read_image(text.as_bytes()).unwrap();
+    assert_eq!(blocks.len(), 2);
+    assert_eq!(blocks[1][0].to_i32(), 100);
+    assert_eq!(blocks[1][49].to_i32(), 149);
+    assert_eq!(blocks[1][50], Word::ZERO);
+}
+
+#[test]
+fn should_reject_bad_images() {
+    let result = read_image("MIX-TAPE 1\n12\nnot a word\n".as_bytes());
+    assert!(matches!(result, Err(ImageError::BadLine(3, _))));
+
+    let result = read_image("MIX-TAPE 2\n".as_bytes());
+    assert!(matches!(result, Err(ImageError::UnsupportedVersion(2))));
+
+    let result = read_image("something else\n".as_bytes());
+    assert!(matches!(result, Err(ImageError::BadHeader(_))));
+
+    let mut buffer = Vec::new();
+    write_image(&sample_blocks(), ImageFormat::BINARY, &mut buffer).unwrap();
+    buffer.truncate(buffer.len() - 1);
+    let result = read_image(buffer.as_slice());
+    assert!(matches!(result, Err(ImageError::Truncated)));
+
+    let mut buffer = Vec::new();
+    write_image(&sample_blocks(), ImageFormat::BINARY, &mut buffer).unwrap();
+    // the second byte of the first word, bytes only go up to 99 even on a decimal machine
+    buffer[13] = 100;
+    let result = read_image(buffer.as_slice());
+    assert!(matches!(result, Err(ImageError::BadWord(0))));
+}
+
+#[test]
+fn should_run_programs_against_loaded_tapes() {
+    let path = std::env::temp_dir().join(format!("mix-tape-{}.tape", std::process::id()));
+    write_image_to_file(&sample_blocks(), ImageFormat::TEXT, &path).unwrap();
+    let (tape, format) = TapeUnit::load_image(&path).unwrap();
+    assert_eq!(format, ImageFormat::TEXT);
+    assert_eq!(tape.capacity, 3);
+
+    let program = assemble(
+        " ORIG 3000
+START IN 1000(4)
+ LDA 1002
+ IOC 1(4)
+ OUT 1000(4)
+ HLT
+ END START
+",
+    )
+    .unwrap();
+    let mut computer = Computer::new();
+    computer.devices.tapes[4] = Some(tape);
+    computer.load_program(&program).unwrap();
+    assert_eq!(computer.run(None), Ok(StopReason::HALTED));
+    assert_eq!(computer.registers.a.to_byte_string(), "+ 8 5 13 13 16");
+
+    let tape = computer.devices.tapes[4].as_ref().unwrap();
+    tape.save_image(&path, ImageFormat::BINARY).unwrap();
+    let (saved, format) = read_image_from_file(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(format, ImageFormat::BINARY);
+    // block 0 was copied over block 2
+    assert!(saved[2] == saved[0]);
+    assert!(saved[0] == sample_blocks()[0]);
+}
@@ -0,0 +1,132 @@
+use mix_emu::data_types::*;
+use mix_emu::testing::*;
+
+// adds up the numbers on the cards, one to a card, until a card with 0 on it
+const SUM: &str = "CARDS EQU 16
+PRINTER EQU 18
+ ORIG 3000
+START IN CARD(CARDS)
+ JBUS *(CARDS)
+ LDA CARD
+ LDX CARD+1
+ NUM
+ JAZ DONE
+ ADD TOTAL
+ STA TOTAL
+ JMP START
+DONE LDA TOTAL
+ CHAR
+ STX LINE+1
+ OUT LINE(PRINTER)
+ HLT
+TOTAL CON 0
+CARD ORIG *+16
+LINE ALF \"SUM  \"
+ ORIG LINE+24
+ END START
+";
+
+fn differences(test: ProgramTest) -> String {
+    match test.check() {
+        Ok(_) => panic!("expected differences"),
+        Err(e) => e,
+    }
+}
+
+#[test]
+fn should_check_a_whole_program() {
+    let computer = ProgramTest::new(SUM)
+        .with_cards(&["0000000003", "0000000039", "0000000000"])
+        // the jump out of the loop
+        .expect_register(Register::J, 3006)
+        .expect_memory(3014, 42)
+        .expect_printer(&["SUM  00042"])
+        .expect_overflow(false)
+        .run();
+    assert_eq!(computer.instruction_count, 29);
+}
+
+#[test]
+fn should_run_programs_from_the_book() {
+    let computer = ProgramTest::new(include_str!("programs/primes.mixal"))
+        .expect_memory(0, 2)
+        .expect_memory(499, 3571)
+        .expect_register(Register::I1, 0)
+        .run();
+    let printer = computer.devices.line_printer.unwrap();
+    // after skipping to a new page
+    assert_eq!(printer.lines[1], "FIRST FIVE HUNDRED PRIMES");
+    // and the same on a decimal machine
+    ProgramTest::new(include_str!("programs/primes.mixal"))
+        .with_byte_size(ByteSize::DECIMAL)
+        .expect_memory(499, 3571)
+        .run();
+}
+
+#[test]
+fn should_list_every_difference() {
+    let error = differences(
+        ProgramTest::new(SUM)
+            .with_cards(&["0000000003", "0000000000"])
+            .expect_register(Register::I1, 1)
+            .expect_memory(3014, 4)
+            .expect_word(3014, Word::from_i32(3).unwrap().with_sign(Sign::MINUS))
+            .expect_printer(&["SUM  00004", "DONE"]),
+    );
+    assert_eq!(
+        error,
+        "rI1: expected 1, got 0
+<input>:18 TOTAL: expected 4, got 3 (+ 0 0 0 0 3)
+<input>:18 TOTAL: expected -3 (- 0 0 0 0 3), got 3 (+ 0 0 0 0 3)
+printer output differs, - expected, + produced:
+- SUM  00004
++ SUM  00003
+- DONE
+"
+    );
+}
+
+#[test]
+fn should_say_why_a_program_didnt_finish() {
+    let error = differences(ProgramTest::new(SUM));
+    assert!(
+        error.starts_with("the program stopped with a fault at 3000 (<input>:4 START): unit 16"),
+        "{}",
+        error
+    );
+    let error =
+        differences(ProgramTest::new(" ORIG 3000\nSTART JMP *\n END START\n").with_limit(10));
+    assert_eq!(
+        error,
+        "the program didn't halt within 10 instructions, it was at <input>:2 START"
+    );
+    let error = differences(ProgramTest::new(" LDA 1,7\n"));
+    assert!(error.starts_with("the program didn't assemble: line 1:"));
+    let error = differences(ProgramTest::new(" HLT\n").with_tape(8, &[]));
+    assert_eq!(error, "tape unit 8 doesn't exist");
+}
+
+#[test]
+fn should_read_tapes() {
+    let words: Vec<Word> = (1..=100).map(|x| Word::from_i32(x).unwrap()).collect();
+    ProgramTest::new(
+        " ORIG 3000
+START IN 1000(3)
+ LDA 1099
+ HLT
+ END START
+",
+    )
+    .with_tape(3, &words)
+    .expect_register(Register::A, 100)
+    .expect_memory(1000, 1)
+    .run();
+}
+
+#[test]
+#[should_panic(expected = "rA: expected 1, got 0")]
+fn should_panic_with_the_differences() {
+    ProgramTest::new(" HLT\n")
+        .expect_register(Register::A, 1)
+        .run();
+}